@@ -1,4 +1,5 @@
-use crate::{AppState, error::Result, plugin_manager::PluginInfo};
+use crate::{AppState, error::{AppError, Result}, plugin_manager::PluginInfo};
+use network_proxy::{ProxyConfig, ProxyState};
 use serde::{Deserialize, Serialize};
 use tauri::{State, AppHandle};
 use std::sync::Arc;
@@ -66,3 +67,25 @@ pub async fn restart_plugin(
     plugin_manager.restart_plugin(&plugin_id)?;
     Ok(format!("Plugin {} restarted successfully", plugin_id))
 }
+
+#[tauri::command]
+pub async fn get_proxy_settings(state: State<'_, ProxyState>) -> Result<ProxyConfig> {
+    Ok(state.config.read().await.clone())
+}
+
+/// 更新所有网络工具共用的出站代理设置，新建立的连接立即生效
+#[tauri::command]
+pub async fn set_proxy_settings(
+    app: AppHandle,
+    config: ProxyConfig,
+    state: State<'_, ProxyState>,
+) -> Result<()> {
+    if config.enabled {
+        config.validate().map_err(|message| AppError::Config { message })?;
+    }
+    network_proxy::save_config(&app, &config)
+        .await
+        .map_err(|message| AppError::Config { message })?;
+    *state.config.write().await = config;
+    Ok(())
+}
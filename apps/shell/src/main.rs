@@ -71,10 +71,49 @@ fn main() {
         builder = builder.plugin(tauri_plugin_regex_tool::init());
     }
 
+    #[cfg(feature = "tool-mqtt-tool")]
+    {
+        builder = builder.plugin(tauri_plugin_mqtt_tool::init());
+    }
+
+    #[cfg(feature = "tool-http-tool")]
+    {
+        builder = builder.plugin(tauri_plugin_http_tool::init());
+    }
+
+    #[cfg(feature = "tool-mock-server")]
+    {
+        builder = builder.plugin(tauri_plugin_mock_server::init());
+    }
+
+    #[cfg(feature = "tool-port-scanner")]
+    {
+        builder = builder.plugin(tauri_plugin_port_scanner::init());
+    }
+
+    #[cfg(feature = "tool-netstat")]
+    {
+        builder = builder.plugin(tauri_plugin_netstat::init());
+    }
+
+    #[cfg(feature = "tool-ip-tool")]
+    {
+        builder = builder.plugin(tauri_plugin_ip_tool::init());
+    }
+
+    #[cfg(feature = "tool-dns-tool")]
+    {
+        builder = builder.plugin(tauri_plugin_dns_tool::init());
+    }
+
     builder.setup(|app| {
             let app_state = AppState::default();
             app.manage(app_state);
 
+            // 网络工具共用的代理设置
+            let proxy_config = network_proxy::load_config(app.handle());
+            app.manage(network_proxy::ProxyState::new(proxy_config));
+
             // 初始化插件管理器
             let mut plugin_manager = PluginManager::new();
 
@@ -114,6 +153,41 @@ fn main() {
                 error!("Failed to load regex-tool plugin: {}", e);
             }
 
+            #[cfg(feature = "tool-mqtt-tool")]
+            if let Err(e) = plugin_manager.load_plugin("mqtt-tool") {
+                error!("Failed to load mqtt-tool plugin: {}", e);
+            }
+
+            #[cfg(feature = "tool-http-tool")]
+            if let Err(e) = plugin_manager.load_plugin("http-tool") {
+                error!("Failed to load http-tool plugin: {}", e);
+            }
+
+            #[cfg(feature = "tool-mock-server")]
+            if let Err(e) = plugin_manager.load_plugin("mock-server") {
+                error!("Failed to load mock-server plugin: {}", e);
+            }
+
+            #[cfg(feature = "tool-port-scanner")]
+            if let Err(e) = plugin_manager.load_plugin("port-scanner") {
+                error!("Failed to load port-scanner plugin: {}", e);
+            }
+
+            #[cfg(feature = "tool-netstat")]
+            if let Err(e) = plugin_manager.load_plugin("netstat") {
+                error!("Failed to load netstat plugin: {}", e);
+            }
+
+            #[cfg(feature = "tool-ip-tool")]
+            if let Err(e) = plugin_manager.load_plugin("ip-tool") {
+                error!("Failed to load ip-tool plugin: {}", e);
+            }
+
+            #[cfg(feature = "tool-dns-tool")]
+            if let Err(e) = plugin_manager.load_plugin("dns-tool") {
+                error!("Failed to load dns-tool plugin: {}", e);
+            }
+
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
@@ -122,7 +196,9 @@ fn main() {
             load_plugin,
             unload_plugin,
             get_plugin_status,
-            restart_plugin
+            restart_plugin,
+            get_proxy_settings,
+            set_proxy_settings
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
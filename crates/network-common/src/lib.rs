@@ -0,0 +1,6 @@
+//! 网络工具共用的辅助函数
+//!
+//! [`payload`] 负责用户输入与字节之间的转换，[`tls`] 提供证书读取和跳过证书校验的校验器。
+
+pub mod payload;
+pub mod tls;
@@ -0,0 +1,51 @@
+/// 按编码将用户输入转换为待发送的字节，支持 "text"（默认）和 "hex"
+pub fn decode(message: &str, encoding: Option<&str>) -> Result<Vec<u8>, String> {
+    match encoding.unwrap_or("text") {
+        "text" => Ok(message.as_bytes().to_vec()),
+        "hex" => decode_hex(message),
+        other => Err(format!("Unsupported encoding: {}", other)),
+    }
+}
+
+/// 解析十六进制字符串，允许空白分隔和 `0x` 前缀，如 "01 0A ff" 或 "0x010aff"
+pub fn decode_hex(input: &str) -> Result<Vec<u8>, String> {
+    let digits: String = input
+        .split_whitespace()
+        .map(|part| part.trim_start_matches("0x").trim_start_matches("0X"))
+        .collect();
+    if !digits.is_ascii() {
+        return Err("Hex string contains non-hex characters".to_string());
+    }
+    if !digits.len().is_multiple_of(2) {
+        return Err("Hex string must have an even number of digits".to_string());
+    }
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&digits[i..i + 2], 16)
+                .map_err(|_| format!("Invalid hex byte: {}", &digits[i..i + 2]))
+        })
+        .collect()
+}
+
+/// 解析十六进制匹配模式，`??` 表示任意字节
+pub fn decode_hex_pattern(pattern: &str) -> Result<Vec<Option<u8>>, String> {
+    let digits: String = pattern.split_whitespace().collect();
+    if !digits.is_ascii() || !digits.len().is_multiple_of(2) {
+        return Err(format!("Invalid hex pattern: {}", pattern));
+    }
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| match &digits[i..i + 2] {
+            "??" => Ok(None),
+            byte => u8::from_str_radix(byte, 16)
+                .map(Some)
+                .map_err(|_| format!("Invalid hex byte in pattern: {}", byte)),
+        })
+        .collect()
+}
+
+/// 以空格分隔的小写十六进制显示字节
+pub fn to_hex(data: &[u8]) -> String {
+    data.iter().map(|b| format!("{:02x}", b)).collect::<Vec<_>>().join(" ")
+}
@@ -0,0 +1,254 @@
+//! 网络工具共用的出站代理设置
+//!
+//! 代理在 shell 中统一配置并保存到 `network-proxy.json`，TCP、WebSocket 和 HTTP 客户端
+//! 通过 [`for_host`] 获取某次连接应使用的代理。
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager, Runtime};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::sync::RwLock;
+use tokio_socks::tcp::Socks5Stream;
+use tracing::warn;
+
+/// HTTP CONNECT 响应头的读取上限
+const MAX_CONNECT_RESPONSE: usize = 8 * 1024;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProxyKind {
+    #[default]
+    Socks5,
+    Http,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProxyConfig {
+    /// 连接未单独指定时是否默认经过代理
+    pub enabled: bool,
+    pub kind: ProxyKind,
+    pub host: String,
+    pub port: u16,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// 不经过代理的主机：精确匹配，".example.com" 或 "*.example.com" 匹配其子域名，"*" 匹配全部
+    pub bypass: Vec<String>,
+}
+
+impl Default for ProxyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            kind: ProxyKind::Socks5,
+            host: "127.0.0.1".to_string(),
+            port: 1080,
+            username: None,
+            password: None,
+            bypass: vec!["localhost".to_string(), "127.0.0.1".to_string(), "::1".to_string()],
+        }
+    }
+}
+
+/// 按 URL userinfo 的规则编码用户名和密码
+fn encode_userinfo(input: &str) -> String {
+    input
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (byte as char).to_string(),
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+/// 拼接 "host:port"，IPv6 地址加上方括号
+fn authority(host: &str, port: u16) -> String {
+    if host.contains(':') {
+        format!("[{}]:{}", host, port)
+    } else {
+        format!("{}:{}", host, port)
+    }
+}
+
+impl ProxyConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.host.trim().is_empty() {
+            return Err("Proxy host is empty".to_string());
+        }
+        if self.port == 0 {
+            return Err("Proxy port must not be 0".to_string());
+        }
+        Ok(())
+    }
+
+    /// 代理服务器地址，用于日志和错误信息
+    pub fn address(&self) -> String {
+        authority(&self.host, self.port)
+    }
+
+    /// 目标主机是否在直连列表中
+    pub fn bypasses(&self, host: &str) -> bool {
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+        self.bypass.iter().map(|entry| entry.trim()).any(|entry| {
+            if entry == "*" {
+                return true;
+            }
+            match entry.strip_prefix("*.").or_else(|| entry.strip_prefix('.')) {
+                Some(domain) => {
+                    host.eq_ignore_ascii_case(domain)
+                        || host.len() > domain.len()
+                            && host.to_ascii_lowercase().ends_with(&format!(".{}", domain.to_ascii_lowercase()))
+                }
+                None => host.eq_ignore_ascii_case(entry),
+            }
+        })
+    }
+
+    /// 供 HTTP 客户端使用的代理 URL，SOCKS5 使用 socks5h 让代理解析主机名
+    pub fn url(&self) -> String {
+        let scheme = match self.kind {
+            ProxyKind::Socks5 => "socks5h",
+            ProxyKind::Http => "http",
+        };
+        match &self.username {
+            Some(username) => format!(
+                "{}://{}:{}@{}",
+                scheme,
+                encode_userinfo(username),
+                encode_userinfo(self.password.as_deref().unwrap_or_default()),
+                self.address()
+            ),
+            None => format!("{}://{}", scheme, self.address()),
+        }
+    }
+
+    /// 连接代理服务器并建立到目标的隧道
+    pub async fn connect(&self, host: &str, port: u16) -> Result<TcpStream, String> {
+        self.validate()?;
+        let stream = TcpStream::connect((self.host.as_str(), self.port))
+            .await
+            .map_err(|e| format!("Failed to connect to proxy {}: {}", self.address(), e))?;
+        self.handshake(stream, host, port).await
+    }
+
+    /// 在已连接到代理服务器的流上建立到目标的隧道，成功后返回的流直接与目标通信
+    pub async fn handshake(&self, stream: TcpStream, host: &str, port: u16) -> Result<TcpStream, String> {
+        match self.kind {
+            ProxyKind::Socks5 => self.socks5_handshake(stream, host, port).await,
+            ProxyKind::Http => self.http_connect(stream, host, port).await,
+        }
+    }
+
+    async fn socks5_handshake(&self, stream: TcpStream, host: &str, port: u16) -> Result<TcpStream, String> {
+        let result = match &self.username {
+            Some(username) => {
+                let password = self.password.as_deref().unwrap_or_default();
+                Socks5Stream::connect_with_password_and_socket(stream, (host, port), username, password).await
+            }
+            None => Socks5Stream::connect_with_socket(stream, (host, port)).await,
+        };
+        result
+            .map(|stream| stream.into_inner())
+            .map_err(|e| format!("SOCKS5 proxy {} failed: {}", self.address(), e))
+    }
+
+    async fn http_connect(&self, mut stream: TcpStream, host: &str, port: u16) -> Result<TcpStream, String> {
+        let target = authority(host, port);
+        let mut request = format!("CONNECT {0} HTTP/1.1\r\nHost: {0}\r\n", target);
+        if let Some(username) = &self.username {
+            let credentials = format!("{}:{}", username, self.password.as_deref().unwrap_or_default());
+            let encoded = base64::engine::general_purpose::STANDARD.encode(credentials);
+            request.push_str(&format!("Proxy-Authorization: Basic {}\r\n", encoded));
+        }
+        request.push_str("\r\n");
+        stream
+            .write_all(request.as_bytes())
+            .await
+            .map_err(|e| format!("HTTP proxy {} write failed: {}", self.address(), e))?;
+
+        // 逐字节读取响应头，避免把隧道中目标已发送的数据读走
+        let mut response = Vec::new();
+        while !response.ends_with(b"\r\n\r\n") {
+            if response.len() >= MAX_CONNECT_RESPONSE {
+                return Err(format!("HTTP proxy {} sent an oversized response", self.address()));
+            }
+            let byte = stream
+                .read_u8()
+                .await
+                .map_err(|e| format!("HTTP proxy {} closed the connection: {}", self.address(), e))?;
+            response.push(byte);
+        }
+
+        let response = String::from_utf8_lossy(&response);
+        let status_line = response.lines().next().unwrap_or_default();
+        let status = status_line
+            .split_whitespace()
+            .nth(1)
+            .and_then(|code| code.parse::<u16>().ok())
+            .ok_or_else(|| format!("HTTP proxy {} sent an invalid response: {}", self.address(), status_line))?;
+        if !(200..300).contains(&status) {
+            return Err(format!("HTTP proxy {} refused CONNECT to {}: {}", self.address(), target, status_line));
+        }
+        Ok(stream)
+    }
+}
+
+/// shell 管理的代理状态
+pub struct ProxyState {
+    pub config: RwLock<ProxyConfig>,
+}
+
+impl ProxyState {
+    pub fn new(config: ProxyConfig) -> Self {
+        Self { config: RwLock::new(config) }
+    }
+}
+
+/// 返回到目标主机的连接应使用的代理。
+///
+/// `use_proxy` 为单个连接的设置：`Some(false)` 直连，`Some(true)` 强制使用代理，
+/// `None` 时按全局开关和直连列表决定
+pub async fn for_host<R: Runtime>(app: &AppHandle<R>, host: &str, use_proxy: Option<bool>) -> Option<ProxyConfig> {
+    let state = app.try_state::<ProxyState>()?;
+    let config = state.config.read().await;
+    let selected = match use_proxy {
+        Some(use_proxy) => use_proxy,
+        None => config.enabled && !config.bypasses(host),
+    };
+    selected.then(|| config.clone())
+}
+
+fn config_path<R: Runtime>(app: &AppHandle<R>) -> Option<PathBuf> {
+    app.path().app_config_dir().ok().map(|dir| dir.join("network-proxy.json"))
+}
+
+/// 读取保存的代理设置，文件不存在或无法解析时使用默认设置
+pub fn load_config<R: Runtime>(app: &AppHandle<R>) -> ProxyConfig {
+    let Some(path) = config_path(app) else {
+        return ProxyConfig::default();
+    };
+    match std::fs::read_to_string(&path) {
+        Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+            warn!("Invalid proxy config {}: {}", path.display(), e);
+            ProxyConfig::default()
+        }),
+        Err(_) => ProxyConfig::default(),
+    }
+}
+
+pub async fn save_config<R: Runtime>(app: &AppHandle<R>, config: &ProxyConfig) -> Result<(), String> {
+    let path = config_path(app).ok_or_else(|| "Config directory is unavailable".to_string())?;
+    let contents =
+        serde_json::to_string_pretty(config).map_err(|e| format!("Failed to serialize proxy config: {}", e))?;
+
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir)
+            .await
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    tokio::fs::write(&path, contents)
+        .await
+        .map_err(|e| format!("Failed to save proxy config to {}: {}", path.display(), e))
+}
@@ -0,0 +1,163 @@
+mod query;
+
+use serde::{Deserialize, Serialize};
+use tauri::{plugin::TauriPlugin, Runtime, Manager, AppHandle};
+use tracing::{info, warn};
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+pub use query::{DnsQuery, DnsRecord, DnsResponse};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DnsToolConfig {
+    pub enabled: bool,
+    /// 查询未指定服务器时使用，为空时使用系统 DNS
+    #[serde(default)]
+    pub default_server: Option<String>,
+    /// "udp" 或 "tcp"
+    pub default_protocol: String,
+    pub timeout_ms: u64,
+}
+
+impl Default for DnsToolConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            default_server: None,
+            default_protocol: "udp".to_string(),
+            timeout_ms: 5000,
+        }
+    }
+}
+
+pub struct DnsToolState {
+    config: RwLock<DnsToolConfig>,
+}
+
+impl DnsToolState {
+    pub fn new(config: DnsToolConfig) -> Self {
+        Self { config: RwLock::new(config) }
+    }
+}
+
+#[tauri::command]
+async fn get_config<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<DnsToolConfig, String> {
+    let state = app.state::<DnsToolState>();
+    let config = state.config.read().await.clone();
+    Ok(config)
+}
+
+#[tauri::command]
+async fn update_config<R: Runtime>(
+    app: AppHandle<R>,
+    config: DnsToolConfig,
+) -> Result<(), String> {
+    if let Some(server) = &config.default_server {
+        query::parse_server(server)?;
+    }
+    let state = app.state::<DnsToolState>();
+    info!("dns-tool config updated: {:?}", config);
+    *state.config.write().await = config;
+    save_config(&app).await
+}
+
+fn config_path<R: Runtime>(app: &AppHandle<R>) -> Option<PathBuf> {
+    app.path().app_config_dir().ok().map(|dir| dir.join("dns-tool.json"))
+}
+
+/// 读取保存的配置，文件不存在或无法解析时使用默认配置
+fn load_config<R: Runtime>(app: &AppHandle<R>) -> DnsToolConfig {
+    let Some(path) = config_path(app) else {
+        return DnsToolConfig::default();
+    };
+    match std::fs::read_to_string(&path) {
+        Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+            warn!("Invalid dns-tool config {}: {}", path.display(), e);
+            DnsToolConfig::default()
+        }),
+        Err(_) => DnsToolConfig::default(),
+    }
+}
+
+async fn save_config<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    let path = config_path(app).ok_or_else(|| "Config directory is unavailable".to_string())?;
+    let state = app.state::<DnsToolState>();
+    let contents = serde_json::to_string_pretty(&*state.config.read().await)
+        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir)
+            .await
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    tokio::fs::write(&path, contents)
+        .await
+        .map_err(|e| format!("Failed to save config to {}: {}", path.display(), e))
+}
+
+/// 发送 DNS 查询。使用系统 DNS 时依次尝试各个服务器，直到有服务器应答
+#[tauri::command]
+async fn dns_lookup<R: Runtime>(
+    app: AppHandle<R>,
+    query: DnsQuery,
+) -> Result<DnsResponse, String> {
+    let state = app.state::<DnsToolState>();
+    let config = state.config.read().await.clone();
+
+    let servers = match query.server.filter(|s| !s.trim().is_empty()).or(config.default_server) {
+        Some(server) => vec![query::parse_server(&server)?],
+        None => query::system_servers()?,
+    };
+    let protocol = query.protocol.unwrap_or(config.default_protocol).to_lowercase();
+    let tcp = match protocol.as_str() {
+        "udp" => false,
+        "tcp" => true,
+        other => return Err(format!("Unsupported protocol: {}", other)),
+    };
+    let timeout = Duration::from_millis(query.timeout_ms.unwrap_or(config.timeout_ms));
+
+    let mut last_error = "No DNS servers configured".to_string();
+    for server in servers {
+        info!("DNS {} {} via {} ({})", query.record_type, query.name, server, protocol);
+        match query::lookup(&query.name, &query.record_type, server, tcp, timeout).await {
+            Ok(response) => return Ok(response),
+            Err(e) => {
+                warn!("DNS query via {} failed: {}", server, e);
+                last_error = e;
+            }
+        }
+    }
+    Err(last_error)
+}
+
+/// 列出系统配置的 DNS 服务器
+#[tauri::command]
+async fn dns_system_servers<R: Runtime>(
+    _app: AppHandle<R>,
+) -> Result<Vec<String>, String> {
+    let servers = query::system_servers()?;
+    Ok(servers.iter().map(|server| server.to_string()).collect())
+}
+
+pub fn init<R: Runtime>() -> TauriPlugin<R> {
+    tauri::plugin::Builder::new("dns-tool")
+        .invoke_handler(tauri::generate_handler![
+            get_config,
+            update_config,
+            dns_lookup,
+            dns_system_servers
+        ])
+        .setup(|app, _api| {
+            // 初始化插件状态，优先使用保存的配置
+            let config = load_config(app);
+            let state = DnsToolState::new(config);
+            app.manage(state);
+
+            info!("dns-tool plugin initialized");
+            Ok(())
+        })
+        .build()
+}
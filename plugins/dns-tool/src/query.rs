@@ -0,0 +1,394 @@
+use hickory_proto::op::{Edns, Message, MessageType, OpCode, Query};
+use hickory_proto::rr::{Name, Record, RecordType};
+use network_common::payload::to_hex;
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpStream, UdpSocket};
+
+fn default_record_type() -> String {
+    "A".to_string()
+}
+
+/// 一次 DNS 查询
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DnsQuery {
+    /// 域名；查询 PTR 时也可以直接填写 IP 地址
+    pub name: String,
+    /// 记录类型，如 "A"、"AAAA"、"MX"、"TXT"、"SRV"、"SOA"
+    #[serde(default = "default_record_type")]
+    pub record_type: String,
+    /// DNS 服务器，如 "8.8.8.8"、"127.0.0.1:5353" 或 "[::1]:53"，为空时使用配置中的服务器或系统 DNS
+    #[serde(default)]
+    pub server: Option<String>,
+    /// "udp" 或 "tcp"，为空时使用配置中的默认协议
+    #[serde(default)]
+    pub protocol: Option<String>,
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DnsRecord {
+    pub name: String,
+    pub record_type: String,
+    pub class: String,
+    pub ttl: u32,
+    /// 记录内容，使用区域文件中的表示形式
+    pub data: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DnsResponse {
+    pub id: u16,
+    pub server: String,
+    /// 实际使用的协议，UDP 响应被截断后会改用 TCP
+    pub protocol: String,
+    pub name: String,
+    pub record_type: String,
+    /// 响应码，如 "No Error"、"Non-Existent Domain"
+    pub response_code: String,
+    pub authoritative: bool,
+    pub truncated: bool,
+    pub recursion_available: bool,
+    pub answers: Vec<DnsRecord>,
+    pub authority: Vec<DnsRecord>,
+    pub additional: Vec<DnsRecord>,
+    pub response_time_ms: f64,
+    pub size: usize,
+    /// 原始响应报文的十六进制
+    pub raw: String,
+}
+
+/// 解析服务器地址，未指定端口时使用 53
+pub fn parse_server(input: &str) -> Result<SocketAddr, String> {
+    let input = input.trim();
+    input
+        .parse::<SocketAddr>()
+        .or_else(|_| {
+            input
+                .trim_start_matches('[')
+                .trim_end_matches(']')
+                .parse::<IpAddr>()
+                .map(|ip| SocketAddr::new(ip, 53))
+        })
+        .map_err(|_| format!("Invalid DNS server: {}", input))
+}
+
+/// 读取系统配置的 DNS 服务器
+pub fn system_servers() -> Result<Vec<SocketAddr>, String> {
+    let (config, _) = hickory_resolver::system_conf::read_system_conf()
+        .map_err(|e| format!("Failed to read system DNS configuration: {}", e))?;
+    // 系统配置中同一服务器会分别列出 UDP 和 TCP
+    let mut servers: Vec<SocketAddr> = Vec::new();
+    for server in config.name_servers() {
+        if !servers.contains(&server.socket_addr) {
+            servers.push(server.socket_addr);
+        }
+    }
+    Ok(servers)
+}
+
+fn build_query(name: &str, record_type: RecordType) -> Result<(u16, Vec<u8>), String> {
+    let name = match (record_type, name.trim().parse::<IpAddr>()) {
+        (RecordType::PTR, Ok(ip)) => Name::from(ip),
+        _ => {
+            let mut name = Name::from_utf8(name.trim()).map_err(|e| format!("Invalid domain name {}: {}", name, e))?;
+            name.set_fqdn(true);
+            name
+        }
+    };
+
+    let id = rand::random();
+    let mut edns = Edns::new();
+    edns.set_max_payload(4096);
+    let mut message = Message::new();
+    message
+        .set_id(id)
+        .set_message_type(MessageType::Query)
+        .set_op_code(OpCode::Query)
+        .set_recursion_desired(true)
+        .add_query(Query::query(name, record_type))
+        .set_edns(edns);
+    let query = message.to_vec().map_err(|e| format!("Failed to encode DNS query: {}", e))?;
+    Ok((id, query))
+}
+
+async fn exchange_udp(server: SocketAddr, query: &[u8], id: u16) -> Result<Vec<u8>, String> {
+    let bind = match server {
+        SocketAddr::V4(_) => SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0),
+        SocketAddr::V6(_) => SocketAddr::new(IpAddr::V6(Ipv6Addr::UNSPECIFIED), 0),
+    };
+    let socket = UdpSocket::bind(bind).await.map_err(|e| format!("Failed to bind UDP socket: {}", e))?;
+    socket
+        .connect(server)
+        .await
+        .map_err(|e| format!("Failed to reach {}: {}", server, e))?;
+    socket
+        .send(query)
+        .await
+        .map_err(|e| format!("Failed to send query to {}: {}", server, e))?;
+
+    let mut buffer = vec![0u8; 65535];
+    loop {
+        let n = socket
+            .recv(&mut buffer)
+            .await
+            .map_err(|e| format!("Failed to receive from {}: {}", server, e))?;
+        // 忽略 ID 不匹配的迟到响应
+        if n >= 2 && u16::from_be_bytes([buffer[0], buffer[1]]) == id {
+            buffer.truncate(n);
+            return Ok(buffer);
+        }
+    }
+}
+
+/// TCP 上的 DNS 报文带 2 字节长度前缀
+async fn exchange_tcp(server: SocketAddr, query: &[u8], id: u16) -> Result<Vec<u8>, String> {
+    let mut stream = TcpStream::connect(server)
+        .await
+        .map_err(|e| format!("Failed to connect to {}: {}", server, e))?;
+    let mut framed = (query.len() as u16).to_be_bytes().to_vec();
+    framed.extend_from_slice(query);
+    stream
+        .write_all(&framed)
+        .await
+        .map_err(|e| format!("Failed to send query to {}: {}", server, e))?;
+
+    let length = stream
+        .read_u16()
+        .await
+        .map_err(|e| format!("Failed to receive from {}: {}", server, e))?;
+    let mut buffer = vec![0u8; length as usize];
+    stream
+        .read_exact(&mut buffer)
+        .await
+        .map_err(|e| format!("Failed to receive from {}: {}", server, e))?;
+    if buffer.len() < 2 || u16::from_be_bytes([buffer[0], buffer[1]]) != id {
+        return Err(format!("Response from {} does not match the query ID", server));
+    }
+    Ok(buffer)
+}
+
+async fn exchange(server: SocketAddr, query: &[u8], id: u16, tcp: bool, timeout: Duration) -> Result<Vec<u8>, String> {
+    let exchange = async {
+        match tcp {
+            true => exchange_tcp(server, query, id).await,
+            false => exchange_udp(server, query, id).await,
+        }
+    };
+    tokio::time::timeout(timeout, exchange)
+        .await
+        .map_err(|_| format!("DNS query to {} timed out after {}ms", server, timeout.as_millis()))?
+}
+
+fn convert(records: &[Record]) -> Vec<DnsRecord> {
+    records
+        .iter()
+        .map(|record| DnsRecord {
+            name: record.name().to_string(),
+            record_type: record.record_type().to_string(),
+            class: record.dns_class().to_string(),
+            ttl: record.ttl(),
+            data: record.data().map(|data| data.to_string()).unwrap_or_default(),
+        })
+        .collect()
+}
+
+/// 向指定服务器发送查询，UDP 响应被截断时自动改用 TCP 重试
+pub async fn lookup(
+    name: &str,
+    record_type: &str,
+    server: SocketAddr,
+    tcp: bool,
+    timeout: Duration,
+) -> Result<DnsResponse, String> {
+    let record_type = RecordType::from_str(&record_type.trim().to_uppercase())
+        .map_err(|_| format!("Unsupported record type: {}", record_type))?;
+    let (id, query) = build_query(name, record_type)?;
+
+    let started = Instant::now();
+    let mut tcp = tcp;
+    let mut raw = exchange(server, &query, id, tcp, timeout).await?;
+    let mut message = Message::from_vec(&raw).map_err(|e| format!("Invalid DNS response: {}", e))?;
+    if !tcp && message.truncated() {
+        tcp = true;
+        raw = exchange(server, &query, id, tcp, timeout).await?;
+        message = Message::from_vec(&raw).map_err(|e| format!("Invalid DNS response: {}", e))?;
+    }
+    let elapsed = started.elapsed();
+
+    Ok(DnsResponse {
+        id: message.id(),
+        server: server.to_string(),
+        protocol: if tcp { "tcp" } else { "udp" }.to_string(),
+        name: message
+            .queries()
+            .first()
+            .map(|query| query.name().to_string())
+            .unwrap_or_else(|| name.to_string()),
+        record_type: record_type.to_string(),
+        response_code: message.response_code().to_string(),
+        authoritative: message.authoritative(),
+        truncated: message.truncated(),
+        recursion_available: message.recursion_available(),
+        answers: convert(message.answers()),
+        authority: convert(message.name_servers()),
+        additional: convert(message.additionals()),
+        response_time_ms: elapsed.as_secs_f64() * 1000.0,
+        size: raw.len(),
+        raw: to_hex(&raw),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hickory_proto::rr::rdata::{A, MX, SOA};
+    use hickory_proto::rr::RData;
+
+    /// 本地 DNS 桩服务器的应答方式
+    #[derive(Clone, Copy)]
+    enum Mode {
+        /// UDP 和 TCP 都返回完整应答
+        Answer,
+        /// UDP 只返回设置了 TC 位的空应答，TCP 返回完整应答
+        TruncateUdp,
+        /// 收到查询后不应答
+        Silent,
+        /// TCP 应答使用错误的报文 ID
+        WrongTcpId,
+    }
+
+    fn records(query: &Query) -> Vec<Record> {
+        let name = query.name().clone();
+        let rdata = match query.query_type() {
+            RecordType::A => RData::A(A::new(192, 0, 2, 1)),
+            RecordType::MX => RData::MX(MX::new(10, Name::from_ascii("mail.example.com.").unwrap())),
+            RecordType::SOA => RData::SOA(SOA::new(
+                Name::from_ascii("ns1.example.com.").unwrap(),
+                Name::from_ascii("admin.example.com.").unwrap(),
+                2024010101,
+                3600,
+                600,
+                86400,
+                300,
+            )),
+            _ => return Vec::new(),
+        };
+        vec![Record::from_rdata(name, 300, rdata)]
+    }
+
+    fn respond(request: &[u8], answer: bool, truncated: bool) -> Vec<u8> {
+        let request = Message::from_vec(request).unwrap();
+        let mut response = Message::new();
+        response
+            .set_id(request.id())
+            .set_message_type(MessageType::Response)
+            .set_op_code(OpCode::Query)
+            .set_recursion_desired(true)
+            .set_recursion_available(true)
+            .set_truncated(truncated)
+            .add_queries(request.queries().to_vec());
+        if answer {
+            for query in request.queries() {
+                response.add_answers(records(query));
+            }
+        }
+        response.to_vec().unwrap()
+    }
+
+    /// 在 127.0.0.1 的同一端口上启动 UDP 和 TCP 桩服务器
+    async fn start(mode: Mode) -> SocketAddr {
+        let (udp, tcp) = loop {
+            let udp = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+            if let Ok(tcp) = tokio::net::TcpListener::bind(udp.local_addr().unwrap()).await {
+                break (udp, tcp);
+            }
+        };
+        let addr = udp.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let mut buffer = vec![0u8; 65535];
+            while let Ok((n, peer)) = udp.recv_from(&mut buffer).await {
+                let response = match mode {
+                    Mode::Silent => continue,
+                    Mode::TruncateUdp => respond(&buffer[..n], false, true),
+                    Mode::Answer | Mode::WrongTcpId => respond(&buffer[..n], true, false),
+                };
+                udp.send_to(&response, peer).await.unwrap();
+            }
+        });
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = tcp.accept().await {
+                let length = stream.read_u16().await.unwrap();
+                let mut request = vec![0u8; length as usize];
+                stream.read_exact(&mut request).await.unwrap();
+                if matches!(mode, Mode::Silent) {
+                    continue;
+                }
+                let mut response = respond(&request, true, false);
+                if matches!(mode, Mode::WrongTcpId) {
+                    response[0] ^= 0xff;
+                }
+                let mut framed = (response.len() as u16).to_be_bytes().to_vec();
+                framed.extend_from_slice(&response);
+                stream.write_all(&framed).await.unwrap();
+            }
+        });
+        addr
+    }
+
+    const TIMEOUT: Duration = Duration::from_secs(2);
+
+    #[tokio::test]
+    async fn parses_a_mx_and_soa_records() {
+        let server = start(Mode::Answer).await;
+
+        let response = lookup("example.com", "A", server, false, TIMEOUT).await.unwrap();
+        assert_eq!(response.protocol, "udp");
+        assert_eq!(response.name, "example.com.");
+        assert_eq!(response.response_code, "No Error");
+        assert_eq!(response.answers.len(), 1);
+        assert_eq!(response.answers[0].record_type, "A");
+        assert_eq!(response.answers[0].data, "192.0.2.1");
+        assert_eq!(response.answers[0].ttl, 300);
+
+        let response = lookup("example.com", "mx", server, false, TIMEOUT).await.unwrap();
+        assert_eq!(response.answers[0].record_type, "MX");
+        assert_eq!(response.answers[0].data, "10 mail.example.com.");
+
+        let response = lookup("example.com", "SOA", server, true, TIMEOUT).await.unwrap();
+        assert_eq!(response.protocol, "tcp");
+        assert_eq!(
+            response.answers[0].data,
+            "ns1.example.com. admin.example.com. 2024010101 3600 600 86400 300"
+        );
+        assert_eq!(response.answers[0].ttl, 300);
+    }
+
+    #[tokio::test]
+    async fn retries_truncated_udp_response_over_tcp() {
+        let server = start(Mode::TruncateUdp).await;
+        let response = lookup("example.com", "A", server, false, TIMEOUT).await.unwrap();
+        assert_eq!(response.protocol, "tcp");
+        assert!(!response.truncated);
+        assert_eq!(response.answers[0].data, "192.0.2.1");
+    }
+
+    #[tokio::test]
+    async fn times_out_when_server_does_not_answer() {
+        let server = start(Mode::Silent).await;
+        let error = lookup("example.com", "A", server, false, Duration::from_millis(200)).await.unwrap_err();
+        assert!(error.contains("timed out"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn rejects_tcp_response_with_wrong_id() {
+        let server = start(Mode::WrongTcpId).await;
+        let error = lookup("example.com", "A", server, true, TIMEOUT).await.unwrap_err();
+        assert!(error.contains("does not match"), "{}", error);
+    }
+}
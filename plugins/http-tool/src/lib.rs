@@ -0,0 +1,221 @@
+mod request;
+
+use serde::{Deserialize, Serialize};
+use tauri::{plugin::TauriPlugin, Runtime, Manager, AppHandle};
+use tracing::{info, warn};
+use std::path::PathBuf;
+use tokio::sync::RwLock;
+
+pub use request::{Auth, HttpRequest, HttpResponse, KeyValue, MultipartPart, ProxySettings, RequestBody, Timing};
+
+/// 保存的请求集合
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Collection {
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub requests: Vec<HttpRequest>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpToolConfig {
+    pub enabled: bool,
+    /// 请求未指定超时时使用（毫秒）
+    pub default_timeout_ms: u64,
+    /// 读取的响应体上限（字节），超出部分被丢弃
+    pub max_body_size: usize,
+    #[serde(default)]
+    pub collections: Vec<Collection>,
+}
+
+impl Default for HttpToolConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            default_timeout_ms: 30000,
+            max_body_size: 10 * 1024 * 1024,
+            collections: Vec::new(),
+        }
+    }
+}
+
+pub struct HttpToolState {
+    config: RwLock<HttpToolConfig>,
+}
+
+impl HttpToolState {
+    pub fn new(config: HttpToolConfig) -> Self {
+        Self { config: RwLock::new(config) }
+    }
+}
+
+#[tauri::command]
+async fn get_config<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<HttpToolConfig, String> {
+    let state = app.state::<HttpToolState>();
+    let config = state.config.read().await.clone();
+    Ok(config)
+}
+
+#[tauri::command]
+async fn update_config<R: Runtime>(
+    app: AppHandle<R>,
+    config: HttpToolConfig,
+) -> Result<(), String> {
+    let state = app.state::<HttpToolState>();
+    info!(
+        "http-tool config updated: timeout {}ms, max body {} bytes, {} collections",
+        config.default_timeout_ms,
+        config.max_body_size,
+        config.collections.len()
+    );
+    *state.config.write().await = config;
+    save_config(&app).await
+}
+
+fn config_path<R: Runtime>(app: &AppHandle<R>) -> Option<PathBuf> {
+    app.path().app_config_dir().ok().map(|dir| dir.join("http-tool.json"))
+}
+
+/// 读取保存的配置，文件不存在或无法解析时使用默认配置
+fn load_config<R: Runtime>(app: &AppHandle<R>) -> HttpToolConfig {
+    let Some(path) = config_path(app) else {
+        return HttpToolConfig::default();
+    };
+    match std::fs::read_to_string(&path) {
+        Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+            warn!("Invalid http-tool config {}: {}", path.display(), e);
+            HttpToolConfig::default()
+        }),
+        Err(_) => HttpToolConfig::default(),
+    }
+}
+
+async fn save_config<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    let path = config_path(app).ok_or_else(|| "Config directory is unavailable".to_string())?;
+    let state = app.state::<HttpToolState>();
+    let contents = serde_json::to_string_pretty(&*state.config.read().await)
+        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir)
+            .await
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    tokio::fs::write(&path, contents)
+        .await
+        .map_err(|e| format!("Failed to save config to {}: {}", path.display(), e))
+}
+
+#[tauri::command]
+async fn http_get_collections<R: Runtime>(app: AppHandle<R>) -> Result<Vec<Collection>, String> {
+    let state = app.state::<HttpToolState>();
+    let collections = state.config.read().await.collections.clone();
+    Ok(collections)
+}
+
+/// 新增或更新集合（按 id），id 为空时自动生成
+#[tauri::command]
+async fn http_save_collection<R: Runtime>(app: AppHandle<R>, mut collection: Collection) -> Result<Collection, String> {
+    if collection.id.is_empty() {
+        collection.id = format!("collection_{}", uuid::Uuid::new_v4().simple());
+    }
+
+    let state = app.state::<HttpToolState>();
+    {
+        let mut config = state.config.write().await;
+        match config.collections.iter_mut().find(|c| c.id == collection.id) {
+            Some(existing) => *existing = collection.clone(),
+            None => config.collections.push(collection.clone()),
+        }
+    }
+    save_config(&app).await?;
+
+    info!("HTTP collection {} saved", collection.id);
+    Ok(collection)
+}
+
+#[tauri::command]
+async fn http_remove_collection<R: Runtime>(app: AppHandle<R>, collection_id: String) -> Result<(), String> {
+    let state = app.state::<HttpToolState>();
+    state.config.write().await.collections.retain(|c| c.id != collection_id);
+    save_config(&app).await?;
+
+    info!("HTTP collection {} removed", collection_id);
+    Ok(())
+}
+
+/// 在集合中新增或更新请求（按 id），id 为空时自动生成
+#[tauri::command]
+async fn http_save_request<R: Runtime>(
+    app: AppHandle<R>,
+    collection_id: String,
+    mut request: HttpRequest,
+) -> Result<HttpRequest, String> {
+    if request.id.is_empty() {
+        request.id = format!("request_{}", uuid::Uuid::new_v4().simple());
+    }
+
+    let state = app.state::<HttpToolState>();
+    {
+        let mut config = state.config.write().await;
+        let collection = config
+            .collections
+            .iter_mut()
+            .find(|c| c.id == collection_id)
+            .ok_or_else(|| "Collection not found".to_string())?;
+        match collection.requests.iter_mut().find(|r| r.id == request.id) {
+            Some(existing) => *existing = request.clone(),
+            None => collection.requests.push(request.clone()),
+        }
+    }
+    save_config(&app).await?;
+
+    info!("HTTP request {} saved to {}", request.id, collection_id);
+    Ok(request)
+}
+
+#[tauri::command]
+async fn http_remove_request<R: Runtime>(
+    app: AppHandle<R>,
+    collection_id: String,
+    request_id: String,
+) -> Result<(), String> {
+    let state = app.state::<HttpToolState>();
+    {
+        let mut config = state.config.write().await;
+        let collection = config
+            .collections
+            .iter_mut()
+            .find(|c| c.id == collection_id)
+            .ok_or_else(|| "Collection not found".to_string())?;
+        collection.requests.retain(|r| r.id != request_id);
+    }
+    save_config(&app).await
+}
+
+pub fn init<R: Runtime>() -> TauriPlugin<R> {
+    tauri::plugin::Builder::new("http-tool")
+        .invoke_handler(tauri::generate_handler![
+            get_config,
+            update_config,
+            request::http_send,
+            http_get_collections,
+            http_save_collection,
+            http_remove_collection,
+            http_save_request,
+            http_remove_request
+        ])
+        .setup(|app, _api| {
+            // 初始化插件状态，优先使用保存的配置
+            let config = load_config(app);
+            let state = HttpToolState::new(config);
+            app.manage(state);
+
+            info!("http-tool plugin initialized");
+            Ok(())
+        })
+        .build()
+}
@@ -0,0 +1,414 @@
+use network_proxy::ProxyConfig;
+use reqwest::multipart::{Form, Part};
+use reqwest::redirect::Policy;
+use reqwest::{Client, ClientBuilder, Method, Proxy, RequestBuilder};
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, Runtime};
+use tracing::info;
+
+use crate::HttpToolState;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyValue {
+    pub key: String,
+    pub value: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn enabled(pairs: &[KeyValue]) -> impl Iterator<Item = (&str, &str)> {
+    pairs.iter().filter(|p| p.enabled).map(|p| (p.key.as_str(), p.value.as_str()))
+}
+
+/// multipart 表单的一项，`file_path` 不为空时上传文件，否则使用 `value`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MultipartPart {
+    pub name: String,
+    #[serde(default)]
+    pub value: Option<String>,
+    #[serde(default)]
+    pub file_path: Option<String>,
+    #[serde(default)]
+    pub content_type: Option<String>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RequestBody {
+    #[default]
+    None,
+    Raw {
+        content: String,
+        #[serde(default)]
+        content_type: Option<String>,
+    },
+    Json {
+        content: String,
+    },
+    Form {
+        fields: Vec<KeyValue>,
+    },
+    Multipart {
+        parts: Vec<MultipartPart>,
+    },
+    File {
+        path: String,
+        #[serde(default)]
+        content_type: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Auth {
+    #[default]
+    None,
+    Basic {
+        username: String,
+        #[serde(default)]
+        password: Option<String>,
+    },
+    Bearer {
+        token: String,
+    },
+    /// 以请求头或查询参数携带的 API Key
+    ApiKey {
+        key: String,
+        value: String,
+        #[serde(default)]
+        in_query: bool,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProxySettings {
+    /// 如 "http://127.0.0.1:8080" 或 "socks5://127.0.0.1:1080"
+    pub url: String,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+}
+
+/// 一个请求的完整定义，也是集合中保存的内容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpRequest {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub name: String,
+    pub method: String,
+    pub url: String,
+    #[serde(default)]
+    pub headers: Vec<KeyValue>,
+    #[serde(default)]
+    pub query: Vec<KeyValue>,
+    #[serde(default)]
+    pub body: RequestBody,
+    #[serde(default)]
+    pub auth: Auth,
+    /// 为空时使用配置中的默认超时
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+    #[serde(default = "default_true")]
+    pub follow_redirects: bool,
+    #[serde(default = "default_max_redirects")]
+    pub max_redirects: usize,
+    /// 单独为请求指定的代理，优先于 shell 中配置的代理
+    #[serde(default)]
+    pub proxy: Option<ProxySettings>,
+    /// 是否经过 shell 中配置的代理，未设置时按代理的全局开关和直连列表决定
+    #[serde(default)]
+    pub use_proxy: Option<bool>,
+    /// 跳过服务端证书校验，仅用于测试
+    #[serde(default)]
+    pub insecure: bool,
+}
+
+fn default_max_redirects() -> usize {
+    10
+}
+
+/// 各阶段耗时（毫秒）；`first_byte_ms` 包含建立连接、TLS 握手和服务端处理的时间
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Timing {
+    /// 经代理或直接使用 IP 地址时为空
+    pub dns_ms: Option<f64>,
+    pub first_byte_ms: f64,
+    pub download_ms: f64,
+    pub total_ms: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub status_text: String,
+    pub http_version: String,
+    pub headers: Vec<(String, String)>,
+    pub content_type: Option<String>,
+    /// 按 UTF-8 解码的响应体
+    pub body: String,
+    /// JSON 响应经格式化后的内容
+    pub pretty_body: Option<String>,
+    /// 响应体不是有效的 UTF-8 时的十六进制内容
+    pub body_hex: Option<String>,
+    pub size: usize,
+    /// 响应体超过配置的上限时被截断
+    pub truncated: bool,
+    pub final_url: String,
+    /// 依次经过的重定向地址
+    pub redirects: Vec<String>,
+    pub timing: Timing,
+}
+
+fn ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+async fn build_body(builder: RequestBuilder, body: &RequestBody) -> Result<RequestBuilder, String> {
+    Ok(match body {
+        RequestBody::None => builder,
+        RequestBody::Raw { content, content_type } => {
+            let builder = builder.body(content.clone());
+            match content_type {
+                Some(content_type) => builder.header(reqwest::header::CONTENT_TYPE, content_type),
+                None => builder,
+            }
+        }
+        RequestBody::Json { content } => {
+            serde_json::from_str::<serde_json::Value>(content).map_err(|e| format!("Invalid JSON body: {}", e))?;
+            builder
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .body(content.clone())
+        }
+        RequestBody::Form { fields } => builder.form(&enabled(fields).collect::<Vec<_>>()),
+        RequestBody::Multipart { parts } => {
+            let mut form = Form::new();
+            for part in parts.iter().filter(|p| p.enabled) {
+                let mut field = match &part.file_path {
+                    Some(path) => {
+                        let data = tokio::fs::read(path)
+                            .await
+                            .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+                        let file_name = std::path::Path::new(path)
+                            .file_name()
+                            .map(|name| name.to_string_lossy().into_owned())
+                            .unwrap_or_else(|| part.name.clone());
+                        Part::bytes(data).file_name(file_name)
+                    }
+                    None => Part::text(part.value.clone().unwrap_or_default()),
+                };
+                if let Some(content_type) = &part.content_type {
+                    field = field
+                        .mime_str(content_type)
+                        .map_err(|e| format!("Invalid content type {}: {}", content_type, e))?;
+                }
+                form = form.part(part.name.clone(), field);
+            }
+            builder.multipart(form)
+        }
+        RequestBody::File { path, content_type } => {
+            let data = tokio::fs::read(path)
+                .await
+                .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+            let builder = builder.body(data);
+            match content_type {
+                Some(content_type) => builder.header(reqwest::header::CONTENT_TYPE, content_type),
+                None => builder,
+            }
+        }
+    })
+}
+
+fn apply_auth(builder: RequestBuilder, auth: &Auth) -> RequestBuilder {
+    match auth {
+        Auth::None => builder,
+        Auth::Basic { username, password } => builder.basic_auth(username, password.as_ref()),
+        Auth::Bearer { token } => builder.bearer_auth(token),
+        Auth::ApiKey { key, value, in_query: true } => builder.query(&[(key, value)]),
+        Auth::ApiKey { key, value, in_query: false } => builder.header(key, value),
+    }
+}
+
+/// 解析主机名并计时，解析出的全部地址固定给本次请求使用，避免重复解析，
+/// 同时保留连接时依次尝试各个地址的行为
+async fn resolve(url: &reqwest::Url) -> Result<Option<(String, Vec<SocketAddr>, Duration)>, String> {
+    let Some(domain) = url.domain() else {
+        return Ok(None);
+    };
+    let port = url
+        .port_or_known_default()
+        .ok_or_else(|| format!("Unsupported URL scheme: {}", url.scheme()))?;
+    let started = Instant::now();
+    let addrs: Vec<SocketAddr> = tokio::net::lookup_host((domain, port))
+        .await
+        .map_err(|e| format!("DNS lookup for {} failed: {}", domain, e))?
+        .collect();
+    if addrs.is_empty() {
+        return Err(format!("DNS lookup for {} returned no addresses", domain));
+    }
+    Ok(Some((domain.to_string(), addrs, started.elapsed())))
+}
+
+fn build_client(
+    request: &HttpRequest,
+    shared_proxy: Option<&ProxyConfig>,
+    timeout: Duration,
+    redirects: Arc<Mutex<Vec<String>>>,
+) -> Result<ClientBuilder, String> {
+    let max_redirects = request.max_redirects;
+    let policy = if request.follow_redirects {
+        Policy::custom(move |attempt| {
+            if attempt.previous().len() > max_redirects {
+                return attempt.error(format!("Too many redirects (more than {})", max_redirects));
+            }
+            if let Ok(mut redirects) = redirects.lock() {
+                redirects.push(attempt.url().to_string());
+            }
+            attempt.follow()
+        })
+    } else {
+        Policy::none()
+    };
+
+    let mut builder = Client::builder()
+        .timeout(timeout)
+        .redirect(policy)
+        .danger_accept_invalid_certs(request.insecure);
+    if let Some(settings) = &request.proxy {
+        let mut proxy = Proxy::all(&settings.url).map_err(|e| format!("Invalid proxy {}: {}", settings.url, e))?;
+        if let Some(username) = &settings.username {
+            proxy = proxy.basic_auth(username, settings.password.as_deref().unwrap_or_default());
+        }
+        builder = builder.proxy(proxy);
+    } else if let Some(shared) = shared_proxy {
+        shared.validate()?;
+        let proxy = Proxy::all(shared.url()).map_err(|e| format!("Invalid proxy {}: {}", shared.address(), e))?;
+        builder = builder.proxy(proxy);
+    } else {
+        builder = builder.no_proxy();
+    }
+    Ok(builder)
+}
+
+fn is_json(content_type: Option<&str>, body: &str) -> bool {
+    match content_type {
+        Some(content_type) if content_type.contains("json") => true,
+        _ => body.trim_start().starts_with(['{', '[']),
+    }
+}
+
+/// 发送请求，返回状态、响应头、分阶段耗时以及（JSON 时格式化后的）响应体
+#[tauri::command]
+pub(crate) async fn http_send<R: Runtime>(
+    app: AppHandle<R>,
+    request: HttpRequest,
+) -> Result<HttpResponse, String> {
+    let state = app.state::<HttpToolState>();
+    let (default_timeout, max_body_size) = {
+        let config = state.config.read().await;
+        (config.default_timeout_ms, config.max_body_size)
+    };
+
+    let method = Method::from_bytes(request.method.to_uppercase().as_bytes())
+        .map_err(|_| format!("Invalid HTTP method: {}", request.method))?;
+    let url = reqwest::Url::parse(&request.url).map_err(|e| format!("Invalid URL {}: {}", request.url, e))?;
+    let timeout = Duration::from_millis(request.timeout_ms.unwrap_or(default_timeout));
+    let redirects = Arc::new(Mutex::new(Vec::new()));
+
+    let shared_proxy = match &request.proxy {
+        Some(_) => None,
+        None => network_proxy::for_host(&app, url.host_str().unwrap_or_default(), request.use_proxy).await,
+    };
+    let mut client = build_client(&request, shared_proxy.as_ref(), timeout, redirects.clone())?;
+    // 经代理时由代理解析主机名
+    let dns = match (&request.proxy, &shared_proxy) {
+        (None, None) => resolve(&url).await?,
+        _ => None,
+    };
+    if let Some((domain, addrs, _)) = &dns {
+        client = client.resolve_to_addrs(domain, addrs);
+    }
+    let client = client.build().map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let mut builder = client.request(method, url).query(&enabled(&request.query).collect::<Vec<_>>());
+    for (key, value) in enabled(&request.headers) {
+        builder = builder.header(key, value);
+    }
+    builder = apply_auth(builder, &request.auth);
+    builder = build_body(builder, &request.body).await?;
+
+    info!("HTTP {} {}", request.method, request.url);
+    let started = Instant::now();
+    let mut response = builder.send().await.map_err(|e| format!("Request failed: {}", e))?;
+    let first_byte = started.elapsed();
+
+    let status = response.status();
+    let http_version = format!("{:?}", response.version());
+    let final_url = response.url().to_string();
+    let headers: Vec<(String, String)> = response
+        .headers()
+        .iter()
+        .map(|(name, value)| (name.to_string(), String::from_utf8_lossy(value.as_bytes()).into_owned()))
+        .collect();
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+
+    let mut data = Vec::new();
+    let mut truncated = false;
+    while let Some(chunk) = response.chunk().await.map_err(|e| format!("Failed to read response body: {}", e))? {
+        if data.len() + chunk.len() > max_body_size {
+            data.extend_from_slice(&chunk[..max_body_size - data.len()]);
+            truncated = true;
+            break;
+        }
+        data.extend_from_slice(&chunk);
+    }
+    let download = started.elapsed() - first_byte;
+
+    let (body, body_hex) = match std::str::from_utf8(&data) {
+        Ok(text) => (text.to_string(), None),
+        Err(_) => (
+            String::from_utf8_lossy(&data).into_owned(),
+            Some(data.iter().map(|b| format!("{:02x}", b)).collect::<Vec<_>>().join(" ")),
+        ),
+    };
+    let pretty_body = (body_hex.is_none() && !truncated && is_json(content_type.as_deref(), &body))
+        .then(|| tauri_plugin_json_formatter::format_json(&body, 2).ok())
+        .flatten();
+
+    let dns_time = dns.map(|(_, _, elapsed)| elapsed);
+    let redirects = redirects.lock().map(|r| r.clone()).unwrap_or_default();
+    Ok(HttpResponse {
+        status: status.as_u16(),
+        status_text: status.canonical_reason().unwrap_or_default().to_string(),
+        http_version,
+        headers,
+        content_type,
+        body,
+        pretty_body,
+        body_hex,
+        size: data.len(),
+        truncated,
+        final_url,
+        redirects,
+        timing: Timing {
+            dns_ms: dns_time.map(ms),
+            first_byte_ms: ms(first_byte),
+            download_ms: ms(download),
+            total_ms: ms(first_byte + download + dns_time.unwrap_or_default()),
+        },
+    })
+}
@@ -0,0 +1,380 @@
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+/// 一个网段，IPv4 和 IPv6 统一用 u128 表示，`bits` 为地址位数（32 或 128）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Network {
+    bits: u32,
+    addr: u128,
+    prefix: u32,
+}
+
+/// 网段的详细信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkInfo {
+    pub version: u8,
+    /// 规范化后的网段，如 "192.168.1.0/24"
+    pub cidr: String,
+    /// 输入的地址
+    pub address: String,
+    pub prefix: u32,
+    pub netmask: String,
+    pub wildcard: String,
+    pub network: String,
+    /// 仅 IPv4 有广播地址
+    pub broadcast: Option<String>,
+    pub first_host: String,
+    pub last_host: String,
+    /// 地址总数，IPv6 可能超过 64 位整数，使用字符串表示
+    pub total_addresses: String,
+    pub usable_hosts: String,
+    /// 地址类型，如 "private"、"public"、"loopback"
+    pub scope: String,
+}
+
+/// 同一地址的各种表示形式
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddressForms {
+    pub version: u8,
+    /// 点分十进制（IPv4）或压缩形式（IPv6）
+    pub standard: String,
+    /// IPv6 的完整形式，IPv4 与 `standard` 相同
+    pub expanded: String,
+    pub integer: String,
+    pub hex: String,
+    /// 按字节（IPv4）或按 16 位分组（IPv6）的二进制
+    pub binary: String,
+    /// 反向解析域名，如 "1.1.168.192.in-addr.arpa"
+    pub reverse_pointer: String,
+    pub scope: String,
+}
+
+fn full_mask(bits: u32) -> u128 {
+    if bits == 128 {
+        u128::MAX
+    } else {
+        (1u128 << bits) - 1
+    }
+}
+
+fn prefix_mask(bits: u32, prefix: u32) -> u128 {
+    if prefix == 0 {
+        0
+    } else {
+        (u128::MAX << (128 - prefix)) >> (128 - bits)
+    }
+}
+
+fn to_ip(bits: u32, value: u128) -> IpAddr {
+    if bits == 32 {
+        IpAddr::V4(Ipv4Addr::from(value as u32))
+    } else {
+        IpAddr::V6(Ipv6Addr::from(value))
+    }
+}
+
+fn from_ip(ip: IpAddr) -> (u32, u128) {
+    match ip {
+        IpAddr::V4(ip) => (32, u32::from(ip) as u128),
+        IpAddr::V6(ip) => (128, u128::from(ip)),
+    }
+}
+
+/// 解析地址，支持标准写法、十进制整数和 "0x" 开头的十六进制。
+/// 十进制整数不超过 32 位、十六进制不超过 8 位数字时视为 IPv4
+pub fn parse_address(input: &str) -> Result<(u32, u128), String> {
+    let input = input.trim().trim_start_matches('[').trim_end_matches(']');
+    if let Ok(ip) = input.parse::<IpAddr>() {
+        return Ok(from_ip(ip));
+    }
+    let invalid = || format!("Invalid IP address: {}", input);
+    match input.strip_prefix("0x").or_else(|| input.strip_prefix("0X")) {
+        Some(hex) => {
+            let digits = hex.replace([':', '_'], "");
+            let value = u128::from_str_radix(&digits, 16).map_err(|_| invalid())?;
+            Ok((if digits.len() <= 8 { 32 } else { 128 }, value))
+        }
+        None => {
+            let value = input.parse::<u128>().map_err(|_| invalid())?;
+            Ok((if value <= u32::MAX as u128 { 32 } else { 128 }, value))
+        }
+    }
+}
+
+fn scope(bits: u32, value: u128) -> &'static str {
+    match to_ip(bits, value) {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            if ip.is_unspecified() {
+                "unspecified"
+            } else if ip.is_loopback() {
+                "loopback"
+            } else if ip.is_private() {
+                "private"
+            } else if ip.is_link_local() {
+                "link-local"
+            } else if a == 100 && (64..128).contains(&b) {
+                "shared"
+            } else if ip.is_multicast() {
+                "multicast"
+            } else if ip.is_broadcast() {
+                "broadcast"
+            } else if ip.is_documentation() {
+                "documentation"
+            } else if a >= 240 {
+                "reserved"
+            } else {
+                "public"
+            }
+        }
+        IpAddr::V6(ip) => {
+            let first = ip.segments()[0];
+            if ip.is_unspecified() {
+                "unspecified"
+            } else if ip.is_loopback() {
+                "loopback"
+            } else if ip.to_ipv4_mapped().is_some() {
+                "ipv4-mapped"
+            } else if first & 0xfe00 == 0xfc00 {
+                "unique-local"
+            } else if first & 0xffc0 == 0xfe80 {
+                "link-local"
+            } else if ip.is_multicast() {
+                "multicast"
+            } else if first == 0x2001 && ip.segments()[1] == 0x0db8 {
+                "documentation"
+            } else {
+                "public"
+            }
+        }
+    }
+}
+
+impl Network {
+    /// 解析 "192.168.1.10/24"、"192.168.1.0/255.255.255.0" 或 "2001:db8::/32"，不带前缀时视为单个地址
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let input = input.trim();
+        let (addr, prefix) = match input.split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix.trim())),
+            None => (input, None),
+        };
+        let (bits, addr) = parse_address(addr)?;
+        let prefix = match prefix {
+            None => bits,
+            Some(prefix) => match prefix.parse::<u32>() {
+                Ok(prefix) if prefix <= bits => prefix,
+                Ok(_) => return Err(format!("Prefix length {} is out of range", prefix)),
+                Err(_) => {
+                    // 点分形式的子网掩码，必须是连续的 1
+                    let (mask_bits, mask) = parse_address(prefix)?;
+                    let ones = mask.count_ones();
+                    if mask_bits != bits || mask != prefix_mask(bits, ones) {
+                        return Err(format!("Invalid netmask: {}", prefix));
+                    }
+                    ones
+                }
+            },
+        };
+        Ok(Self { bits, addr, prefix })
+    }
+
+    fn network(&self) -> u128 {
+        self.addr & prefix_mask(self.bits, self.prefix)
+    }
+
+    fn last(&self) -> u128 {
+        self.network() | (full_mask(self.bits) & !prefix_mask(self.bits, self.prefix))
+    }
+
+    /// 规范化为网络地址加前缀
+    pub fn normalized(&self) -> Self {
+        Self { addr: self.network(), ..*self }
+    }
+
+    /// 判断地址或网段是否完全落在本网段内
+    pub fn contains(&self, other: &Network) -> bool {
+        self.bits == other.bits
+            && other.prefix >= self.prefix
+            && other.network() & prefix_mask(self.bits, self.prefix) == self.network()
+    }
+
+    pub fn info(&self) -> NetworkInfo {
+        let (bits, prefix) = (self.bits, self.prefix);
+        let host_bits = bits - prefix;
+        let total = match host_bits {
+            128 => "340282366920938463463374607431768211456".to_string(),
+            n => (1u128 << n).to_string(),
+        };
+        let (network, last) = (self.network(), self.last());
+
+        // IPv4 的 /31 和 /32 没有网络地址和广播地址之分
+        let (first_host, last_host, usable_hosts, broadcast) = if bits == 32 {
+            let broadcast = Some(to_ip(bits, last).to_string());
+            if host_bits >= 2 {
+                (network + 1, last - 1, ((1u128 << host_bits) - 2).to_string(), broadcast)
+            } else {
+                (network, last, total.clone(), None)
+            }
+        } else {
+            (network, last, total.clone(), None)
+        };
+
+        NetworkInfo {
+            version: if bits == 32 { 4 } else { 6 },
+            cidr: format!("{}/{}", to_ip(bits, network), prefix),
+            address: to_ip(bits, self.addr).to_string(),
+            prefix,
+            netmask: to_ip(bits, prefix_mask(bits, prefix)).to_string(),
+            wildcard: to_ip(bits, full_mask(bits) & !prefix_mask(bits, prefix)).to_string(),
+            network: to_ip(bits, network).to_string(),
+            broadcast,
+            first_host: to_ip(bits, first_host).to_string(),
+            last_host: to_ip(bits, last_host).to_string(),
+            total_addresses: total,
+            usable_hosts,
+            scope: scope(bits, self.addr).to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for Network {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", to_ip(self.bits, self.addr), self.prefix)
+    }
+}
+
+/// 把网段划分为前缀为 `new_prefix` 的子网，最多返回 `limit` 个
+pub fn split(network: &Network, new_prefix: u32, limit: usize) -> Result<Vec<Network>, String> {
+    if new_prefix < network.prefix || new_prefix > network.bits {
+        return Err(format!(
+            "New prefix must be between /{} and /{}",
+            network.prefix, network.bits
+        ));
+    }
+    let count_bits = new_prefix - network.prefix;
+    if count_bits >= 64 || (1u64 << count_bits) > limit as u64 {
+        return Err(format!("Splitting into /{} would produce more than {} subnets", new_prefix, limit));
+    }
+    let start = network.network();
+    if count_bits == 0 {
+        // 前缀不变时只有网段本身，`::/0` 的地址位移会溢出，单独处理
+        return Ok(vec![network.normalized()]);
+    }
+    Ok((0..1u128 << count_bits)
+        .map(|i| Network {
+            bits: network.bits,
+            addr: start + (i << (network.bits - new_prefix)),
+            prefix: new_prefix,
+        })
+        .collect())
+}
+
+/// 按需要的子网数计算划分后的前缀长度
+pub fn prefix_for_count(network: &Network, count: u32) -> Result<u32, String> {
+    if count == 0 {
+        return Err("Subnet count must be at least 1".to_string());
+    }
+    // 超过 2^31 时没有 u32 的 2 的幂，需要 32 位
+    let extra = count.checked_next_power_of_two().map_or(32, u32::trailing_zeros);
+    let prefix = network.prefix + extra;
+    if prefix > network.bits {
+        return Err(format!("{} cannot be split into {} subnets", network.normalized(), count));
+    }
+    Ok(prefix)
+}
+
+/// 起始地址为 `start`、大小为 2^`block` 的地址块的最后一个地址
+fn block_last(start: u128, block: u32) -> u128 {
+    if block == 128 {
+        u128::MAX
+    } else {
+        start + ((1u128 << block) - 1)
+    }
+}
+
+/// 用最少的网段覆盖 [start, end] 地址范围
+fn range_networks(bits: u32, mut start: u128, end: u128) -> Vec<Network> {
+    let mut networks = Vec::new();
+    loop {
+        // 从起始地址对齐允许的最大块开始，逐步缩小到不超过范围末尾
+        let mut block = if start == 0 { bits } else { start.trailing_zeros().min(bits) };
+        while block > 0 && block_last(start, block) > end {
+            block -= 1;
+        }
+        networks.push(Network { bits, addr: start, prefix: bits - block });
+
+        let last = block_last(start, block);
+        if last >= end {
+            break;
+        }
+        start = last + 1;
+    }
+    networks
+}
+
+/// 地址范围转换为 CIDR 网段列表
+pub fn range_to_networks(start: &str, end: &str) -> Result<Vec<Network>, String> {
+    let (start_bits, start) = parse_address(start)?;
+    let (end_bits, end) = parse_address(end)?;
+    if start_bits != end_bits {
+        return Err("Range start and end must be the same IP version".to_string());
+    }
+    if start > end {
+        return Err("Range start must not be after the end".to_string());
+    }
+    Ok(range_networks(start_bits, start, end))
+}
+
+/// 合并网段：去掉被包含的网段，并把相邻的网段聚合为尽量大的超网
+pub fn aggregate(networks: &[Network]) -> Vec<Network> {
+    let mut ranges: Vec<(u32, u128, u128)> = networks.iter().map(|n| (n.bits, n.network(), n.last())).collect();
+    ranges.sort();
+
+    let mut merged: Vec<(u32, u128, u128)> = Vec::new();
+    for (bits, start, end) in ranges {
+        match merged.last_mut() {
+            Some((last_bits, _, last_end)) if *last_bits == bits && last_end.checked_add(1).is_none_or(|next| start <= next) => {
+                *last_end = (*last_end).max(end);
+            }
+            _ => merged.push((bits, start, end)),
+        }
+    }
+    merged
+        .into_iter()
+        .flat_map(|(bits, start, end)| range_networks(bits, start, end))
+        .collect()
+}
+
+pub fn convert(input: &str) -> Result<AddressForms, String> {
+    let (bits, value) = parse_address(input)?;
+    let ip = to_ip(bits, value);
+    let (expanded, binary, reverse_pointer) = match ip {
+        IpAddr::V4(ip) => {
+            let octets = ip.octets();
+            let binary = octets.iter().map(|o| format!("{:08b}", o)).collect::<Vec<_>>().join(".");
+            let reverse = octets.iter().rev().map(|o| o.to_string()).collect::<Vec<_>>().join(".");
+            (ip.to_string(), binary, format!("{}.in-addr.arpa", reverse))
+        }
+        IpAddr::V6(ip) => {
+            let segments = ip.segments();
+            let expanded = segments.iter().map(|s| format!("{:04x}", s)).collect::<Vec<_>>().join(":");
+            let binary = segments.iter().map(|s| format!("{:016b}", s)).collect::<Vec<_>>().join(":");
+            let nibbles: String = format!("{:032x}", value)
+                .chars()
+                .rev()
+                .map(|c| format!("{}.", c))
+                .collect();
+            (expanded, binary, format!("{}ip6.arpa", nibbles))
+        }
+    };
+    Ok(AddressForms {
+        version: if bits == 32 { 4 } else { 6 },
+        standard: ip.to_string(),
+        expanded,
+        integer: value.to_string(),
+        hex: format!("0x{:0width$X}", value, width = (bits / 4) as usize),
+        binary,
+        reverse_pointer,
+        scope: scope(bits, value).to_string(),
+    })
+}
@@ -0,0 +1,187 @@
+mod calc;
+
+use serde::{Deserialize, Serialize};
+use tauri::{plugin::TauriPlugin, Runtime, Manager, AppHandle};
+use tracing::{info, warn};
+use std::path::PathBuf;
+use tokio::sync::RwLock;
+
+pub use calc::{AddressForms, Network, NetworkInfo};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IpToolConfig {
+    pub enabled: bool,
+    /// 子网划分返回的子网数上限
+    pub max_split_results: usize,
+}
+
+impl Default for IpToolConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_split_results: 4096,
+        }
+    }
+}
+
+pub struct IpToolState {
+    config: RwLock<IpToolConfig>,
+}
+
+impl IpToolState {
+    pub fn new(config: IpToolConfig) -> Self {
+        Self { config: RwLock::new(config) }
+    }
+}
+
+#[tauri::command]
+async fn get_config<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<IpToolConfig, String> {
+    let state = app.state::<IpToolState>();
+    let config = state.config.read().await.clone();
+    Ok(config)
+}
+
+#[tauri::command]
+async fn update_config<R: Runtime>(
+    app: AppHandle<R>,
+    config: IpToolConfig,
+) -> Result<(), String> {
+    let state = app.state::<IpToolState>();
+    info!("ip-tool config updated: {:?}", config);
+    *state.config.write().await = config;
+    save_config(&app).await
+}
+
+fn config_path<R: Runtime>(app: &AppHandle<R>) -> Option<PathBuf> {
+    app.path().app_config_dir().ok().map(|dir| dir.join("ip-tool.json"))
+}
+
+/// 读取保存的配置，文件不存在或无法解析时使用默认配置
+fn load_config<R: Runtime>(app: &AppHandle<R>) -> IpToolConfig {
+    let Some(path) = config_path(app) else {
+        return IpToolConfig::default();
+    };
+    match std::fs::read_to_string(&path) {
+        Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+            warn!("Invalid ip-tool config {}: {}", path.display(), e);
+            IpToolConfig::default()
+        }),
+        Err(_) => IpToolConfig::default(),
+    }
+}
+
+async fn save_config<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    let path = config_path(app).ok_or_else(|| "Config directory is unavailable".to_string())?;
+    let state = app.state::<IpToolState>();
+    let contents = serde_json::to_string_pretty(&*state.config.read().await)
+        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir)
+            .await
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    tokio::fs::write(&path, contents)
+        .await
+        .map_err(|e| format!("Failed to save config to {}: {}", path.display(), e))
+}
+
+/// 计算网段信息，如 "192.168.1.10/24"、"10.0.0.0/255.0.0.0" 或 "2001:db8::/48"
+#[tauri::command]
+async fn ip_network_info<R: Runtime>(
+    _app: AppHandle<R>,
+    cidr: String,
+) -> Result<NetworkInfo, String> {
+    Ok(Network::parse(&cidr)?.info())
+}
+
+/// 把网段划分为子网，按新的前缀长度或需要的子网数划分
+#[tauri::command]
+async fn ip_split<R: Runtime>(
+    app: AppHandle<R>,
+    cidr: String,
+    prefix: Option<u32>,
+    count: Option<u32>,
+) -> Result<Vec<NetworkInfo>, String> {
+    let network = Network::parse(&cidr)?.normalized();
+    let prefix = match (prefix, count) {
+        (Some(prefix), _) => prefix,
+        (None, Some(count)) => calc::prefix_for_count(&network, count)?,
+        (None, None) => return Err("Either a new prefix or a subnet count is required".to_string()),
+    };
+    let limit = app.state::<IpToolState>().config.read().await.max_split_results;
+    let subnets = calc::split(&network, prefix, limit)?;
+    Ok(subnets.iter().map(Network::info).collect())
+}
+
+/// 合并网段为最少的超网
+#[tauri::command]
+async fn ip_aggregate<R: Runtime>(
+    _app: AppHandle<R>,
+    cidrs: Vec<String>,
+) -> Result<Vec<String>, String> {
+    let networks = cidrs
+        .iter()
+        .filter(|cidr| !cidr.trim().is_empty())
+        .map(|cidr| Network::parse(cidr))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(calc::aggregate(&networks).iter().map(Network::to_string).collect())
+}
+
+/// 地址范围转换为 CIDR 网段列表，如 10.0.0.5 - 10.0.0.20
+#[tauri::command]
+async fn ip_range_to_cidrs<R: Runtime>(
+    _app: AppHandle<R>,
+    start: String,
+    end: String,
+) -> Result<Vec<String>, String> {
+    let networks = calc::range_to_networks(&start, &end)?;
+    Ok(networks.iter().map(Network::to_string).collect())
+}
+
+/// 判断地址（或网段）是否在网段内
+#[tauri::command]
+async fn ip_contains<R: Runtime>(
+    _app: AppHandle<R>,
+    cidr: String,
+    address: String,
+) -> Result<bool, String> {
+    let network = Network::parse(&cidr)?;
+    let address = Network::parse(&address)?;
+    Ok(network.contains(&address))
+}
+
+/// 地址在点分、整数、十六进制和二进制形式之间转换
+#[tauri::command]
+async fn ip_convert<R: Runtime>(
+    _app: AppHandle<R>,
+    address: String,
+) -> Result<AddressForms, String> {
+    calc::convert(&address)
+}
+
+pub fn init<R: Runtime>() -> TauriPlugin<R> {
+    tauri::plugin::Builder::new("ip-tool")
+        .invoke_handler(tauri::generate_handler![
+            get_config,
+            update_config,
+            ip_network_info,
+            ip_split,
+            ip_aggregate,
+            ip_range_to_cidrs,
+            ip_contains,
+            ip_convert
+        ])
+        .setup(|app, _api| {
+            // 初始化插件状态，优先使用保存的配置
+            let config = load_config(app);
+            let state = IpToolState::new(config);
+            app.manage(state);
+
+            info!("ip-tool plugin initialized");
+            Ok(())
+        })
+        .build()
+}
@@ -25,6 +25,19 @@ impl JsonFormatterState {
     }
 }
 
+/// 按指定缩进格式化 JSON，保留键的原始顺序，供其他工具（如 HTTP 客户端）复用
+pub fn format_json(input: &str, indent: usize) -> Result<String, String> {
+    let value: serde_json::Value = serde_json::from_str(input).map_err(|e| format!("Invalid JSON: {}", e))?;
+    let indent = " ".repeat(indent);
+    let mut output = Vec::new();
+    let mut serializer = serde_json::Serializer::with_formatter(
+        &mut output,
+        serde_json::ser::PrettyFormatter::with_indent(indent.as_bytes()),
+    );
+    value.serialize(&mut serializer).map_err(|e| format!("Failed to format JSON: {}", e))?;
+    String::from_utf8(output).map_err(|e| format!("Failed to format JSON: {}", e))
+}
+
 #[tauri::command]
 async fn get_config<R: Runtime>(
     app: AppHandle<R>,
@@ -0,0 +1,229 @@
+mod route;
+mod server;
+
+use serde::{Deserialize, Serialize};
+use tauri::{plugin::TauriPlugin, Runtime, Manager, AppHandle, Emitter};
+use tracing::{info, warn};
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+use tokio::sync::{Mutex, RwLock};
+
+pub use route::MockRoute;
+pub use server::{MockServerInfo, RequestLog};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MockServerConfig {
+    pub enabled: bool,
+    pub default_port: u16,
+    pub default_bind_address: String,
+    /// 为所有响应添加 CORS 头并自动应答预检请求
+    pub cors: bool,
+    /// 保留的请求日志条数
+    pub log_limit: usize,
+    #[serde(default)]
+    pub routes: Vec<MockRoute>,
+}
+
+impl Default for MockServerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            default_port: 3000,
+            default_bind_address: "127.0.0.1".to_string(),
+            cors: true,
+            log_limit: 500,
+            routes: Vec::new(),
+        }
+    }
+}
+
+pub struct MockServerState {
+    config: RwLock<MockServerConfig>,
+    servers: RwLock<HashMap<String, server::RunningServer>>,
+    /// 请求日志及下一条日志的序号
+    log: Mutex<(VecDeque<RequestLog>, u64)>,
+}
+
+impl MockServerState {
+    pub fn new(config: MockServerConfig) -> Self {
+        Self {
+            config: RwLock::new(config),
+            servers: RwLock::new(HashMap::new()),
+            log: Mutex::new((VecDeque::new(), 1)),
+        }
+    }
+
+    /// 为日志分配序号并保存，超出上限时丢弃最早的记录
+    async fn record(&self, mut entry: RequestLog) -> RequestLog {
+        let limit = self.config.read().await.log_limit;
+        let mut log = self.log.lock().await;
+        entry.id = log.1;
+        log.1 += 1;
+        log.0.push_back(entry.clone());
+        while log.0.len() > limit {
+            log.0.pop_front();
+        }
+        entry
+    }
+}
+
+fn emit_event<R: Runtime, S: Serialize + Clone>(app: &AppHandle<R>, event: &str, payload: S) {
+    if let Err(e) = app.emit(event, payload) {
+        warn!("Failed to emit {}: {}", event, e);
+    }
+}
+
+#[tauri::command]
+async fn get_config<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<MockServerConfig, String> {
+    let state = app.state::<MockServerState>();
+    let config = state.config.read().await.clone();
+    Ok(config)
+}
+
+#[tauri::command]
+async fn update_config<R: Runtime>(
+    app: AppHandle<R>,
+    config: MockServerConfig,
+) -> Result<(), String> {
+    for route in &config.routes {
+        route::validate(route)?;
+    }
+    let state = app.state::<MockServerState>();
+    info!("mock-server config updated: {:?}", config);
+    *state.config.write().await = config;
+    save_config(&app).await
+}
+
+fn config_path<R: Runtime>(app: &AppHandle<R>) -> Option<PathBuf> {
+    app.path().app_config_dir().ok().map(|dir| dir.join("mock-server.json"))
+}
+
+/// 读取保存的配置，文件不存在或无法解析时使用默认配置
+fn load_config<R: Runtime>(app: &AppHandle<R>) -> MockServerConfig {
+    let Some(path) = config_path(app) else {
+        return MockServerConfig::default();
+    };
+    match std::fs::read_to_string(&path) {
+        Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+            warn!("Invalid mock-server config {}: {}", path.display(), e);
+            MockServerConfig::default()
+        }),
+        Err(_) => MockServerConfig::default(),
+    }
+}
+
+async fn save_config<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    let path = config_path(app).ok_or_else(|| "Config directory is unavailable".to_string())?;
+    let state = app.state::<MockServerState>();
+    let contents = serde_json::to_string_pretty(&*state.config.read().await)
+        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir)
+            .await
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    tokio::fs::write(&path, contents)
+        .await
+        .map_err(|e| format!("Failed to save config to {}: {}", path.display(), e))
+}
+
+#[tauri::command]
+async fn mock_get_routes<R: Runtime>(app: AppHandle<R>) -> Result<Vec<MockRoute>, String> {
+    let state = app.state::<MockServerState>();
+    let routes = state.config.read().await.routes.clone();
+    Ok(routes)
+}
+
+/// 新增或更新路由（按 id），id 为空时自动生成；修改立即对运行中的服务生效
+#[tauri::command]
+async fn mock_save_route<R: Runtime>(app: AppHandle<R>, mut route: MockRoute) -> Result<MockRoute, String> {
+    route::validate(&route)?;
+    if route.id.is_empty() {
+        route.id = format!("route_{}", uuid::Uuid::new_v4().simple());
+    }
+
+    let state = app.state::<MockServerState>();
+    {
+        let mut config = state.config.write().await;
+        match config.routes.iter_mut().find(|r| r.id == route.id) {
+            Some(existing) => *existing = route.clone(),
+            None => config.routes.push(route.clone()),
+        }
+    }
+    save_config(&app).await?;
+
+    info!("Mock route {} saved", route.id);
+    Ok(route)
+}
+
+#[tauri::command]
+async fn mock_remove_route<R: Runtime>(app: AppHandle<R>, route_id: String) -> Result<(), String> {
+    let state = app.state::<MockServerState>();
+    state.config.write().await.routes.retain(|r| r.id != route_id);
+    save_config(&app).await?;
+
+    info!("Mock route {} removed", route_id);
+    Ok(())
+}
+
+/// 整体替换路由列表，也用于调整顺序：匹配时按列表顺序优先
+#[tauri::command]
+async fn mock_set_routes<R: Runtime>(app: AppHandle<R>, routes: Vec<MockRoute>) -> Result<(), String> {
+    for route in &routes {
+        route::validate(route)?;
+    }
+    let state = app.state::<MockServerState>();
+    state.config.write().await.routes = routes;
+    save_config(&app).await
+}
+
+/// 获取请求日志，可按服务过滤
+#[tauri::command]
+async fn mock_get_log<R: Runtime>(app: AppHandle<R>, server_id: Option<String>) -> Result<Vec<RequestLog>, String> {
+    let state = app.state::<MockServerState>();
+    let log = state.log.lock().await;
+    let entries = log
+        .0
+        .iter()
+        .filter(|entry| server_id.as_ref().is_none_or(|id| &entry.server_id == id))
+        .cloned()
+        .collect();
+    Ok(entries)
+}
+
+#[tauri::command]
+async fn mock_clear_log<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
+    let state = app.state::<MockServerState>();
+    state.log.lock().await.0.clear();
+    Ok(())
+}
+
+pub fn init<R: Runtime>() -> TauriPlugin<R> {
+    tauri::plugin::Builder::new("mock-server")
+        .invoke_handler(tauri::generate_handler![
+            get_config,
+            update_config,
+            server::mock_server_start,
+            server::mock_server_stop,
+            server::mock_server_list,
+            mock_get_routes,
+            mock_save_route,
+            mock_remove_route,
+            mock_set_routes,
+            mock_get_log,
+            mock_clear_log
+        ])
+        .setup(|app, _api| {
+            // 初始化插件状态，优先使用保存的配置
+            let config = load_config(app);
+            let state = MockServerState::new(config);
+            app.manage(state);
+
+            info!("mock-server plugin initialized");
+            Ok(())
+        })
+        .build()
+}
@@ -0,0 +1,147 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// 用户定义的路由，随配置保存，按顺序匹配
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MockRoute {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// HTTP 方法，"*" 匹配任意方法
+    pub method: String,
+    /// 路径模式：`:name` 匹配一级路径，`*name` 匹配剩余的所有路径，如 "/users/:id" 或 "/static/*path"
+    pub path: String,
+    #[serde(default = "default_status")]
+    pub status: u16,
+    #[serde(default)]
+    pub headers: Vec<(String, String)>,
+    /// 响应体模板，占位符见 [`render`]
+    #[serde(default)]
+    pub body: String,
+    /// 应答前的延迟（毫秒）
+    #[serde(default)]
+    pub delay_ms: u64,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_status() -> u16 {
+    200
+}
+
+/// 校验路由定义
+pub fn validate(route: &MockRoute) -> Result<(), String> {
+    if !route.path.starts_with('/') {
+        return Err(format!("Route path must start with '/': {}", route.path));
+    }
+    let segments: Vec<&str> = route.path.split('/').collect();
+    for (index, segment) in segments.iter().enumerate() {
+        if segment.starts_with('*') && index != segments.len() - 1 {
+            return Err(format!("Wildcard must be the last segment: {}", route.path));
+        }
+    }
+    if !(100..=999).contains(&route.status) {
+        return Err(format!("Invalid status code: {}", route.status));
+    }
+    Ok(())
+}
+
+/// 按模式匹配路径，成功时返回路径参数
+pub fn match_path(pattern: &str, path: &str) -> Option<HashMap<String, String>> {
+    let pattern: Vec<&str> = pattern.trim_end_matches('/').split('/').collect();
+    let path: Vec<&str> = path.trim_end_matches('/').split('/').collect();
+    let mut params = HashMap::new();
+
+    for (index, segment) in pattern.iter().enumerate() {
+        if let Some(name) = segment.strip_prefix('*') {
+            params.insert(name.to_string(), path.get(index..).unwrap_or_default().join("/"));
+            return Some(params);
+        }
+        let value = path.get(index)?;
+        match segment.strip_prefix(':') {
+            Some(name) => {
+                params.insert(name.to_string(), value.to_string());
+            }
+            None if segment != value => return None,
+            None => {}
+        }
+    }
+    (pattern.len() == path.len()).then_some(params)
+}
+
+pub fn match_method(pattern: &str, method: &str) -> bool {
+    pattern == "*" || pattern.eq_ignore_ascii_case(method)
+}
+
+/// 模板渲染所需的请求数据
+pub struct RequestContext {
+    pub method: String,
+    pub path: String,
+    pub params: HashMap<String, String>,
+    pub query: HashMap<String, String>,
+    /// 请求头名称为小写
+    pub headers: HashMap<String, String>,
+    pub body: String,
+    pub json: Option<serde_json::Value>,
+}
+
+/// 按点分路径取 JSON 字段，数组用下标，如 "user.tags.0"
+fn json_field(value: &serde_json::Value, path: &str) -> Option<String> {
+    let field = path.split('.').try_fold(value, |value, key| match value {
+        serde_json::Value::Array(items) => items.get(key.parse::<usize>().ok()?),
+        _ => value.get(key),
+    })?;
+    Some(match field {
+        serde_json::Value::String(text) => text.clone(),
+        other => other.to_string(),
+    })
+}
+
+/// 渲染响应模板。支持的占位符：
+/// - `{{method}}`、`{{path}}`、`{{body}}`：请求方法、路径和原始请求体
+/// - `{{params.x}}`、`{{query.x}}`、`{{headers.x}}`：路径参数、查询参数和请求头
+/// - `{{body.a.b}}`：JSON 请求体中的字段
+/// - `{{timestamp}}`、`{{unix}}`、`{{unix_ms}}`：当前时间
+///
+/// 无法解析的占位符原样保留
+pub fn render(template: &str, context: &RequestContext) -> String {
+    let mut output = String::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find("}}") else {
+            rest = &rest[start..];
+            break;
+        };
+        let raw = &rest[start..start + end + 2];
+        let placeholder = rest[start + 2..start + end].trim();
+        rest = &rest[start + end + 2..];
+
+        let now = Utc::now();
+        let value = match placeholder {
+            "method" => Some(context.method.clone()),
+            "path" => Some(context.path.clone()),
+            "body" => Some(context.body.clone()),
+            "timestamp" => Some(now.to_rfc3339()),
+            "unix" => Some(now.timestamp().to_string()),
+            "unix_ms" => Some(now.timestamp_millis().to_string()),
+            other => match other.split_once('.') {
+                Some(("params", name)) => context.params.get(name).cloned(),
+                Some(("query", name)) => context.query.get(name).cloned(),
+                Some(("headers", name)) => context.headers.get(&name.to_lowercase()).cloned(),
+                Some(("body", path)) => context.json.as_ref().and_then(|json| json_field(json, path)),
+                _ => None,
+            },
+        };
+        output.push_str(value.as_deref().unwrap_or(raw));
+    }
+    output.push_str(rest);
+    output
+}
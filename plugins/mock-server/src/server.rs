@@ -0,0 +1,331 @@
+use bytes::Bytes;
+use chrono::Utc;
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::body::Incoming;
+use hyper::header::{HeaderName, HeaderValue, CONTENT_TYPE};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Method, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::net::{IpAddr, SocketAddr};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, Runtime};
+use tokio::net::TcpListener;
+use tokio::task::{JoinHandle, JoinSet};
+use tracing::{debug, info, warn};
+
+use crate::route::{match_method, match_path, render, RequestContext};
+use crate::{emit_event, MockServerState};
+
+/// 读取的请求体上限
+const MAX_REQUEST_BODY: usize = 10 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MockServerInfo {
+    pub id: String,
+    pub local_addr: String,
+    pub started_at: String,
+}
+
+pub(crate) struct RunningServer {
+    pub info: MockServerInfo,
+    task: JoinHandle<()>,
+}
+
+/// 收到的一次请求，通过 "mock_request" 事件实时发送
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RequestLog {
+    pub id: u64,
+    pub server_id: String,
+    pub timestamp: String,
+    pub remote_addr: String,
+    pub method: String,
+    pub path: String,
+    pub query: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+    /// 命中的路由，未命中时为空
+    pub route_id: Option<String>,
+    pub status: u16,
+    pub duration_ms: f64,
+}
+
+/// 解码 URL 中的百分号编码，`plus_as_space` 用于查询参数
+fn percent_decode(input: &str, plus_as_space: bool) -> String {
+    let bytes = input.as_bytes();
+    let mut output = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' if i + 2 < bytes.len() => {
+                let escaped = std::str::from_utf8(&bytes[i + 1..i + 3])
+                    .ok()
+                    .and_then(|digits| u8::from_str_radix(digits, 16).ok());
+                if let Some(byte) = escaped {
+                    output.push(byte);
+                    i += 3;
+                    continue;
+                }
+                output.push(b'%');
+            }
+            b'+' if plus_as_space => output.push(b' '),
+            byte => output.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&output).into_owned()
+}
+
+fn parse_query(query: &str) -> HashMap<String, String> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| match pair.split_once('=') {
+            Some((key, value)) => (percent_decode(key, true), percent_decode(value, true)),
+            None => (percent_decode(pair, true), String::new()),
+        })
+        .collect()
+}
+
+fn guess_content_type(body: &str) -> &'static str {
+    if body.trim_start().starts_with(['{', '[']) {
+        "application/json"
+    } else {
+        "text/plain; charset=utf-8"
+    }
+}
+
+fn text_response(status: StatusCode, body: String) -> Response<Full<Bytes>> {
+    let mut response = Response::new(Full::new(Bytes::from(body)));
+    *response.status_mut() = status;
+    response
+        .headers_mut()
+        .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    response
+}
+
+fn add_cors_headers(response: &mut Response<Full<Bytes>>, preflight: bool) {
+    let headers = response.headers_mut();
+    if !headers.contains_key("access-control-allow-origin") {
+        headers.insert("access-control-allow-origin", HeaderValue::from_static("*"));
+    }
+    if preflight {
+        headers.insert(
+            "access-control-allow-methods",
+            HeaderValue::from_static("GET, POST, PUT, PATCH, DELETE, HEAD, OPTIONS"),
+        );
+        headers.insert("access-control-allow-headers", HeaderValue::from_static("*"));
+        headers.insert("access-control-max-age", HeaderValue::from_static("86400"));
+    }
+}
+
+async fn handle<R: Runtime>(
+    app: AppHandle<R>,
+    server_id: String,
+    peer: SocketAddr,
+    request: Request<Incoming>,
+) -> Result<Response<Full<Bytes>>, Infallible> {
+    let started = Instant::now();
+    let state = app.state::<MockServerState>();
+    let (parts, body) = request.into_parts();
+
+    let method = parts.method.to_string();
+    let path = percent_decode(parts.uri.path(), false);
+    let raw_query = parts.uri.query().unwrap_or_default().to_string();
+    let headers: Vec<(String, String)> = parts
+        .headers
+        .iter()
+        .map(|(name, value)| (name.to_string(), String::from_utf8_lossy(value.as_bytes()).into_owned()))
+        .collect();
+    let body = Limited::new(body, MAX_REQUEST_BODY).collect().await.map(|body| body.to_bytes());
+
+    let (cors, route) = {
+        let config = state.config.read().await;
+        let route = config
+            .routes
+            .iter()
+            .filter(|route| route.enabled && match_method(&route.method, &method))
+            .find_map(|route| match_path(&route.path, &path).map(|params| (route.clone(), params)));
+        (config.cors, route)
+    };
+
+    let body = match body {
+        Ok(body) => String::from_utf8_lossy(&body).into_owned(),
+        Err(e) => {
+            warn!("Mock server {} rejected request body: {}", server_id, e);
+            let response = text_response(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                serde_json::json!({ "error": "Request body too large" }).to_string(),
+            );
+            return Ok(response);
+        }
+    };
+
+    let (mut response, route_id) = match route {
+        Some((route, params)) => {
+            let context = RequestContext {
+                method: method.clone(),
+                path: path.clone(),
+                params,
+                query: parse_query(&raw_query),
+                headers: headers.iter().map(|(k, v)| (k.clone(), v.clone())).collect(),
+                json: serde_json::from_str(&body).ok(),
+                body: body.clone(),
+            };
+            if route.delay_ms > 0 {
+                tokio::time::sleep(Duration::from_millis(route.delay_ms)).await;
+            }
+
+            let rendered = render(&route.body, &context);
+            let mut builder = Response::builder().status(route.status);
+            if !route.headers.iter().any(|(name, _)| name.eq_ignore_ascii_case("content-type")) {
+                builder = builder.header(CONTENT_TYPE, guess_content_type(&rendered));
+            }
+            for (name, value) in &route.headers {
+                match (HeaderName::try_from(name.as_str()), HeaderValue::try_from(render(value, &context))) {
+                    (Ok(name), Ok(value)) => builder = builder.header(name, value),
+                    _ => warn!("Mock route {} has an invalid header {}", route.id, name),
+                }
+            }
+            let response = builder.body(Full::new(Bytes::from(rendered))).unwrap_or_else(|e| {
+                text_response(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    serde_json::json!({ "error": format!("Invalid mock response: {}", e) }).to_string(),
+                )
+            });
+            (response, Some(route.id))
+        }
+        // 未定义 OPTIONS 路由时自动应答 CORS 预检请求
+        None if cors && parts.method == Method::OPTIONS => {
+            let mut response = Response::new(Full::new(Bytes::new()));
+            *response.status_mut() = StatusCode::NO_CONTENT;
+            (response, None)
+        }
+        None => {
+            let body = serde_json::json!({
+                "error": "No mock route matched",
+                "method": method,
+                "path": path,
+            });
+            (text_response(StatusCode::NOT_FOUND, body.to_string()), None)
+        }
+    };
+    if cors {
+        add_cors_headers(&mut response, parts.method == Method::OPTIONS);
+    }
+
+    let entry = RequestLog {
+        id: 0,
+        server_id,
+        timestamp: Utc::now().to_rfc3339(),
+        remote_addr: peer.to_string(),
+        method,
+        path,
+        query: raw_query,
+        headers,
+        body,
+        route_id,
+        status: response.status().as_u16(),
+        duration_ms: started.elapsed().as_secs_f64() * 1000.0,
+    };
+    let entry = state.record(entry).await;
+    emit_event(&app, "mock_request", &entry);
+
+    Ok(response)
+}
+
+async fn serve<R: Runtime>(app: AppHandle<R>, server_id: String, listener: TcpListener) {
+    // 服务停止时随 JoinSet 一起终止所有连接
+    let mut connections = JoinSet::new();
+
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok((stream, peer)) => {
+                    let app = app.clone();
+                    let server_id = server_id.clone();
+                    connections.spawn(async move {
+                        let service = service_fn(move |request| handle(app.clone(), server_id.clone(), peer, request));
+                        if let Err(e) = http1::Builder::new().serve_connection(TokioIo::new(stream), service).await {
+                            debug!("Mock server connection from {} ended: {}", peer, e);
+                        }
+                    });
+                }
+                Err(e) => {
+                    warn!("Mock server {} accept error: {}", server_id, e);
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                }
+            },
+            Some(_) = connections.join_next(), if !connections.is_empty() => {}
+        }
+    }
+}
+
+#[tauri::command]
+pub(crate) async fn mock_server_start<R: Runtime>(
+    app: AppHandle<R>,
+    port: Option<u16>,
+    bind_address: Option<String>,
+) -> Result<MockServerInfo, String> {
+    let state = app.state::<MockServerState>();
+    let (bind_address, port) = {
+        let config = state.config.read().await;
+        (
+            bind_address.unwrap_or_else(|| config.default_bind_address.clone()),
+            port.unwrap_or(config.default_port),
+        )
+    };
+    let ip: IpAddr = bind_address
+        .parse()
+        .map_err(|_| format!("Invalid bind address: {}", bind_address))?;
+    let listener = TcpListener::bind(SocketAddr::new(ip, port))
+        .await
+        .map_err(|e| format!("Mock server start failed: {}", e))?;
+    let local_addr = listener.local_addr().map_err(|e| e.to_string())?;
+
+    let server_id = format!("mock_{}_{}", local_addr.ip(), local_addr.port());
+    let info = MockServerInfo {
+        id: server_id.clone(),
+        local_addr: local_addr.to_string(),
+        started_at: Utc::now().to_rfc3339(),
+    };
+    let task = tokio::spawn(serve(app.clone(), server_id.clone(), listener));
+    state
+        .servers
+        .write()
+        .await
+        .insert(server_id.clone(), RunningServer { info: info.clone(), task });
+    emit_event(&app, "mock_server_started", &info);
+
+    info!("Mock server listening on http://{}", local_addr);
+    Ok(info)
+}
+
+#[tauri::command]
+pub(crate) async fn mock_server_stop<R: Runtime>(
+    app: AppHandle<R>,
+    server_id: String,
+) -> Result<(), String> {
+    let state = app.state::<MockServerState>();
+    let server = state
+        .servers
+        .write()
+        .await
+        .remove(&server_id)
+        .ok_or_else(|| "Mock server not found".to_string())?;
+    server.task.abort();
+    emit_event(&app, "mock_server_stopped", &server.info);
+
+    info!("Mock server {} stopped", server_id);
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) async fn mock_server_list<R: Runtime>(app: AppHandle<R>) -> Result<Vec<MockServerInfo>, String> {
+    let state = app.state::<MockServerState>();
+    let servers = state.servers.read().await.values().map(|server| server.info.clone()).collect();
+    Ok(servers)
+}
@@ -0,0 +1,972 @@
+use bytes::Bytes;
+use chrono::Utc;
+use network_common::payload::{decode, decode_hex, to_hex};
+use rumqttc::v5;
+use rumqttc::v5::mqttbytes::v5::{LastWillProperties, PublishProperties};
+use rumqttc::{Outgoing, TlsConfiguration, Transport};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Runtime};
+use tracing::{info, warn};
+
+use crate::{emit_event, tls, MqttConnectionInfo, MqttToolState, Subscription, TlsOptions};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProtocolVersion {
+    #[default]
+    #[serde(rename = "3.1.1")]
+    V311,
+    #[serde(rename = "5.0")]
+    V5,
+}
+
+/// MQTT 5.0 的消息属性，用于发布、遗嘱和收到的消息
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MessageProperties {
+    pub payload_format_indicator: Option<u8>,
+    /// 消息过期时间（秒）
+    pub message_expiry_interval: Option<u32>,
+    pub content_type: Option<String>,
+    pub response_topic: Option<String>,
+    /// 十六进制表示的关联数据
+    pub correlation_data: Option<String>,
+    pub user_properties: Vec<(String, String)>,
+    /// 收到的消息匹配的订阅标识符，发布时忽略
+    pub subscription_identifiers: Vec<usize>,
+}
+
+impl MessageProperties {
+    fn correlation_data(&self) -> Result<Option<Bytes>, String> {
+        self.correlation_data
+            .as_deref()
+            .map(|data| decode_hex(data).map(Bytes::from))
+            .transpose()
+    }
+
+    fn to_publish(&self) -> Result<PublishProperties, String> {
+        Ok(PublishProperties {
+            payload_format_indicator: self.payload_format_indicator,
+            message_expiry_interval: self.message_expiry_interval,
+            topic_alias: None,
+            response_topic: self.response_topic.clone(),
+            correlation_data: self.correlation_data()?,
+            user_properties: self.user_properties.clone(),
+            subscription_identifiers: Vec::new(),
+            content_type: self.content_type.clone(),
+        })
+    }
+
+    fn from_publish(properties: &PublishProperties) -> Self {
+        Self {
+            payload_format_indicator: properties.payload_format_indicator,
+            message_expiry_interval: properties.message_expiry_interval,
+            content_type: properties.content_type.clone(),
+            response_topic: properties.response_topic.clone(),
+            correlation_data: properties.correlation_data.as_deref().map(to_hex),
+            user_properties: properties.user_properties.clone(),
+            subscription_identifiers: properties.subscription_identifiers.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LastWillOptions {
+    pub topic: String,
+    pub payload: String,
+    #[serde(default)]
+    pub encoding: Option<String>,
+    #[serde(default)]
+    pub qos: u8,
+    #[serde(default)]
+    pub retain: bool,
+    /// 遗嘱延迟发送时间（秒），仅 MQTT 5.0
+    #[serde(default)]
+    pub delay_interval: Option<u32>,
+    /// 仅 MQTT 5.0
+    #[serde(default)]
+    pub properties: Option<MessageProperties>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MqttConnectOptions {
+    #[serde(default)]
+    pub host: Option<String>,
+    #[serde(default)]
+    pub port: Option<u16>,
+    /// 为空时自动生成
+    #[serde(default)]
+    pub client_id: Option<String>,
+    #[serde(default)]
+    pub protocol_version: ProtocolVersion,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+    #[serde(default)]
+    pub keep_alive_secs: Option<u64>,
+    /// MQTT 3.1.1 的 clean session，MQTT 5.0 的 clean start
+    #[serde(default = "default_true")]
+    pub clean_session: bool,
+    #[serde(default)]
+    pub tls: Option<TlsOptions>,
+    #[serde(default)]
+    pub last_will: Option<LastWillOptions>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// 收发的消息，通过 "mqtt_message" 事件发送
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MqttMessage {
+    pub connection_id: String,
+    pub topic: String,
+    pub payload: String,
+    pub payload_hex: String,
+    pub size: usize,
+    pub qos: u8,
+    pub retain: bool,
+    pub dup: bool,
+    pub direction: String, // "sent" or "received"
+    pub timestamp: String,
+    pub properties: Option<MessageProperties>,
+}
+
+impl MqttMessage {
+    fn new(connection_id: &str, topic: String, payload: &[u8], qos: u8, retain: bool, direction: &str) -> Self {
+        Self {
+            connection_id: connection_id.to_string(),
+            topic,
+            payload: String::from_utf8_lossy(payload).into_owned(),
+            payload_hex: to_hex(payload),
+            size: payload.len(),
+            qos,
+            retain,
+            dup: false,
+            direction: direction.to_string(),
+            timestamp: Utc::now().to_rfc3339(),
+            properties: None,
+        }
+    }
+}
+
+/// 服务端对订阅的应答，通过 "mqtt_suback" 事件发送；拒绝的订阅对应的 `granted_qos` 为空
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubAckResult {
+    pub connection_id: String,
+    pub packet_id: u16,
+    /// 应答对应的主题过滤器
+    pub topics: Vec<String>,
+    pub granted_qos: Vec<Option<u8>>,
+}
+
+/// 服务端对取消订阅的应答，通过 "mqtt_unsuback" 事件发送；MQTT 3.1.1 的应答总是成功
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnsubAckResult {
+    pub connection_id: String,
+    pub packet_id: u16,
+    /// 应答对应的主题过滤器
+    pub topics: Vec<String>,
+    pub success: bool,
+}
+
+/// 已提交但尚未收到 SUBACK/UNSUBACK 的请求。请求按提交顺序发出，
+/// 发出时才分配报文 ID，因此先排队，发出后再按报文 ID 对应
+pub(crate) struct PendingRequests<T> {
+    queued: VecDeque<T>,
+    in_flight: HashMap<u16, T>,
+}
+
+impl<T> Default for PendingRequests<T> {
+    fn default() -> Self {
+        Self { queued: VecDeque::new(), in_flight: HashMap::new() }
+    }
+}
+
+impl<T> PendingRequests<T> {
+    fn queue(&mut self, request: T) {
+        self.queued.push_back(request);
+    }
+
+    /// 撤销最后一个排队的请求，用于提交失败时
+    fn unqueue(&mut self) {
+        self.queued.pop_back();
+    }
+
+    fn sent(&mut self, packet_id: u16) {
+        if let Some(request) = self.queued.pop_front() {
+            self.in_flight.insert(packet_id, request);
+        }
+    }
+
+    fn acked(&mut self, packet_id: u16) -> Option<T> {
+        self.in_flight.remove(&packet_id)
+    }
+}
+
+fn qos_v4(level: u8) -> Result<rumqttc::QoS, String> {
+    match level {
+        0 => Ok(rumqttc::QoS::AtMostOnce),
+        1 => Ok(rumqttc::QoS::AtLeastOnce),
+        2 => Ok(rumqttc::QoS::ExactlyOnce),
+        other => Err(format!("Invalid QoS level: {}", other)),
+    }
+}
+
+fn qos_v5(level: u8) -> Result<v5::mqttbytes::QoS, String> {
+    match level {
+        0 => Ok(v5::mqttbytes::QoS::AtMostOnce),
+        1 => Ok(v5::mqttbytes::QoS::AtLeastOnce),
+        2 => Ok(v5::mqttbytes::QoS::ExactlyOnce),
+        other => Err(format!("Invalid QoS level: {}", other)),
+    }
+}
+
+/// 校验订阅的主题过滤器：`#` 只能作为最后一级，`+` 和 `#` 必须占据整级
+fn validate_filter(filter: &str) -> Result<(), String> {
+    if filter.is_empty() {
+        return Err("Topic filter must not be empty".to_string());
+    }
+    let levels: Vec<&str> = filter.split('/').collect();
+    for (index, level) in levels.iter().enumerate() {
+        if level.contains('#') && (*level != "#" || index != levels.len() - 1) {
+            return Err(format!("Invalid use of '#' in topic filter: {}", filter));
+        }
+        if level.contains('+') && *level != "+" {
+            return Err(format!("Invalid use of '+' in topic filter: {}", filter));
+        }
+    }
+    Ok(())
+}
+
+/// 发布的主题不能为空，也不能包含通配符
+fn validate_topic(topic: &str) -> Result<(), String> {
+    if topic.is_empty() {
+        return Err("Topic must not be empty".to_string());
+    }
+    if topic.contains(['+', '#']) {
+        return Err(format!("Wildcards are not allowed in a publish topic: {}", topic));
+    }
+    Ok(())
+}
+
+#[derive(Clone)]
+pub(crate) enum Client {
+    V4(rumqttc::AsyncClient),
+    V5(v5::AsyncClient),
+}
+
+enum EventLoop {
+    V4(Box<rumqttc::EventLoop>),
+    V5(Box<v5::EventLoop>),
+}
+
+/// 循环中收到的、需要处理的事件
+enum Incoming {
+    ConnAck(bool),
+    Message(Box<MqttMessage>),
+    SubAck(SubAckResult),
+    /// SUBSCRIBE 已发出，附报文 ID
+    SubscribeSent(u16),
+    UnsubAck(UnsubAckResult),
+    /// UNSUBSCRIBE 已发出，附报文 ID
+    UnsubscribeSent(u16),
+    /// 本端发出了 DISCONNECT，或服务端发来 DISCONNECT（附原因）
+    Disconnected(Option<String>),
+    Other,
+}
+
+impl EventLoop {
+    async fn poll(&mut self, connection_id: &str) -> Result<Incoming, String> {
+        match self {
+            EventLoop::V4(eventloop) => {
+                use rumqttc::{Event, Packet, SubscribeReasonCode};
+                let event = eventloop.poll().await.map_err(|e| e.to_string())?;
+                Ok(match event {
+                    Event::Incoming(Packet::ConnAck(ack)) => Incoming::ConnAck(ack.session_present),
+                    Event::Incoming(Packet::Publish(publish)) => {
+                        let mut message = MqttMessage::new(
+                            connection_id,
+                            publish.topic,
+                            &publish.payload,
+                            publish.qos as u8,
+                            publish.retain,
+                            "received",
+                        );
+                        message.dup = publish.dup;
+                        Incoming::Message(Box::new(message))
+                    }
+                    Event::Incoming(Packet::SubAck(ack)) => Incoming::SubAck(SubAckResult {
+                        connection_id: connection_id.to_string(),
+                        packet_id: ack.pkid,
+                        topics: Vec::new(),
+                        granted_qos: ack
+                            .return_codes
+                            .iter()
+                            .map(|code| match code {
+                                SubscribeReasonCode::Success(qos) => Some(*qos as u8),
+                                SubscribeReasonCode::Failure => None,
+                            })
+                            .collect(),
+                    }),
+                    Event::Incoming(Packet::UnsubAck(ack)) => Incoming::UnsubAck(UnsubAckResult {
+                        connection_id: connection_id.to_string(),
+                        packet_id: ack.pkid,
+                        topics: Vec::new(),
+                        success: true,
+                    }),
+                    Event::Outgoing(Outgoing::Subscribe(packet_id)) => Incoming::SubscribeSent(packet_id),
+                    Event::Outgoing(Outgoing::Unsubscribe(packet_id)) => Incoming::UnsubscribeSent(packet_id),
+                    Event::Outgoing(Outgoing::Disconnect) => Incoming::Disconnected(None),
+                    _ => Incoming::Other,
+                })
+            }
+            EventLoop::V5(eventloop) => {
+                use v5::mqttbytes::v5::{Packet, SubscribeReasonCode, UnsubAckReason};
+                use v5::Event;
+                let event = eventloop.poll().await.map_err(|e| e.to_string())?;
+                Ok(match event {
+                    Event::Incoming(Packet::ConnAck(ack)) => Incoming::ConnAck(ack.session_present),
+                    Event::Incoming(Packet::Publish(publish)) => {
+                        let mut message = MqttMessage::new(
+                            connection_id,
+                            String::from_utf8_lossy(&publish.topic).into_owned(),
+                            &publish.payload,
+                            publish.qos as u8,
+                            publish.retain,
+                            "received",
+                        );
+                        message.dup = publish.dup;
+                        message.properties = publish.properties.as_ref().map(MessageProperties::from_publish);
+                        Incoming::Message(Box::new(message))
+                    }
+                    Event::Incoming(Packet::SubAck(ack)) => Incoming::SubAck(SubAckResult {
+                        connection_id: connection_id.to_string(),
+                        packet_id: ack.pkid,
+                        topics: Vec::new(),
+                        granted_qos: ack
+                            .return_codes
+                            .iter()
+                            .map(|code| match code {
+                                SubscribeReasonCode::Success(qos) => Some(*qos as u8),
+                                _ => None,
+                            })
+                            .collect(),
+                    }),
+                    Event::Incoming(Packet::UnsubAck(ack)) => Incoming::UnsubAck(UnsubAckResult {
+                        connection_id: connection_id.to_string(),
+                        packet_id: ack.pkid,
+                        topics: Vec::new(),
+                        // 订阅本就不存在时也视为已取消
+                        success: ack
+                            .reasons
+                            .iter()
+                            .all(|reason| matches!(reason, UnsubAckReason::Success | UnsubAckReason::NoSubscriptionExisted)),
+                    }),
+                    Event::Incoming(Packet::Disconnect(disconnect)) => {
+                        Incoming::Disconnected(Some(format!("Disconnected by broker: {:?}", disconnect.reason_code)))
+                    }
+                    Event::Outgoing(Outgoing::Subscribe(packet_id)) => Incoming::SubscribeSent(packet_id),
+                    Event::Outgoing(Outgoing::Unsubscribe(packet_id)) => Incoming::UnsubscribeSent(packet_id),
+                    Event::Outgoing(Outgoing::Disconnect) => Incoming::Disconnected(None),
+                    _ => Incoming::Other,
+                })
+            }
+        }
+    }
+}
+
+fn last_will_payload(will: &LastWillOptions) -> Result<Vec<u8>, String> {
+    validate_topic(&will.topic)?;
+    decode(&will.payload, will.encoding.as_deref())
+}
+
+fn build_v4(options: &MqttConnectOptions, client_id: &str, host: &str, port: u16, keep_alive: u64) -> Result<rumqttc::MqttOptions, String> {
+    let mut mqtt_options = rumqttc::MqttOptions::new(client_id, host, port);
+    mqtt_options
+        .set_keep_alive(Duration::from_secs(keep_alive))
+        .set_clean_session(options.clean_session);
+    if let Some(username) = &options.username {
+        mqtt_options.set_credentials(username, options.password.clone().unwrap_or_default());
+    }
+    if let Some(will) = &options.last_will {
+        if will.delay_interval.is_some() || will.properties.is_some() {
+            return Err("Last will properties require MQTT 5.0".to_string());
+        }
+        let payload = last_will_payload(will)?;
+        mqtt_options.set_last_will(rumqttc::LastWill::new(&will.topic, payload, qos_v4(will.qos)?, will.retain));
+    }
+    if let Some(tls) = &options.tls {
+        mqtt_options.set_transport(Transport::tls_with_config(TlsConfiguration::Rustls(tls::client_config(tls)?)));
+    }
+    Ok(mqtt_options)
+}
+
+fn build_v5(options: &MqttConnectOptions, client_id: &str, host: &str, port: u16, keep_alive: u64) -> Result<v5::MqttOptions, String> {
+    let mut mqtt_options = v5::MqttOptions::new(client_id, host, port);
+    mqtt_options
+        .set_keep_alive(Duration::from_secs(keep_alive))
+        .set_clean_start(options.clean_session);
+    if let Some(username) = &options.username {
+        mqtt_options.set_credentials(username, options.password.clone().unwrap_or_default());
+    }
+    if let Some(will) = &options.last_will {
+        let payload = last_will_payload(will)?;
+        let properties = match (&will.properties, will.delay_interval) {
+            (None, None) => None,
+            (properties, delay_interval) => {
+                let properties = properties.clone().unwrap_or_default();
+                Some(LastWillProperties {
+                    delay_interval,
+                    payload_format_indicator: properties.payload_format_indicator,
+                    message_expiry_interval: properties.message_expiry_interval,
+                    content_type: properties.content_type.clone(),
+                    response_topic: properties.response_topic.clone(),
+                    correlation_data: properties.correlation_data()?,
+                    user_properties: properties.user_properties,
+                })
+            }
+        };
+        mqtt_options.set_last_will(v5::mqttbytes::v5::LastWill::new(
+            &will.topic,
+            payload,
+            qos_v5(will.qos)?,
+            will.retain,
+            properties,
+        ));
+    }
+    if let Some(tls) = &options.tls {
+        mqtt_options.set_transport(Transport::tls_with_config(TlsConfiguration::Rustls(tls::client_config(tls)?)));
+    }
+    Ok(mqtt_options)
+}
+
+/// 按协议版本创建客户端，连接在首次 poll 时才会建立
+fn new_client(options: &MqttConnectOptions, client_id: &str, host: &str, port: u16, keep_alive: u64) -> Result<(Client, EventLoop), String> {
+    Ok(match options.protocol_version {
+        ProtocolVersion::V311 => {
+            let (client, eventloop) = rumqttc::AsyncClient::new(build_v4(options, client_id, host, port, keep_alive)?, 64);
+            (Client::V4(client), EventLoop::V4(Box::new(eventloop)))
+        }
+        ProtocolVersion::V5 => {
+            let (client, eventloop) = v5::AsyncClient::new(build_v5(options, client_id, host, port, keep_alive)?, 64);
+            (Client::V5(client), EventLoop::V5(Box::new(eventloop)))
+        }
+    })
+}
+
+/// 连接到 broker，收到 CONNACK 后返回连接 ID，之后在后台接收消息
+#[tauri::command]
+pub(crate) async fn mqtt_connect<R: Runtime>(
+    app: AppHandle<R>,
+    options: MqttConnectOptions,
+) -> Result<String, String> {
+    let state = app.state::<MqttToolState>();
+    let (host, port, keep_alive, timeout) = {
+        let config = state.config.read().await;
+        (
+            options.host.clone().unwrap_or_else(|| config.default_host.clone()),
+            options.port.unwrap_or(config.default_port),
+            options.keep_alive_secs.unwrap_or(config.keep_alive_secs),
+            Duration::from_millis(config.connection_timeout),
+        )
+    };
+    let client_id = options
+        .client_id
+        .clone()
+        .filter(|id| !id.is_empty())
+        .unwrap_or_else(|| format!("gearbox-{}", &uuid::Uuid::new_v4().simple().to_string()[..8]));
+    let connection_id = format!("mqtt_{}@{}_{}", client_id, host, port);
+    if state.clients.read().await.contains_key(&connection_id) {
+        return Err(format!("Connection {} already exists", connection_id));
+    }
+
+    let (client, mut eventloop) = new_client(&options, &client_id, &host, port, keep_alive)?;
+
+    // 首次 poll 时才会真正建立连接，在这里等到 CONNACK 以便直接返回连接错误
+    let session_present = tokio::time::timeout(timeout, async {
+        loop {
+            if let Incoming::ConnAck(session_present) = eventloop.poll(&connection_id).await? {
+                return Ok::<_, String>(session_present);
+            }
+        }
+    })
+    .await
+    .map_err(|_| format!("Connection to {}:{} timed out", host, port))?
+    .map_err(|e| format!("Connection to {}:{} failed: {}", host, port, e))?;
+
+    let connection_info = MqttConnectionInfo {
+        id: connection_id.clone(),
+        host: host.clone(),
+        port,
+        client_id,
+        protocol_version: options.protocol_version,
+        tls: options.tls.is_some(),
+        status: "connected".to_string(),
+        created_at: Utc::now().to_rfc3339(),
+        session_present,
+        subscriptions: Vec::new(),
+        messages_sent: 0,
+        messages_received: 0,
+        error: None,
+    };
+    state.connections.write().await.insert(connection_id.clone(), connection_info.clone());
+    state.clients.write().await.insert(connection_id.clone(), client);
+    let task = tokio::spawn(run(app.clone(), connection_id.clone(), eventloop));
+    state.tasks.write().await.insert(connection_id.clone(), task);
+    emit_event(&app, "mqtt_connected", &connection_info);
+
+    info!("MQTT connected to {}:{} as {}", host, port, connection_id);
+    Ok(connection_id)
+}
+
+async fn run<R: Runtime>(app: AppHandle<R>, connection_id: String, mut eventloop: EventLoop) {
+    let state = app.state::<MqttToolState>();
+
+    let error = loop {
+        match eventloop.poll(&connection_id).await {
+            Ok(Incoming::Message(message)) => {
+                if let Some(info) = state.connections.write().await.get_mut(&connection_id) {
+                    info.messages_received += 1;
+                }
+                emit_event(&app, "mqtt_message", &message);
+            }
+            Ok(Incoming::SubscribeSent(packet_id)) => {
+                if let Some(pending) = state.pending_subscriptions.lock().await.get_mut(&connection_id) {
+                    pending.sent(packet_id);
+                }
+            }
+            Ok(Incoming::SubAck(mut ack)) => {
+                let subscription = match state.pending_subscriptions.lock().await.get_mut(&connection_id) {
+                    Some(pending) => pending.acked(ack.packet_id),
+                    None => None,
+                };
+                if let Some(subscription) = subscription {
+                    record_suback(&state, &connection_id, &subscription, &ack).await;
+                    ack.topics.push(subscription.topic);
+                }
+                emit_event(&app, "mqtt_suback", &ack);
+            }
+            Ok(Incoming::UnsubscribeSent(packet_id)) => {
+                if let Some(pending) = state.pending_unsubscriptions.lock().await.get_mut(&connection_id) {
+                    pending.sent(packet_id);
+                }
+            }
+            Ok(Incoming::UnsubAck(mut ack)) => {
+                let topic = match state.pending_unsubscriptions.lock().await.get_mut(&connection_id) {
+                    Some(pending) => pending.acked(ack.packet_id),
+                    None => None,
+                };
+                if let Some(topic) = topic {
+                    record_unsuback(&state, &connection_id, &topic, &ack).await;
+                    ack.topics.push(topic);
+                }
+                emit_event(&app, "mqtt_unsuback", &ack);
+            }
+            Ok(Incoming::Disconnected(reason)) => break reason,
+            Ok(_) => {}
+            Err(e) => {
+                warn!("MQTT connection {} lost: {}", connection_id, e);
+                break Some(e);
+            }
+        }
+    };
+
+    state.tasks.write().await.remove(&connection_id);
+    finish(&app, &connection_id, error).await;
+}
+
+/// 订阅被接受时按授予的 QoS 记录，被拒绝时不记录
+async fn record_suback(state: &MqttToolState, connection_id: &str, subscription: &Subscription, ack: &SubAckResult) {
+    match ack.granted_qos.first().copied().flatten() {
+        Some(qos) => {
+            if let Some(info) = state.connections.write().await.get_mut(connection_id) {
+                info.subscriptions.retain(|s| s.topic != subscription.topic);
+                info.subscriptions.push(Subscription { topic: subscription.topic.clone(), qos });
+            }
+            info!("MQTT {} subscribed to {} (QoS {})", connection_id, subscription.topic, qos);
+        }
+        None => warn!("MQTT {} subscription to {} rejected by broker", connection_id, subscription.topic),
+    }
+}
+
+/// 取消成功时移除订阅记录，失败时保留
+async fn record_unsuback(state: &MqttToolState, connection_id: &str, topic: &str, ack: &UnsubAckResult) {
+    if ack.success {
+        if let Some(info) = state.connections.write().await.get_mut(connection_id) {
+            info.subscriptions.retain(|s| s.topic != topic);
+        }
+        info!("MQTT {} unsubscribed from {}", connection_id, topic);
+    } else {
+        warn!("MQTT {} unsubscribe from {} rejected by broker", connection_id, topic);
+    }
+}
+
+/// 标记连接已断开并通知前端，重复调用时只通知一次
+async fn finish<R: Runtime>(app: &AppHandle<R>, connection_id: &str, error: Option<String>) {
+    let state = app.state::<MqttToolState>();
+    state.clients.write().await.remove(connection_id);
+    state.pending_subscriptions.lock().await.remove(connection_id);
+    state.pending_unsubscriptions.lock().await.remove(connection_id);
+
+    let info = {
+        let mut connections = state.connections.write().await;
+        match connections.get_mut(connection_id) {
+            Some(info) if info.status != "disconnected" => {
+                info.status = "disconnected".to_string();
+                info.error = error;
+                Some(info.clone())
+            }
+            _ => None,
+        }
+    };
+    if let Some(info) = info {
+        emit_event(app, "mqtt_disconnected", &info);
+    }
+}
+
+async fn client<R: Runtime>(app: &AppHandle<R>, connection_id: &str) -> Result<Client, String> {
+    let state = app.state::<MqttToolState>();
+    let client = state.clients.read().await.get(connection_id).cloned();
+    client.ok_or_else(|| "Connection not found".to_string())
+}
+
+#[tauri::command]
+pub(crate) async fn mqtt_disconnect<R: Runtime>(
+    app: AppHandle<R>,
+    connection_id: String,
+) -> Result<(), String> {
+    let state = app.state::<MqttToolState>();
+    if let Ok(client) = client(&app, &connection_id).await {
+        let result = match client {
+            Client::V4(client) => client.disconnect().await.map_err(|e| e.to_string()),
+            Client::V5(client) => client.disconnect().await.map_err(|e| e.to_string()),
+        };
+        if let Err(e) = result {
+            warn!("MQTT disconnect request for {} failed: {}", connection_id, e);
+        }
+    }
+
+    // 等待后台循环发出 DISCONNECT，超时则直接终止
+    let task = state.tasks.write().await.remove(&connection_id);
+    if let Some(mut task) = task {
+        if tokio::time::timeout(Duration::from_secs(1), &mut task).await.is_err() {
+            task.abort();
+        }
+    }
+    finish(&app, &connection_id, None).await;
+
+    info!("MQTT connection {} closed", connection_id);
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) async fn mqtt_subscribe<R: Runtime>(
+    app: AppHandle<R>,
+    connection_id: String,
+    topic: String,
+    qos: Option<u8>,
+) -> Result<(), String> {
+    validate_filter(&topic)?;
+    let qos = qos.unwrap_or(0);
+    let client = client(&app, &connection_id).await?;
+
+    // 收到 SUBACK 后才记录订阅；排队和提交在同一把锁内完成，保证与发出顺序一致
+    let state = app.state::<MqttToolState>();
+    let mut pending = state.pending_subscriptions.lock().await;
+    let pending = pending.entry(connection_id.clone()).or_default();
+    pending.queue(Subscription { topic: topic.clone(), qos });
+    let result = match client {
+        Client::V4(client) => qos_v4(qos).and_then(|level| client.try_subscribe(&topic, level).map_err(|e| e.to_string())),
+        Client::V5(client) => qos_v5(qos).and_then(|level| client.try_subscribe(&topic, level).map_err(|e| e.to_string())),
+    };
+    if let Err(e) = result {
+        pending.unqueue();
+        return Err(format!("Subscribe failed: {}", e));
+    }
+
+    info!("MQTT {} subscribing to {} (QoS {})", connection_id, topic, qos);
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) async fn mqtt_unsubscribe<R: Runtime>(
+    app: AppHandle<R>,
+    connection_id: String,
+    topic: String,
+) -> Result<(), String> {
+    let client = client(&app, &connection_id).await?;
+
+    // 收到 UNSUBACK 后才移除订阅，与订阅时相同
+    let state = app.state::<MqttToolState>();
+    let mut pending = state.pending_unsubscriptions.lock().await;
+    let pending = pending.entry(connection_id.clone()).or_default();
+    pending.queue(topic.clone());
+    let result = match client {
+        Client::V4(client) => client.try_unsubscribe(&topic).map_err(|e| e.to_string()),
+        Client::V5(client) => client.try_unsubscribe(&topic).map_err(|e| e.to_string()),
+    };
+    if let Err(e) = result {
+        pending.unqueue();
+        return Err(format!("Unsubscribe failed: {}", e));
+    }
+
+    info!("MQTT {} unsubscribing from {}", connection_id, topic);
+    Ok(())
+}
+
+/// 发布消息；`properties` 仅在 MQTT 5.0 连接上可用
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn mqtt_publish<R: Runtime>(
+    app: AppHandle<R>,
+    connection_id: String,
+    topic: String,
+    payload: String,
+    encoding: Option<String>,
+    qos: Option<u8>,
+    retain: Option<bool>,
+    properties: Option<MessageProperties>,
+) -> Result<(), String> {
+    validate_topic(&topic)?;
+    let data = decode(&payload, encoding.as_deref())?;
+    let qos = qos.unwrap_or(0);
+    let retain = retain.unwrap_or(false);
+
+    let result = match client(&app, &connection_id).await? {
+        Client::V4(client) => {
+            if properties.is_some() {
+                return Err("Message properties require MQTT 5.0".to_string());
+            }
+            client.publish(&topic, qos_v4(qos)?, retain, data.clone()).await.map_err(|e| e.to_string())
+        }
+        Client::V5(client) => match &properties {
+            Some(properties) => client
+                .publish_with_properties(&topic, qos_v5(qos)?, retain, data.clone(), properties.to_publish()?)
+                .await
+                .map_err(|e| e.to_string()),
+            None => client.publish(&topic, qos_v5(qos)?, retain, data.clone()).await.map_err(|e| e.to_string()),
+        },
+    };
+    result.map_err(|e| format!("Publish failed: {}", e))?;
+
+    let state = app.state::<MqttToolState>();
+    if let Some(info) = state.connections.write().await.get_mut(&connection_id) {
+        info.messages_sent += 1;
+    }
+    let mut message = MqttMessage::new(&connection_id, topic, &data, qos, retain, "sent");
+    message.properties = properties;
+    emit_event(&app, "mqtt_message", &message);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_broker;
+    use std::net::SocketAddr;
+
+    const WAIT: Duration = Duration::from_secs(5);
+
+    fn options(protocol_version: ProtocolVersion) -> MqttConnectOptions {
+        MqttConnectOptions {
+            host: None,
+            port: None,
+            client_id: None,
+            protocol_version,
+            username: None,
+            password: None,
+            keep_alive_secs: None,
+            clean_session: true,
+            tls: None,
+            last_will: None,
+        }
+    }
+
+    async fn connect(options: &MqttConnectOptions, client_id: &str, broker: SocketAddr) -> (Client, EventLoop) {
+        let (client, mut eventloop) = new_client(options, client_id, &broker.ip().to_string(), broker.port(), 30).unwrap();
+        tokio::time::timeout(WAIT, async {
+            while !matches!(eventloop.poll(client_id).await.unwrap(), Incoming::ConnAck(_)) {}
+        })
+        .await
+        .expect("no CONNACK");
+        (client, eventloop)
+    }
+
+    async fn next_suback(eventloop: &mut EventLoop) -> SubAckResult {
+        tokio::time::timeout(WAIT, async {
+            loop {
+                if let Incoming::SubAck(ack) = eventloop.poll("test").await.unwrap() {
+                    return ack;
+                }
+            }
+        })
+        .await
+        .expect("no SUBACK")
+    }
+
+    async fn next_message(eventloop: &mut EventLoop) -> MqttMessage {
+        tokio::time::timeout(WAIT, async {
+            loop {
+                if let Incoming::Message(message) = eventloop.poll("test").await.unwrap() {
+                    return *message;
+                }
+            }
+        })
+        .await
+        .expect("no message")
+    }
+
+    #[tokio::test]
+    async fn v5_publish_subscribe_retain_and_last_will() {
+        let broker = test_broker::start().await;
+
+        let (subscriber, mut subscriber_loop) = connect(&options(ProtocolVersion::V5), "subscriber", broker).await;
+        let Client::V5(subscriber) = subscriber else { unreachable!() };
+        subscriber.subscribe("gearbox/+/temp", qos_v5(1).unwrap()).await.unwrap();
+        assert_eq!(next_suback(&mut subscriber_loop).await.granted_qos, vec![Some(1)]);
+        subscriber.subscribe("gearbox/will/#", qos_v5(1).unwrap()).await.unwrap();
+        assert_eq!(next_suback(&mut subscriber_loop).await.granted_qos, vec![Some(1)]);
+
+        let mut publisher_options = options(ProtocolVersion::V5);
+        publisher_options.last_will = Some(LastWillOptions {
+            topic: "gearbox/will/publisher".to_string(),
+            payload: "offline".to_string(),
+            encoding: None,
+            qos: 1,
+            retain: false,
+            delay_interval: None,
+            properties: Some(MessageProperties {
+                content_type: Some("text/plain".to_string()),
+                ..Default::default()
+            }),
+        });
+        let (publisher, mut publisher_loop) = connect(&publisher_options, "publisher", broker).await;
+        let Client::V5(publisher) = publisher else { unreachable!() };
+        let driver = tokio::spawn(async move { while publisher_loop.poll("publisher").await.is_ok() {} });
+
+        let properties = MessageProperties {
+            message_expiry_interval: Some(60),
+            content_type: Some("application/json".to_string()),
+            correlation_data: Some("01 02".to_string()),
+            user_properties: vec![("unit".to_string(), "C".to_string())],
+            ..Default::default()
+        };
+        publisher
+            .publish_with_properties("gearbox/sensors/temp", qos_v5(1).unwrap(), true, "21.5", properties.to_publish().unwrap())
+            .await
+            .unwrap();
+
+        let message = next_message(&mut subscriber_loop).await;
+        assert_eq!(message.topic, "gearbox/sensors/temp");
+        assert_eq!(message.payload, "21.5");
+        assert_eq!(message.qos, 1);
+        let received = message.properties.expect("missing properties");
+        assert_eq!(received.content_type.as_deref(), Some("application/json"));
+        assert_eq!(received.correlation_data.as_deref(), Some("01 02"));
+        assert_eq!(received.user_properties, vec![("unit".to_string(), "C".to_string())]);
+        assert_eq!(received.message_expiry_interval, Some(60));
+
+        // 之后订阅的客户端收到保留消息
+        let (late, mut late_loop) = connect(&options(ProtocolVersion::V5), "late", broker).await;
+        let Client::V5(late) = late else { unreachable!() };
+        late.subscribe("gearbox/#", qos_v5(1).unwrap()).await.unwrap();
+        let retained = next_message(&mut late_loop).await;
+        assert_eq!(retained.topic, "gearbox/sensors/temp");
+        assert!(retained.retain);
+
+        // 发布者异常断开，broker 发布遗嘱
+        driver.abort();
+        let will = next_message(&mut subscriber_loop).await;
+        assert_eq!(will.topic, "gearbox/will/publisher");
+        assert_eq!(will.payload, "offline");
+        assert_eq!(will.properties.and_then(|p| p.content_type).as_deref(), Some("text/plain"));
+    }
+
+    #[tokio::test]
+    async fn v311_suback_reports_rejected_filters() {
+        let broker = test_broker::start().await;
+        let (client, mut eventloop) = connect(&options(ProtocolVersion::V311), "v311", broker).await;
+        let Client::V4(client) = client else { unreachable!() };
+
+        client.subscribe("denied/#", qos_v4(1).unwrap()).await.unwrap();
+        assert_eq!(next_suback(&mut eventloop).await.granted_qos, vec![None]);
+        client.subscribe("sensors/+/temp", qos_v4(1).unwrap()).await.unwrap();
+        assert_eq!(next_suback(&mut eventloop).await.granted_qos, vec![Some(1)]);
+
+        client.publish("sensors/a/temp", qos_v4(0).unwrap(), false, "ok").await.unwrap();
+        let message = next_message(&mut eventloop).await;
+        assert_eq!(message.topic, "sensors/a/temp");
+        assert_eq!(message.payload, "ok");
+    }
+
+    #[tokio::test]
+    async fn pending_subscriptions_match_subacks_by_packet_id() {
+        let broker = test_broker::start().await;
+        let (client, mut eventloop) = connect(&options(ProtocolVersion::V5), "pending", broker).await;
+        let Client::V5(client) = client else { unreachable!() };
+
+        let mut pending = PendingRequests::default();
+        for (topic, qos) in [("denied/#", 1), ("sensors/#", 1)] {
+            pending.queue(Subscription { topic: topic.to_string(), qos });
+            client.try_subscribe(topic, qos_v5(qos).unwrap()).unwrap();
+        }
+
+        let mut acked = Vec::new();
+        tokio::time::timeout(WAIT, async {
+            while acked.len() < 2 {
+                match eventloop.poll("pending").await.unwrap() {
+                    Incoming::SubscribeSent(packet_id) => pending.sent(packet_id),
+                    Incoming::SubAck(ack) => {
+                        let subscription = pending.acked(ack.packet_id).expect("unknown SUBACK");
+                        acked.push((subscription.topic, ack.granted_qos));
+                    }
+                    _ => {}
+                }
+            }
+        })
+        .await
+        .expect("no SUBACK");
+
+        assert_eq!(
+            acked,
+            vec![("denied/#".to_string(), vec![None]), ("sensors/#".to_string(), vec![Some(1)])]
+        );
+    }
+
+    #[tokio::test]
+    async fn pending_unsubscriptions_match_unsubacks_by_packet_id() {
+        let broker = test_broker::start().await;
+        let (client, mut eventloop) = connect(&options(ProtocolVersion::V5), "unsubscribe", broker).await;
+        let Client::V5(client) = client else { unreachable!() };
+        client.subscribe("sensors/#", qos_v5(1).unwrap()).await.unwrap();
+        next_suback(&mut eventloop).await;
+
+        let mut pending = PendingRequests::default();
+        for topic in ["sensors/#", "alarms/#", "denied/#"] {
+            pending.queue(topic.to_string());
+            client.try_unsubscribe(topic).unwrap();
+        }
+
+        let mut acked = Vec::new();
+        tokio::time::timeout(WAIT, async {
+            while acked.len() < 3 {
+                match eventloop.poll("unsubscribe").await.unwrap() {
+                    Incoming::UnsubscribeSent(packet_id) => pending.sent(packet_id),
+                    Incoming::UnsubAck(ack) => acked.push((pending.acked(ack.packet_id).expect("unknown UNSUBACK"), ack.success)),
+                    _ => {}
+                }
+            }
+        })
+        .await
+        .expect("no UNSUBACK");
+
+        assert_eq!(
+            acked,
+            vec![("sensors/#".to_string(), true), ("alarms/#".to_string(), true), ("denied/#".to_string(), false)]
+        );
+    }
+}
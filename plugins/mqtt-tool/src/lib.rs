@@ -0,0 +1,178 @@
+mod client;
+#[cfg(test)]
+mod test_broker;
+mod tls;
+
+use serde::{Deserialize, Serialize};
+use tauri::{plugin::TauriPlugin, Runtime, Manager, AppHandle, Emitter};
+use tracing::{info, warn};
+use tokio::task::JoinHandle;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tokio::sync::{Mutex, RwLock};
+
+pub use client::{LastWillOptions, MessageProperties, MqttConnectOptions, MqttMessage, ProtocolVersion, SubAckResult, UnsubAckResult};
+pub use tls::TlsOptions;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MqttToolConfig {
+    pub enabled: bool,
+    pub default_host: String,
+    pub default_port: u16,
+    /// 默认的 keep alive 间隔（秒）
+    pub keep_alive_secs: u64,
+    /// 等待 CONNACK 的超时时间（毫秒）
+    pub connection_timeout: u64,
+}
+
+impl Default for MqttToolConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            default_host: "127.0.0.1".to_string(),
+            default_port: 1883,
+            keep_alive_secs: 60,
+            connection_timeout: 5000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Subscription {
+    pub topic: String,
+    pub qos: u8,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MqttConnectionInfo {
+    pub id: String,
+    pub host: String,
+    pub port: u16,
+    pub client_id: String,
+    pub protocol_version: ProtocolVersion,
+    pub tls: bool,
+    pub status: String, // "connected" or "disconnected"
+    pub created_at: String,
+    /// CONNACK 中的 session present 标志
+    pub session_present: bool,
+    pub subscriptions: Vec<Subscription>,
+    pub messages_sent: u64,
+    pub messages_received: u64,
+    /// 连接异常断开的原因
+    pub error: Option<String>,
+}
+
+pub struct MqttToolState {
+    config: RwLock<MqttToolConfig>,
+    clients: RwLock<HashMap<String, client::Client>>,
+    tasks: RwLock<HashMap<String, JoinHandle<()>>>,
+    pending_subscriptions: Mutex<HashMap<String, client::PendingRequests<Subscription>>>,
+    pending_unsubscriptions: Mutex<HashMap<String, client::PendingRequests<String>>>,
+    connections: RwLock<HashMap<String, MqttConnectionInfo>>,
+}
+
+impl MqttToolState {
+    pub fn new(config: MqttToolConfig) -> Self {
+        Self {
+            config: RwLock::new(config),
+            clients: RwLock::new(HashMap::new()),
+            tasks: RwLock::new(HashMap::new()),
+            pending_subscriptions: Mutex::new(HashMap::new()),
+            pending_unsubscriptions: Mutex::new(HashMap::new()),
+            connections: RwLock::new(HashMap::new()),
+        }
+    }
+}
+
+fn emit_event<R: Runtime, S: Serialize + Clone>(app: &AppHandle<R>, event: &str, payload: S) {
+    if let Err(e) = app.emit(event, payload) {
+        warn!("Failed to emit {}: {}", event, e);
+    }
+}
+
+#[tauri::command]
+async fn get_config<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<MqttToolConfig, String> {
+    let state = app.state::<MqttToolState>();
+    let config = state.config.read().await.clone();
+    Ok(config)
+}
+
+#[tauri::command]
+async fn update_config<R: Runtime>(
+    app: AppHandle<R>,
+    config: MqttToolConfig,
+) -> Result<(), String> {
+    let state = app.state::<MqttToolState>();
+    info!("mqtt-tool config updated: {:?}", config);
+    *state.config.write().await = config;
+    save_config(&app).await
+}
+
+fn config_path<R: Runtime>(app: &AppHandle<R>) -> Option<PathBuf> {
+    app.path().app_config_dir().ok().map(|dir| dir.join("mqtt-tool.json"))
+}
+
+/// 读取保存的配置，文件不存在或无法解析时使用默认配置
+fn load_config<R: Runtime>(app: &AppHandle<R>) -> MqttToolConfig {
+    let Some(path) = config_path(app) else {
+        return MqttToolConfig::default();
+    };
+    match std::fs::read_to_string(&path) {
+        Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+            warn!("Invalid mqtt-tool config {}: {}", path.display(), e);
+            MqttToolConfig::default()
+        }),
+        Err(_) => MqttToolConfig::default(),
+    }
+}
+
+async fn save_config<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    let path = config_path(app).ok_or_else(|| "Config directory is unavailable".to_string())?;
+    let state = app.state::<MqttToolState>();
+    let contents = serde_json::to_string_pretty(&*state.config.read().await)
+        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir)
+            .await
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    tokio::fs::write(&path, contents)
+        .await
+        .map_err(|e| format!("Failed to save config to {}: {}", path.display(), e))
+}
+
+#[tauri::command]
+async fn mqtt_get_connections<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<Vec<MqttConnectionInfo>, String> {
+    let state = app.state::<MqttToolState>();
+    let connections = state.connections.read().await.values().cloned().collect();
+    Ok(connections)
+}
+
+pub fn init<R: Runtime>() -> TauriPlugin<R> {
+    tauri::plugin::Builder::new("mqtt-tool")
+        .invoke_handler(tauri::generate_handler![
+            get_config,
+            update_config,
+            mqtt_get_connections,
+            client::mqtt_connect,
+            client::mqtt_disconnect,
+            client::mqtt_subscribe,
+            client::mqtt_unsubscribe,
+            client::mqtt_publish
+        ])
+        .setup(|app, _api| {
+            // 初始化插件状态，优先使用保存的配置
+            let config = load_config(app);
+            let state = MqttToolState::new(config);
+            app.manage(state);
+
+            info!("mqtt-tool plugin initialized");
+            Ok(())
+        })
+        .build()
+}
@@ -0,0 +1,348 @@
+//! 测试用的进程内 MQTT broker，支持 3.1.1 和 5.0 的 CONNECT（含遗嘱）、SUBSCRIBE、UNSUBSCRIBE、PUBLISH（QoS 0/1、保留消息）和 DISCONNECT。
+//! 以 "denied/" 开头的订阅和取消订阅会被拒绝，用于测试 SUBACK/UNSUBACK 的失败码
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::tcp::OwnedReadHalf;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+
+#[derive(Clone)]
+struct Publish {
+    topic: String,
+    qos: u8,
+    /// MQTT 5.0 属性的原始字节，不含长度前缀
+    properties: Vec<u8>,
+    payload: Vec<u8>,
+}
+
+struct Session {
+    id: usize,
+    version: u8,
+    sender: UnboundedSender<Vec<u8>>,
+    filters: Vec<(String, u8)>,
+}
+
+#[derive(Default)]
+struct Broker {
+    sessions: Vec<Session>,
+    retained: HashMap<String, Publish>,
+    next_packet_id: u16,
+}
+
+impl Broker {
+    fn packet_id(&mut self) -> u16 {
+        self.next_packet_id = self.next_packet_id.wrapping_add(1).max(1);
+        self.next_packet_id
+    }
+
+    /// 把消息转发给所有匹配的订阅，QoS 取发布和订阅中较小的一个
+    fn route(&mut self, publish: &Publish) {
+        let targets: Vec<(u8, UnboundedSender<Vec<u8>>, u8)> = self
+            .sessions
+            .iter()
+            .filter_map(|session| {
+                let granted = session
+                    .filters
+                    .iter()
+                    .filter(|(filter, _)| matches(filter, &publish.topic))
+                    .map(|(_, qos)| *qos)
+                    .max()?;
+                Some((session.version, session.sender.clone(), granted))
+            })
+            .collect();
+        for (version, sender, granted) in targets {
+            let packet_id = self.packet_id();
+            let _ = sender.send(encode_publish(version, publish, publish.qos.min(granted), false, packet_id));
+        }
+    }
+}
+
+type Shared = Arc<Mutex<Broker>>;
+
+/// 主题过滤器匹配，支持 `+` 和 `#`
+fn matches(filter: &str, topic: &str) -> bool {
+    let mut topic_levels = topic.split('/');
+    for level in filter.split('/') {
+        match (level, topic_levels.next()) {
+            ("#", _) => return true,
+            ("+", Some(_)) => {}
+            (level, Some(actual)) if level == actual => {}
+            _ => return false,
+        }
+    }
+    topic_levels.next().is_none()
+}
+
+fn put_varint(out: &mut Vec<u8>, mut value: usize) {
+    loop {
+        let mut byte = (value % 128) as u8;
+        value /= 128;
+        if value > 0 {
+            byte |= 0x80;
+        }
+        out.push(byte);
+        if value == 0 {
+            break;
+        }
+    }
+}
+
+fn put_str(out: &mut Vec<u8>, value: &[u8]) {
+    out.extend_from_slice(&(value.len() as u16).to_be_bytes());
+    out.extend_from_slice(value);
+}
+
+fn packet(header: u8, body: &[u8]) -> Vec<u8> {
+    let mut out = vec![header];
+    put_varint(&mut out, body.len());
+    out.extend_from_slice(body);
+    out
+}
+
+fn encode_publish(version: u8, publish: &Publish, qos: u8, retain: bool, packet_id: u16) -> Vec<u8> {
+    let mut body = Vec::new();
+    put_str(&mut body, publish.topic.as_bytes());
+    if qos > 0 {
+        body.extend_from_slice(&packet_id.to_be_bytes());
+    }
+    if version == 5 {
+        put_varint(&mut body, publish.properties.len());
+        body.extend_from_slice(&publish.properties);
+    }
+    body.extend_from_slice(&publish.payload);
+    packet(0x30 | (qos << 1) | u8::from(retain), &body)
+}
+
+/// 按顺序读取报文体中的各个部分
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> &'a [u8] {
+        let (head, rest) = self.data.split_at(n);
+        self.data = rest;
+        head
+    }
+
+    fn u8(&mut self) -> u8 {
+        self.take(1)[0]
+    }
+
+    fn u16(&mut self) -> u16 {
+        let bytes = self.take(2);
+        u16::from_be_bytes([bytes[0], bytes[1]])
+    }
+
+    fn varint(&mut self) -> usize {
+        let (mut value, mut shift) = (0usize, 0);
+        loop {
+            let byte = self.u8();
+            value |= usize::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return value;
+            }
+            shift += 7;
+        }
+    }
+
+    fn bytes(&mut self) -> &'a [u8] {
+        let len = self.u16() as usize;
+        self.take(len)
+    }
+
+    fn string(&mut self) -> String {
+        String::from_utf8_lossy(self.bytes()).into_owned()
+    }
+
+    fn properties(&mut self) -> &'a [u8] {
+        let len = self.varint();
+        self.take(len)
+    }
+}
+
+async fn read_packet(reader: &mut OwnedReadHalf) -> Option<(u8, Vec<u8>)> {
+    let header = reader.read_u8().await.ok()?;
+    let (mut length, mut shift) = (0usize, 0);
+    loop {
+        let byte = reader.read_u8().await.ok()?;
+        length |= usize::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            break;
+        }
+        shift += 7;
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).await.ok()?;
+    Some((header, body))
+}
+
+async fn handle(broker: Shared, id: usize, stream: TcpStream) {
+    let (mut reader, mut writer) = stream.into_split();
+    let (sender, mut receiver) = unbounded_channel::<Vec<u8>>();
+    tokio::spawn(async move {
+        while let Some(data) = receiver.recv().await {
+            if writer.write_all(&data).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let Some((0x10, body)) = read_packet(&mut reader).await else {
+        return;
+    };
+    let mut connect = Reader { data: &body };
+    connect.string();
+    let version = connect.u8();
+    let flags = connect.u8();
+    connect.u16();
+    if version == 5 {
+        connect.properties();
+    }
+    connect.string();
+    let will = (flags & 0x04 != 0).then(|| {
+        let properties = if version == 5 { connect.properties().to_vec() } else { Vec::new() };
+        let topic = connect.string();
+        let payload = connect.bytes().to_vec();
+        let retain = flags & 0x20 != 0;
+        // 遗嘱属性中的延迟发送时间（0x18）不能出现在 PUBLISH 中，测试中不使用
+        (Publish { topic, qos: (flags >> 3) & 0x03, properties, payload }, retain)
+    });
+    let connack = if version == 5 { vec![0, 0, 0] } else { vec![0, 0] };
+    let _ = sender.send(packet(0x20, &connack));
+    broker.lock().unwrap().sessions.push(Session {
+        id,
+        version,
+        sender: sender.clone(),
+        filters: Vec::new(),
+    });
+
+    let mut clean = false;
+    while let Some((header, body)) = read_packet(&mut reader).await {
+        let mut body = Reader { data: &body };
+        match header >> 4 {
+            3 => {
+                let qos = (header >> 1) & 0x03;
+                let retain = header & 0x01 != 0;
+                let topic = body.string();
+                let packet_id = if qos > 0 { body.u16() } else { 0 };
+                let properties = if version == 5 { body.properties().to_vec() } else { Vec::new() };
+                let publish = Publish { topic, qos, properties, payload: body.data.to_vec() };
+                if qos > 0 {
+                    let _ = sender.send(packet(0x40, &packet_id.to_be_bytes()));
+                }
+                let mut broker = broker.lock().unwrap();
+                if retain {
+                    broker.retained.insert(publish.topic.clone(), publish.clone());
+                }
+                broker.route(&publish);
+            }
+            8 => {
+                let packet_id = body.u16();
+                if version == 5 {
+                    body.properties();
+                }
+                let mut suback = packet_id.to_be_bytes().to_vec();
+                if version == 5 {
+                    suback.push(0);
+                }
+                let mut granted = Vec::new();
+                while !body.data.is_empty() {
+                    let filter = body.string();
+                    let qos = body.u8() & 0x03;
+                    if filter.starts_with("denied/") {
+                        suback.push(0x80);
+                    } else {
+                        suback.push(qos);
+                        granted.push((filter, qos));
+                    }
+                }
+                let _ = sender.send(packet(0x90, &suback));
+
+                let mut broker = broker.lock().unwrap();
+                let retained: Vec<Publish> = broker
+                    .retained
+                    .values()
+                    .filter(|publish| granted.iter().any(|(filter, _)| matches(filter, &publish.topic)))
+                    .cloned()
+                    .collect();
+                for publish in retained {
+                    let qos = granted
+                        .iter()
+                        .filter(|(filter, _)| matches(filter, &publish.topic))
+                        .map(|(_, qos)| *qos)
+                        .max()
+                        .unwrap_or_default();
+                    let packet_id = broker.packet_id();
+                    let _ = sender.send(encode_publish(version, &publish, publish.qos.min(qos), true, packet_id));
+                }
+                if let Some(session) = broker.sessions.iter_mut().find(|session| session.id == id) {
+                    session.filters.extend(granted);
+                }
+            }
+            10 => {
+                let packet_id = body.u16();
+                if version == 5 {
+                    body.properties();
+                }
+                let mut unsuback = packet_id.to_be_bytes().to_vec();
+                if version == 5 {
+                    unsuback.push(0);
+                }
+                let mut broker = broker.lock().unwrap();
+                let Some(session) = broker.sessions.iter_mut().find(|session| session.id == id) else {
+                    continue;
+                };
+                while !body.data.is_empty() {
+                    let filter = body.string();
+                    let existed = session.filters.iter().any(|(f, _)| *f == filter);
+                    session.filters.retain(|(f, _)| *f != filter);
+                    if version == 5 {
+                        unsuback.push(match (filter.starts_with("denied/"), existed) {
+                            (true, _) => 0x87,
+                            (false, true) => 0x00,
+                            (false, false) => 0x11,
+                        });
+                    }
+                }
+                let _ = sender.send(packet(0xb0, &unsuback));
+            }
+            12 => {
+                let _ = sender.send(vec![0xd0, 0]);
+            }
+            14 => {
+                clean = true;
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    let mut broker = broker.lock().unwrap();
+    broker.sessions.retain(|session| session.id != id);
+    // 连接异常断开时发布遗嘱
+    if let Some((will, retain)) = will.filter(|_| !clean) {
+        if retain {
+            broker.retained.insert(will.topic.clone(), will.clone());
+        }
+        broker.route(&will);
+    }
+}
+
+/// 在 127.0.0.1 的随机端口上启动 broker，返回监听地址
+pub async fn start() -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let broker = Shared::default();
+    tokio::spawn(async move {
+        let mut next_id = 0;
+        while let Ok((stream, _)) = listener.accept().await {
+            next_id += 1;
+            tokio::spawn(handle(broker.clone(), next_id, stream));
+        }
+    });
+    addr
+}
@@ -0,0 +1,56 @@
+use network_common::tls::{load_certs, load_key, NoVerification};
+use rustls::crypto::ring;
+use rustls::{ClientConfig, RootCertStore};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// MQTT over TLS 选项，SNI 使用连接的主机名
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TlsOptions {
+    /// 自定义 CA 证书（PEM），为空时使用内置的根证书
+    pub ca_file: Option<String>,
+    /// 跳过服务端证书校验，仅用于测试
+    pub insecure: bool,
+    pub client_cert_file: Option<String>,
+    pub client_key_file: Option<String>,
+    pub alpn_protocols: Vec<String>,
+}
+
+/// 根据选项构造 rustls 客户端配置，交给 rumqttc 完成握手
+pub fn client_config(options: &TlsOptions) -> Result<Arc<ClientConfig>, String> {
+    let provider = Arc::new(ring::default_provider());
+    let builder = ClientConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()
+        .map_err(|e| format!("Invalid TLS configuration: {}", e))?;
+
+    let builder = if options.insecure {
+        builder
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(NoVerification(provider)))
+    } else {
+        let mut roots = RootCertStore::empty();
+        match &options.ca_file {
+            Some(path) => {
+                for cert in load_certs(path)? {
+                    roots
+                        .add(cert)
+                        .map_err(|e| format!("Invalid CA certificate in {}: {}", path, e))?;
+                }
+            }
+            None => roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned()),
+        }
+        builder.with_root_certificates(roots)
+    };
+
+    let mut config = match (&options.client_cert_file, &options.client_key_file) {
+        (Some(cert), Some(key)) => builder
+            .with_client_auth_cert(load_certs(cert)?, load_key(key)?)
+            .map_err(|e| format!("Invalid client certificate: {}", e))?,
+        (None, None) => builder.with_no_client_auth(),
+        _ => return Err("Client certificate and key must be provided together".to_string()),
+    };
+    config.alpn_protocols = options.alpn_protocols.iter().map(|p| p.as_bytes().to_vec()).collect();
+
+    Ok(Arc::new(config))
+}
@@ -0,0 +1,168 @@
+mod sockets;
+
+use serde::{Deserialize, Serialize};
+use tauri::{plugin::TauriPlugin, Runtime, Manager, AppHandle};
+use tracing::{info, warn};
+use std::path::PathBuf;
+use tokio::sync::RwLock;
+
+pub use sockets::{list_sockets, SocketEntry, SocketFilter};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetstatConfig {
+    pub enabled: bool,
+    /// 查找套接字所属的进程，需要遍历 /proc，进程较多时较慢
+    pub resolve_processes: bool,
+}
+
+impl Default for NetstatConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            resolve_processes: true,
+        }
+    }
+}
+
+pub struct NetstatState {
+    config: RwLock<NetstatConfig>,
+}
+
+impl NetstatState {
+    pub fn new(config: NetstatConfig) -> Self {
+        Self { config: RwLock::new(config) }
+    }
+}
+
+/// 描述占用本地端口的进程，如 "python3 (PID 1234)"，供其他工具在端口被占用时提示。
+/// `protocol` 为 "tcp" 或 "udp"；找不到监听的套接字时返回 `None`
+pub fn describe_port_owner(protocol: &str, port: u16) -> Option<String> {
+    let filter = SocketFilter {
+        protocol: Some(protocol.to_string()),
+        listening: true,
+        ..Default::default()
+    };
+    let entries = list_sockets(true).ok()?;
+    let mut owners = entries.iter().filter(|entry| entry.local_port == port && filter.matches(entry));
+    let entry = owners.next()?;
+    Some(
+        entry
+            .owner()
+            .or_else(|| owners.find_map(SocketEntry::owner))
+            .unwrap_or_else(|| format!("a process of uid {}", entry.uid)),
+    )
+}
+
+#[tauri::command]
+async fn get_config<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<NetstatConfig, String> {
+    let state = app.state::<NetstatState>();
+    let config = state.config.read().await.clone();
+    Ok(config)
+}
+
+#[tauri::command]
+async fn update_config<R: Runtime>(
+    app: AppHandle<R>,
+    config: NetstatConfig,
+) -> Result<(), String> {
+    let state = app.state::<NetstatState>();
+    info!("netstat config updated: {:?}", config);
+    *state.config.write().await = config;
+    save_config(&app).await
+}
+
+fn config_path<R: Runtime>(app: &AppHandle<R>) -> Option<PathBuf> {
+    app.path().app_config_dir().ok().map(|dir| dir.join("netstat.json"))
+}
+
+/// 读取保存的配置，文件不存在或无法解析时使用默认配置
+fn load_config<R: Runtime>(app: &AppHandle<R>) -> NetstatConfig {
+    let Some(path) = config_path(app) else {
+        return NetstatConfig::default();
+    };
+    match std::fs::read_to_string(&path) {
+        Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+            warn!("Invalid netstat config {}: {}", path.display(), e);
+            NetstatConfig::default()
+        }),
+        Err(_) => NetstatConfig::default(),
+    }
+}
+
+async fn save_config<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    let path = config_path(app).ok_or_else(|| "Config directory is unavailable".to_string())?;
+    let state = app.state::<NetstatState>();
+    let contents = serde_json::to_string_pretty(&*state.config.read().await)
+        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir)
+            .await
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    tokio::fs::write(&path, contents)
+        .await
+        .map_err(|e| format!("Failed to save config to {}: {}", path.display(), e))
+}
+
+/// 列出本机套接字，按协议和本地端口排序
+#[tauri::command]
+async fn netstat_list<R: Runtime>(
+    app: AppHandle<R>,
+    filter: Option<SocketFilter>,
+) -> Result<Vec<SocketEntry>, String> {
+    let state = app.state::<NetstatState>();
+    let resolve_processes = state.config.read().await.resolve_processes;
+    let filter = filter.unwrap_or_default();
+
+    // 读取 /proc 是阻塞操作
+    let mut entries = tokio::task::spawn_blocking(move || list_sockets(resolve_processes))
+        .await
+        .map_err(|e| e.to_string())??;
+    entries.retain(|entry| filter.matches(entry));
+    entries.sort_by(|a, b| (&a.protocol, a.local_port, &a.state).cmp(&(&b.protocol, b.local_port, &b.state)));
+    Ok(entries)
+}
+
+/// 查找监听指定本地端口的套接字，即“谁在占用这个端口”
+#[tauri::command]
+async fn netstat_port_owners<R: Runtime>(
+    _app: AppHandle<R>,
+    port: u16,
+    protocol: Option<String>,
+) -> Result<Vec<SocketEntry>, String> {
+    let filter = SocketFilter {
+        protocol,
+        listening: true,
+        ..Default::default()
+    };
+    let entries = tokio::task::spawn_blocking(|| list_sockets(true))
+        .await
+        .map_err(|e| e.to_string())??;
+    Ok(entries
+        .into_iter()
+        .filter(|entry| entry.local_port == port && filter.matches(entry))
+        .collect())
+}
+
+pub fn init<R: Runtime>() -> TauriPlugin<R> {
+    tauri::plugin::Builder::new("netstat")
+        .invoke_handler(tauri::generate_handler![
+            get_config,
+            update_config,
+            netstat_list,
+            netstat_port_owners
+        ])
+        .setup(|app, _api| {
+            // 初始化插件状态，优先使用保存的配置
+            let config = load_config(app);
+            let state = NetstatState::new(config);
+            app.manage(state);
+
+            info!("netstat plugin initialized");
+            Ok(())
+        })
+        .build()
+}
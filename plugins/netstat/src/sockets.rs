@@ -0,0 +1,235 @@
+use serde::{Deserialize, Serialize};
+
+/// 一个本地套接字
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SocketEntry {
+    /// "tcp"、"tcp6"、"udp" 或 "udp6"
+    pub protocol: String,
+    pub local_address: String,
+    pub local_port: u16,
+    pub remote_address: String,
+    pub remote_port: u16,
+    /// TCP 状态，如 "LISTEN"、"ESTABLISHED"；未连接的 UDP 套接字为 "UNCONN"
+    pub state: String,
+    pub uid: u32,
+    pub inode: u64,
+    /// 所属进程，无权限读取其他用户的进程时为空
+    pub pid: Option<u32>,
+    pub process: Option<String>,
+}
+
+/// 套接字过滤条件，未设置的字段不参与过滤
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SocketFilter {
+    /// "tcp" 或 "udp"，同时匹配 IPv4 和 IPv6
+    pub protocol: Option<String>,
+    /// 匹配本地或远端端口
+    pub port: Option<u16>,
+    /// 状态名称，不区分大小写
+    pub state: Option<String>,
+    /// 仅列出监听中的套接字（TCP LISTEN 和未连接的 UDP）
+    pub listening: bool,
+    /// 进程名称（包含匹配）或 PID
+    pub process: Option<String>,
+}
+
+impl SocketEntry {
+    pub fn is_listening(&self) -> bool {
+        self.state == "LISTEN" || self.state == "UNCONN"
+    }
+
+    /// 进程描述，如 "python3 (PID 1234)"
+    pub fn owner(&self) -> Option<String> {
+        match (&self.process, self.pid) {
+            (Some(process), Some(pid)) => Some(format!("{} (PID {})", process, pid)),
+            (None, Some(pid)) => Some(format!("PID {}", pid)),
+            _ => None,
+        }
+    }
+}
+
+impl SocketFilter {
+    pub fn matches(&self, entry: &SocketEntry) -> bool {
+        if let Some(protocol) = &self.protocol {
+            if !entry.protocol.starts_with(&protocol.to_lowercase()) {
+                return false;
+            }
+        }
+        if let Some(port) = self.port {
+            if entry.local_port != port && entry.remote_port != port {
+                return false;
+            }
+        }
+        if let Some(state) = &self.state {
+            if !entry.state.eq_ignore_ascii_case(state) {
+                return false;
+            }
+        }
+        if self.listening && !entry.is_listening() {
+            return false;
+        }
+        if let Some(process) = self.process.as_deref().map(str::trim).filter(|p| !p.is_empty()) {
+            let by_pid = process.parse::<u32>().ok().is_some_and(|pid| entry.pid == Some(pid));
+            let by_name = entry
+                .process
+                .as_ref()
+                .is_some_and(|name| name.to_lowercase().contains(&process.to_lowercase()));
+            if !by_pid && !by_name {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// 列出本机的 TCP/UDP 套接字，`resolve_processes` 为 true 时查找所属进程
+#[cfg(target_os = "linux")]
+pub fn list_sockets(resolve_processes: bool) -> Result<Vec<SocketEntry>, String> {
+    linux::list_sockets(resolve_processes)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn list_sockets(_resolve_processes: bool) -> Result<Vec<SocketEntry>, String> {
+    Err("Socket listing is only supported on Linux".to_string())
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use super::SocketEntry;
+    use std::collections::HashMap;
+    use std::net::{Ipv4Addr, Ipv6Addr};
+
+    /// /proc/net/tcp 中的状态编号
+    fn tcp_state(code: u8) -> &'static str {
+        match code {
+            0x01 => "ESTABLISHED",
+            0x02 => "SYN_SENT",
+            0x03 => "SYN_RECV",
+            0x04 => "FIN_WAIT1",
+            0x05 => "FIN_WAIT2",
+            0x06 => "TIME_WAIT",
+            0x07 => "CLOSE",
+            0x08 => "CLOSE_WAIT",
+            0x09 => "LAST_ACK",
+            0x0A => "LISTEN",
+            0x0B => "CLOSING",
+            _ => "UNKNOWN",
+        }
+    }
+
+    /// 解析 "0100007F:1F90" 形式的地址，地址按内核的本机字节序输出
+    fn parse_address(input: &str) -> Option<(String, u16)> {
+        let (addr, port) = input.split_once(':')?;
+        let port = u16::from_str_radix(port, 16).ok()?;
+        let words = (0..addr.len() / 8)
+            .map(|i| u32::from_str_radix(&addr[i * 8..i * 8 + 8], 16).map(u32::to_ne_bytes))
+            .collect::<Result<Vec<_>, _>>()
+            .ok()?;
+        let address = match words.as_slice() {
+            [word] => Ipv4Addr::from(*word).to_string(),
+            [a, b, c, d] => {
+                let mut bytes = [0u8; 16];
+                for (chunk, word) in bytes.chunks_mut(4).zip([a, b, c, d]) {
+                    chunk.copy_from_slice(word);
+                }
+                let ip = Ipv6Addr::from(bytes);
+                // IPv4 映射地址显示为 IPv4 形式
+                match ip.to_ipv4_mapped() {
+                    Some(v4) => v4.to_string(),
+                    None => ip.to_string(),
+                }
+            }
+            _ => return None,
+        };
+        Some((address, port))
+    }
+
+    fn parse_table(protocol: &str, contents: &str) -> Vec<SocketEntry> {
+        let udp = protocol.starts_with("udp");
+        contents
+            .lines()
+            .skip(1)
+            .filter_map(|line| {
+                let fields: Vec<&str> = line.split_whitespace().collect();
+                let (local_address, local_port) = parse_address(fields.get(1)?)?;
+                let (remote_address, remote_port) = parse_address(fields.get(2)?)?;
+                let state = u8::from_str_radix(fields.get(3)?, 16).ok()?;
+                let state = match (udp, state) {
+                    (true, 0x07) => "UNCONN",
+                    _ => tcp_state(state),
+                };
+                Some(SocketEntry {
+                    protocol: protocol.to_string(),
+                    local_address,
+                    local_port,
+                    remote_address,
+                    remote_port,
+                    state: state.to_string(),
+                    uid: fields.get(7)?.parse().ok()?,
+                    inode: fields.get(9)?.parse().ok()?,
+                    pid: None,
+                    process: None,
+                })
+            })
+            .collect()
+    }
+
+    /// 遍历 /proc/<pid>/fd 建立套接字 inode 到进程的映射，跳过无权限读取的进程
+    fn socket_owners() -> HashMap<u64, (u32, String)> {
+        let mut owners = HashMap::new();
+        let Ok(processes) = std::fs::read_dir("/proc") else {
+            return owners;
+        };
+        for process in processes.flatten() {
+            let Some(pid) = process.file_name().to_str().and_then(|name| name.parse::<u32>().ok()) else {
+                continue;
+            };
+            let Ok(fds) = std::fs::read_dir(process.path().join("fd")) else {
+                continue;
+            };
+            let name = std::fs::read_to_string(process.path().join("comm"))
+                .map(|comm| comm.trim().to_string())
+                .unwrap_or_default();
+            for fd in fds.flatten() {
+                let Ok(target) = std::fs::read_link(fd.path()) else {
+                    continue;
+                };
+                let inode = target
+                    .to_str()
+                    .and_then(|target| target.strip_prefix("socket:["))
+                    .and_then(|target| target.strip_suffix(']'))
+                    .and_then(|inode| inode.parse::<u64>().ok());
+                if let Some(inode) = inode {
+                    owners.entry(inode).or_insert_with(|| (pid, name.clone()));
+                }
+            }
+        }
+        owners
+    }
+
+    pub fn list_sockets(resolve_processes: bool) -> Result<Vec<SocketEntry>, String> {
+        let mut entries = Vec::new();
+        for protocol in ["tcp", "tcp6", "udp", "udp6"] {
+            let path = format!("/proc/net/{}", protocol);
+            match std::fs::read_to_string(&path) {
+                Ok(contents) => entries.extend(parse_table(protocol, &contents)),
+                // 未启用 IPv6 时没有 tcp6/udp6
+                Err(_) if protocol.ends_with('6') => {}
+                Err(e) => return Err(format!("Failed to read {}: {}", path, e)),
+            }
+        }
+
+        if !resolve_processes {
+            return Ok(entries);
+        }
+        let owners = socket_owners();
+        for entry in &mut entries {
+            if let Some((pid, name)) = owners.get(&entry.inode) {
+                entry.pid = Some(*pid);
+                entry.process = Some(name.clone());
+            }
+        }
+        Ok(entries)
+    }
+}
@@ -0,0 +1,171 @@
+mod scan;
+mod target;
+
+use serde::{Deserialize, Serialize};
+use tauri::{plugin::TauriPlugin, Runtime, Manager, AppHandle, Emitter};
+use tracing::{info, warn};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+
+pub use scan::{PortResult, ScanInfo, ScanOptions, ScanProgress};
+pub use target::LocalNetwork;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortScannerConfig {
+    pub enabled: bool,
+    /// 未指定扫描目标时使用，默认扫描本机
+    pub default_targets: String,
+    pub default_ports: String,
+    /// 单个端口的连接超时（毫秒）
+    pub timeout_ms: u64,
+    /// 同时进行的连接数
+    pub concurrency: usize,
+    /// 读取 banner 的等待时间（毫秒）
+    pub banner_timeout_ms: u64,
+}
+
+impl Default for PortScannerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            default_targets: "127.0.0.1".to_string(),
+            default_ports: "1-1024".to_string(),
+            timeout_ms: 500,
+            concurrency: 200,
+            banner_timeout_ms: 1000,
+        }
+    }
+}
+
+pub struct PortScannerState {
+    config: RwLock<PortScannerConfig>,
+    scans: RwLock<HashMap<String, ScanInfo>>,
+    tasks: RwLock<HashMap<String, JoinHandle<()>>>,
+}
+
+impl PortScannerState {
+    pub fn new(config: PortScannerConfig) -> Self {
+        Self {
+            config: RwLock::new(config),
+            scans: RwLock::new(HashMap::new()),
+            tasks: RwLock::new(HashMap::new()),
+        }
+    }
+}
+
+fn emit_event<R: Runtime, S: Serialize + Clone>(app: &AppHandle<R>, event: &str, payload: S) {
+    if let Err(e) = app.emit(event, payload) {
+        warn!("Failed to emit {}: {}", event, e);
+    }
+}
+
+#[tauri::command]
+async fn get_config<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<PortScannerConfig, String> {
+    let state = app.state::<PortScannerState>();
+    let config = state.config.read().await.clone();
+    Ok(config)
+}
+
+#[tauri::command]
+async fn update_config<R: Runtime>(
+    app: AppHandle<R>,
+    config: PortScannerConfig,
+) -> Result<(), String> {
+    let state = app.state::<PortScannerState>();
+    info!("port-scanner config updated: {:?}", config);
+    *state.config.write().await = config;
+    save_config(&app).await
+}
+
+fn config_path<R: Runtime>(app: &AppHandle<R>) -> Option<PathBuf> {
+    app.path().app_config_dir().ok().map(|dir| dir.join("port-scanner.json"))
+}
+
+/// 读取保存的配置，文件不存在或无法解析时使用默认配置
+fn load_config<R: Runtime>(app: &AppHandle<R>) -> PortScannerConfig {
+    let Some(path) = config_path(app) else {
+        return PortScannerConfig::default();
+    };
+    match std::fs::read_to_string(&path) {
+        Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+            warn!("Invalid port-scanner config {}: {}", path.display(), e);
+            PortScannerConfig::default()
+        }),
+        Err(_) => PortScannerConfig::default(),
+    }
+}
+
+async fn save_config<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    let path = config_path(app).ok_or_else(|| "Config directory is unavailable".to_string())?;
+    let state = app.state::<PortScannerState>();
+    let contents = serde_json::to_string_pretty(&*state.config.read().await)
+        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir)
+            .await
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    tokio::fs::write(&path, contents)
+        .await
+        .map_err(|e| format!("Failed to save config to {}: {}", path.display(), e))
+}
+
+/// 列出本机网卡所在的局域网网段
+#[tauri::command]
+async fn port_scan_local_networks<R: Runtime>(
+    _app: AppHandle<R>,
+) -> Result<Vec<LocalNetwork>, String> {
+    target::local_networks()
+}
+
+#[tauri::command]
+async fn port_scan_list<R: Runtime>(app: AppHandle<R>) -> Result<Vec<ScanInfo>, String> {
+    let state = app.state::<PortScannerState>();
+    let mut scans: Vec<ScanInfo> = state.scans.read().await.values().cloned().collect();
+    scans.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+    Ok(scans)
+}
+
+#[tauri::command]
+async fn port_scan_get<R: Runtime>(app: AppHandle<R>, scan_id: String) -> Result<ScanInfo, String> {
+    let state = app.state::<PortScannerState>();
+    let scan = state.scans.read().await.get(&scan_id).cloned();
+    scan.ok_or_else(|| "Scan not found".to_string())
+}
+
+/// 清除已结束的扫描记录
+#[tauri::command]
+async fn port_scan_clear<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
+    let state = app.state::<PortScannerState>();
+    state.scans.write().await.retain(|_, scan| scan.status == "running");
+    Ok(())
+}
+
+pub fn init<R: Runtime>() -> TauriPlugin<R> {
+    tauri::plugin::Builder::new("port-scanner")
+        .invoke_handler(tauri::generate_handler![
+            get_config,
+            update_config,
+            scan::port_scan_start,
+            scan::port_scan_stop,
+            port_scan_list,
+            port_scan_get,
+            port_scan_clear,
+            port_scan_local_networks
+        ])
+        .setup(|app, _api| {
+            // 初始化插件状态，优先使用保存的配置
+            let config = load_config(app);
+            let state = PortScannerState::new(config);
+            app.manage(state);
+
+            info!("port-scanner plugin initialized");
+            Ok(())
+        })
+        .build()
+}
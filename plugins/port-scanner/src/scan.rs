@@ -0,0 +1,338 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, Runtime};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::task::JoinSet;
+use tracing::info;
+
+use crate::target::{parse_ports, parse_targets, MAX_PROBES};
+use crate::{emit_event, PortScannerState};
+
+/// 读取的 banner 上限
+const MAX_BANNER: usize = 512;
+
+/// 进度事件的最小间隔
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+/// 并发连接数上限，避免耗尽文件描述符
+const MAX_CONCURRENCY: usize = 2000;
+
+/// 扫描参数，未设置的字段使用配置中的默认值
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScanOptions {
+    /// 扫描目标，如 "127.0.0.1"、"192.168.1.0/24"、"192.168.1.10-20" 或主机名，多个目标以逗号分隔
+    pub targets: Option<String>,
+    /// 端口列表，如 "22,80,8000-8100"
+    pub ports: Option<String>,
+    pub timeout_ms: Option<u64>,
+    pub concurrency: Option<usize>,
+    /// 连接成功后读取服务端的欢迎信息
+    pub grab_banner: bool,
+    pub banner_timeout_ms: Option<u64>,
+    /// 对端未主动发送数据时写入的探测内容，如 "HEAD / HTTP/1.0\r\n\r\n"
+    pub probe: Option<String>,
+}
+
+/// 一个开放的端口，通过 "port_scan_result" 事件实时发送
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortResult {
+    pub scan_id: String,
+    pub host: String,
+    pub port: u16,
+    /// 按 banner 或常用端口推测的服务
+    pub service: Option<String>,
+    pub banner: Option<String>,
+    /// 建立连接的耗时（毫秒）
+    pub latency_ms: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanInfo {
+    pub id: String,
+    pub targets: String,
+    pub ports: String,
+    /// "running"、"completed" 或 "stopped"
+    pub status: String,
+    pub total: usize,
+    pub scanned: usize,
+    pub open: Vec<PortResult>,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanProgress {
+    pub scan_id: String,
+    pub scanned: usize,
+    pub total: usize,
+    pub open: usize,
+}
+
+struct ProbeSettings {
+    timeout: Duration,
+    grab_banner: bool,
+    banner_timeout: Duration,
+    probe: Option<Vec<u8>>,
+}
+
+/// 把 banner 转为单行可读文本
+fn clean_banner(data: &[u8]) -> Option<String> {
+    let text: String = String::from_utf8_lossy(data)
+        .chars()
+        .map(|c| if c.is_control() && c != '\n' && c != '\t' { '.' } else { c })
+        .collect();
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.chars().take(256).collect())
+}
+
+/// 等待对端主动发送数据，没有数据且配置了探测内容时发送探测后再读取一次
+async fn read_banner(stream: &mut TcpStream, settings: &ProbeSettings) -> Option<String> {
+    let mut buffer = vec![0u8; MAX_BANNER];
+    let wait = match settings.probe {
+        Some(_) => settings.banner_timeout / 2,
+        None => settings.banner_timeout,
+    };
+    if let Ok(Ok(n)) = tokio::time::timeout(wait, stream.read(&mut buffer)).await {
+        if n > 0 {
+            return clean_banner(&buffer[..n]);
+        }
+    }
+
+    let probe = settings.probe.as_ref()?;
+    stream.write_all(probe).await.ok()?;
+    match tokio::time::timeout(settings.banner_timeout - wait, stream.read(&mut buffer)).await {
+        Ok(Ok(n)) if n > 0 => clean_banner(&buffer[..n]),
+        _ => None,
+    }
+}
+
+/// 探测单个端口，开放时返回连接耗时和 banner
+async fn probe(addr: SocketAddr, settings: Arc<ProbeSettings>) -> Option<(SocketAddr, Duration, Option<String>)> {
+    let started = Instant::now();
+    let mut stream = tokio::time::timeout(settings.timeout, TcpStream::connect(addr)).await.ok()?.ok()?;
+    let latency = started.elapsed();
+    let banner = match settings.grab_banner {
+        true => read_banner(&mut stream, &settings).await,
+        false => None,
+    };
+    Some((addr, latency, banner))
+}
+
+/// 按 banner 或常用端口推测服务名称
+fn guess_service(port: u16, banner: Option<&str>) -> Option<String> {
+    if let Some(banner) = banner {
+        let detected = if banner.starts_with("SSH-") {
+            Some("ssh")
+        } else if banner.starts_with("HTTP/") {
+            Some("http")
+        } else if banner.starts_with("220") && banner.to_uppercase().contains("FTP") {
+            Some("ftp")
+        } else if banner.starts_with("220") && banner.to_uppercase().contains("SMTP") {
+            Some("smtp")
+        } else if banner.starts_with("+OK") {
+            Some("pop3")
+        } else if banner.starts_with("* OK") {
+            Some("imap")
+        } else if banner.starts_with("RFB ") {
+            Some("vnc")
+        } else {
+            None
+        };
+        if let Some(service) = detected {
+            return Some(service.to_string());
+        }
+    }
+
+    let service = match port {
+        21 => "ftp",
+        22 => "ssh",
+        23 => "telnet",
+        25 => "smtp",
+        53 => "dns",
+        80 => "http",
+        110 => "pop3",
+        143 => "imap",
+        443 => "https",
+        502 => "modbus",
+        1883 => "mqtt",
+        3306 => "mysql",
+        3389 => "rdp",
+        5432 => "postgresql",
+        5900 => "vnc",
+        6379 => "redis",
+        8080 => "http-alt",
+        8883 => "mqtts",
+        27017 => "mongodb",
+        _ => return None,
+    };
+    Some(service.to_string())
+}
+
+async fn update_scan<R: Runtime>(app: &AppHandle<R>, scan_id: &str, update: impl FnOnce(&mut ScanInfo)) -> Option<ScanInfo> {
+    let state = app.state::<PortScannerState>();
+    let mut scans = state.scans.write().await;
+    let scan = scans.get_mut(scan_id)?;
+    update(scan);
+    Some(scan.clone())
+}
+
+fn progress(scan: &ScanInfo) -> ScanProgress {
+    ScanProgress {
+        scan_id: scan.id.clone(),
+        scanned: scan.scanned,
+        total: scan.total,
+        open: scan.open.len(),
+    }
+}
+
+async fn run_scan<R: Runtime>(
+    app: AppHandle<R>,
+    scan_id: String,
+    addrs: Vec<SocketAddr>,
+    concurrency: usize,
+    settings: Arc<ProbeSettings>,
+) {
+    let mut addrs = addrs.into_iter();
+    let mut pending = JoinSet::new();
+    let mut scanned = 0;
+    let mut last_progress = Instant::now();
+
+    loop {
+        while pending.len() < concurrency {
+            match addrs.next() {
+                Some(addr) => {
+                    pending.spawn(probe(addr, settings.clone()));
+                }
+                None => break,
+            }
+        }
+        let Some(result) = pending.join_next().await else {
+            break;
+        };
+        scanned += 1;
+
+        if let Ok(Some((addr, latency, banner))) = result {
+            let result = PortResult {
+                scan_id: scan_id.clone(),
+                host: addr.ip().to_string(),
+                port: addr.port(),
+                service: guess_service(addr.port(), banner.as_deref()),
+                banner,
+                latency_ms: latency.as_secs_f64() * 1000.0,
+            };
+            update_scan(&app, &scan_id, |scan| scan.open.push(result.clone())).await;
+            emit_event(&app, "port_scan_result", &result);
+        }
+        if last_progress.elapsed() >= PROGRESS_INTERVAL {
+            last_progress = Instant::now();
+            if let Some(scan) = update_scan(&app, &scan_id, |scan| scan.scanned = scanned).await {
+                emit_event(&app, "port_scan_progress", progress(&scan));
+            }
+        }
+    }
+
+    let finished = update_scan(&app, &scan_id, |scan| {
+        scan.scanned = scanned;
+        scan.status = "completed".to_string();
+        scan.finished_at = Some(Utc::now().to_rfc3339());
+    })
+    .await;
+    app.state::<PortScannerState>().tasks.write().await.remove(&scan_id);
+
+    if let Some(scan) = finished {
+        info!("Port scan {} completed: {} open of {} probed", scan_id, scan.open.len(), scan.total);
+        emit_event(&app, "port_scan_progress", progress(&scan));
+        emit_event(&app, "port_scan_finished", &scan);
+    }
+}
+
+/// 在后台开始扫描，返回扫描 ID；开放端口、进度和结束分别通过
+/// "port_scan_result"、"port_scan_progress" 和 "port_scan_finished" 事件发送
+#[tauri::command]
+pub(crate) async fn port_scan_start<R: Runtime>(app: AppHandle<R>, options: Option<ScanOptions>) -> Result<String, String> {
+    let state = app.state::<PortScannerState>();
+    let options = options.unwrap_or_default();
+    let config = state.config.read().await.clone();
+
+    let targets = options.targets.filter(|t| !t.trim().is_empty()).unwrap_or(config.default_targets);
+    let ports = options.ports.filter(|p| !p.trim().is_empty()).unwrap_or(config.default_ports);
+    let hosts = parse_targets(&targets).await?;
+    let port_list = parse_ports(&ports)?;
+    let total = hosts.len() * port_list.len();
+    if total > MAX_PROBES {
+        return Err(format!(
+            "Scan is too large: {} hosts × {} ports exceeds {} probes",
+            hosts.len(),
+            port_list.len(),
+            MAX_PROBES
+        ));
+    }
+    let addrs: Vec<SocketAddr> = hosts
+        .iter()
+        .flat_map(|host| port_list.iter().map(move |port| SocketAddr::new(*host, *port)))
+        .collect();
+
+    let concurrency = options.concurrency.unwrap_or(config.concurrency).clamp(1, MAX_CONCURRENCY);
+    let settings = Arc::new(ProbeSettings {
+        timeout: Duration::from_millis(options.timeout_ms.unwrap_or(config.timeout_ms)),
+        grab_banner: options.grab_banner,
+        banner_timeout: Duration::from_millis(options.banner_timeout_ms.unwrap_or(config.banner_timeout_ms)),
+        probe: options.probe.filter(|p| !p.is_empty()).map(String::into_bytes),
+    });
+
+    let scan_id = format!("scan_{}", uuid::Uuid::new_v4().simple());
+    let scan = ScanInfo {
+        id: scan_id.clone(),
+        targets,
+        ports,
+        status: "running".to_string(),
+        total,
+        scanned: 0,
+        open: Vec::new(),
+        started_at: Utc::now().to_rfc3339(),
+        finished_at: None,
+    };
+    state.scans.write().await.insert(scan_id.clone(), scan);
+
+    info!(
+        "Port scan {} started: {} hosts, {} ports, concurrency {}",
+        scan_id,
+        hosts.len(),
+        port_list.len(),
+        concurrency
+    );
+    // 先登记任务再启动，避免扫描很快结束时任务记录残留
+    let mut tasks = state.tasks.write().await;
+    let task = tokio::spawn(run_scan(app.clone(), scan_id.clone(), addrs, concurrency, settings));
+    tasks.insert(scan_id.clone(), task);
+    Ok(scan_id)
+}
+
+#[tauri::command]
+pub(crate) async fn port_scan_stop<R: Runtime>(app: AppHandle<R>, scan_id: String) -> Result<(), String> {
+    let state = app.state::<PortScannerState>();
+    let task = state
+        .tasks
+        .write()
+        .await
+        .remove(&scan_id)
+        .ok_or_else(|| "Scan is not running".to_string())?;
+    task.abort();
+
+    let stopped = update_scan(&app, &scan_id, |scan| {
+        scan.status = "stopped".to_string();
+        scan.finished_at = Some(Utc::now().to_rfc3339());
+    })
+    .await;
+    if let Some(scan) = stopped {
+        emit_event(&app, "port_scan_finished", &scan);
+    }
+
+    info!("Port scan {} stopped", scan_id);
+    Ok(())
+}
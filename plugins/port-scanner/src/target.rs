@@ -0,0 +1,180 @@
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+/// 单次扫描的主机数上限
+pub const MAX_HOSTS: usize = 65536;
+
+/// 单次扫描的探测次数上限（主机数 × 端口数）
+pub const MAX_PROBES: usize = 1_000_000;
+
+/// 本机 IPv4 网卡所在的网段，供前端选择局域网扫描范围
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocalNetwork {
+    pub interface: String,
+    pub address: String,
+    /// 网卡所在网段，如 "192.168.1.0/24"
+    pub cidr: String,
+    /// 建议的扫描范围，网段大于 /24 时取本机地址所在的 /24
+    pub scan_range: String,
+}
+
+fn parse_port(input: &str) -> Result<u16, String> {
+    input
+        .trim()
+        .parse::<u16>()
+        .ok()
+        .filter(|port| *port > 0)
+        .ok_or_else(|| format!("Invalid port: {}", input.trim()))
+}
+
+/// 解析端口列表，如 "22,80,8000-8100"，结果去重并排序
+pub fn parse_ports(input: &str) -> Result<Vec<u16>, String> {
+    let mut ports = Vec::new();
+    for part in input.split(',').map(str::trim).filter(|part| !part.is_empty()) {
+        let (start, end) = match part.split_once('-') {
+            Some((start, end)) => (parse_port(start)?, parse_port(end)?),
+            None => {
+                let port = parse_port(part)?;
+                (port, port)
+            }
+        };
+        if start > end {
+            return Err(format!("Invalid port range: {}", part));
+        }
+        ports.extend(start..=end);
+    }
+    ports.sort_unstable();
+    ports.dedup();
+    if ports.is_empty() {
+        return Err("No ports to scan".to_string());
+    }
+    Ok(ports)
+}
+
+/// 展开 CIDR 网段，IPv4 网段不含网络地址和广播地址（/31、/32 除外）
+fn cidr_hosts(addr: &str, prefix: &str) -> Result<Vec<IpAddr>, String> {
+    let ip: IpAddr = addr.parse().map_err(|_| format!("Invalid address: {}", addr))?;
+    let bits = if ip.is_ipv4() { 32 } else { 128 };
+    let prefix = prefix
+        .parse::<u32>()
+        .ok()
+        .filter(|prefix| *prefix <= bits)
+        .ok_or_else(|| format!("Invalid prefix length: {}", prefix))?;
+    if bits - prefix > 16 {
+        return Err(format!(
+            "Range {}/{} is too large, use a prefix of at least /{}",
+            addr,
+            prefix,
+            bits - 16
+        ));
+    }
+
+    match ip {
+        IpAddr::V4(ip) => {
+            let size = 1u32 << (32 - prefix);
+            let network = u32::from(ip) & !(size - 1);
+            let (first, last) = if size > 2 {
+                (network + 1, network + size - 2)
+            } else {
+                (network, network + size - 1)
+            };
+            Ok((first..=last).map(|n| IpAddr::V4(Ipv4Addr::from(n))).collect())
+        }
+        IpAddr::V6(ip) => {
+            let size = 1u128 << (128 - prefix);
+            let network = u128::from(ip) & !(size - 1);
+            Ok((network..network + size).map(|n| IpAddr::V6(Ipv6Addr::from(n))).collect())
+        }
+    }
+}
+
+/// 展开 IPv4 地址范围，如 "192.168.1.10-20" 或 "192.168.1.10-192.168.1.20"
+fn range_hosts(start: Ipv4Addr, end: &str) -> Result<Vec<IpAddr>, String> {
+    let end = match end.parse::<Ipv4Addr>() {
+        Ok(end) => end,
+        Err(_) => {
+            let last = end.parse::<u8>().map_err(|_| format!("Invalid range end: {}", end))?;
+            let [a, b, c, _] = start.octets();
+            Ipv4Addr::new(a, b, c, last)
+        }
+    };
+    let (start, end) = (u32::from(start), u32::from(end));
+    if start > end {
+        return Err(format!("Invalid address range: {}-{}", Ipv4Addr::from(start), Ipv4Addr::from(end)));
+    }
+    if (end - start) as usize >= MAX_HOSTS {
+        return Err(format!("Address range is too large (more than {} hosts)", MAX_HOSTS));
+    }
+    Ok((start..=end).map(|n| IpAddr::V4(Ipv4Addr::from(n))).collect())
+}
+
+async fn resolve_host(host: &str) -> Result<IpAddr, String> {
+    let addrs: Vec<IpAddr> = tokio::net::lookup_host((host, 0))
+        .await
+        .map_err(|e| format!("Failed to resolve {}: {}", host, e))?
+        .map(|addr| addr.ip())
+        .collect();
+    // 优先使用 IPv4 地址
+    addrs
+        .iter()
+        .find(|ip| ip.is_ipv4())
+        .or_else(|| addrs.first())
+        .copied()
+        .ok_or_else(|| format!("{} has no addresses", host))
+}
+
+/// 解析扫描目标，多个目标以逗号或空白分隔，支持单个地址、主机名、CIDR 网段和 IPv4 地址范围
+pub async fn parse_targets(input: &str) -> Result<Vec<IpAddr>, String> {
+    let mut hosts = Vec::new();
+    for part in input.split(|c: char| c == ',' || c.is_whitespace()).filter(|part| !part.is_empty()) {
+        if let Some((addr, prefix)) = part.split_once('/') {
+            hosts.extend(cidr_hosts(addr, prefix)?);
+        } else if let Some((start, end)) = part
+            .split_once('-')
+            .and_then(|(start, end)| start.parse::<Ipv4Addr>().ok().map(|start| (start, end)))
+        {
+            hosts.extend(range_hosts(start, end)?);
+        } else if let Ok(ip) = part.trim_start_matches('[').trim_end_matches(']').parse::<IpAddr>() {
+            hosts.push(ip);
+        } else {
+            hosts.push(resolve_host(part).await?);
+        }
+
+        if hosts.len() > MAX_HOSTS {
+            return Err(format!("Too many hosts (more than {})", MAX_HOSTS));
+        }
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    hosts.retain(|ip| seen.insert(*ip));
+    if hosts.is_empty() {
+        return Err("No hosts to scan".to_string());
+    }
+    Ok(hosts)
+}
+
+/// 列出本机非回环 IPv4 网卡所在的网段
+pub fn local_networks() -> Result<Vec<LocalNetwork>, String> {
+    let interfaces = if_addrs::get_if_addrs().map_err(|e| format!("Failed to list interfaces: {}", e))?;
+    let networks = interfaces
+        .into_iter()
+        .filter(|iface| !iface.is_loopback())
+        .filter_map(|iface| match iface.addr {
+            if_addrs::IfAddr::V4(addr) => Some((iface.name, addr)),
+            if_addrs::IfAddr::V6(_) => None,
+        })
+        .map(|(interface, addr)| {
+            let network = |prefix: u8| {
+                let mask = u32::MAX.checked_shl(32 - prefix as u32).unwrap_or(0);
+                format!("{}/{}", Ipv4Addr::from(u32::from(addr.ip) & mask), prefix)
+            };
+            LocalNetwork {
+                interface,
+                address: addr.ip.to_string(),
+                cidr: network(addr.prefixlen),
+                scan_range: network(addr.prefixlen.max(24)),
+            }
+        })
+        .collect();
+    Ok(networks)
+}
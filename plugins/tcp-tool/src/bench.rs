@@ -0,0 +1,496 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, Runtime};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use tokio::time::MissedTickBehavior;
+use tracing::{info, warn};
+use network_common::payload;
+
+use crate::{emit_event, socket, SocketOptions, TcpToolState};
+
+fn default_connections() -> u32 {
+    10
+}
+
+fn default_duration_ms() -> u64 {
+    10000
+}
+
+fn default_stats_interval_ms() -> u64 {
+    1000
+}
+
+/// 压测参数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchmarkOptions {
+    pub host: String,
+    pub port: u16,
+    /// 并发连接数
+    #[serde(default = "default_connections")]
+    pub connections: u32,
+    #[serde(default = "default_duration_ms")]
+    pub duration_ms: u64,
+    /// 所有连接合计的发送速率（条/秒），为空时尽快发送
+    #[serde(default)]
+    pub rate: Option<f64>,
+    pub message: String,
+    #[serde(default)]
+    pub encoding: Option<String>,
+    /// 为 true 时每次发送后等待应答并统计往返时延
+    #[serde(default)]
+    pub request_response: bool,
+    /// 视为一次完整应答的最少字节数，默认收到任意数据即可
+    #[serde(default)]
+    pub response_bytes: Option<usize>,
+    #[serde(default = "default_stats_interval_ms")]
+    pub stats_interval_ms: u64,
+}
+
+/// 时延分布（毫秒）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LatencySummary {
+    pub count: usize,
+    pub min: f64,
+    pub mean: f64,
+    pub p50: f64,
+    pub p90: f64,
+    pub p99: f64,
+    pub max: f64,
+}
+
+/// 每个 2 的幂区间等分的子桶数（2^4），相对误差约 6%
+const SUB_BUCKET_BITS: u32 = 4;
+const SUB_BUCKETS: u64 = 1 << SUB_BUCKET_BITS;
+/// 覆盖 u64 微秒范围所需的桶数
+const BUCKETS: usize = ((64 - SUB_BUCKET_BITS + 1) as u64 * SUB_BUCKETS) as usize;
+
+/// 对数分桶的时延直方图（微秒），内存固定，不随请求数增长
+struct LatencyHistogram {
+    buckets: Vec<u64>,
+    count: u64,
+    sum_us: u128,
+    min_us: u64,
+    max_us: u64,
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self {
+            buckets: vec![0; BUCKETS],
+            count: 0,
+            sum_us: 0,
+            min_us: u64::MAX,
+            max_us: 0,
+        }
+    }
+}
+
+impl LatencyHistogram {
+    fn index(us: u64) -> usize {
+        if us < SUB_BUCKETS {
+            return us as usize;
+        }
+        let shift = 63 - us.leading_zeros() - SUB_BUCKET_BITS;
+        ((u64::from(shift) + 1) * SUB_BUCKETS + (us >> shift) - SUB_BUCKETS) as usize
+    }
+
+    /// 桶内的最大值
+    fn upper_bound(index: usize) -> u64 {
+        let (group, sub) = (index as u64 / SUB_BUCKETS, index as u64 % SUB_BUCKETS);
+        if group == 0 {
+            return sub;
+        }
+        let shift = group - 1;
+        ((SUB_BUCKETS + sub) << shift).saturating_add((1 << shift) - 1)
+    }
+
+    fn record(&mut self, latency: Duration) {
+        let us = u64::try_from(latency.as_micros()).unwrap_or(u64::MAX);
+        self.buckets[Self::index(us)] += 1;
+        self.count += 1;
+        self.sum_us += u128::from(us);
+        self.min_us = self.min_us.min(us);
+        self.max_us = self.max_us.max(us);
+    }
+
+    fn summary(&self) -> LatencySummary {
+        if self.count == 0 {
+            return LatencySummary::default();
+        }
+        let millis = |us: u64| us as f64 / 1000.0;
+        let percentile = |p: f64| {
+            let rank = ((self.count - 1) as f64 * p).round() as u64 + 1;
+            let mut seen = 0;
+            let index = self
+                .buckets
+                .iter()
+                .position(|n| {
+                    seen += n;
+                    seen >= rank
+                })
+                .unwrap_or(BUCKETS - 1);
+            millis(Self::upper_bound(index).clamp(self.min_us, self.max_us))
+        };
+        LatencySummary {
+            count: self.count as usize,
+            min: millis(self.min_us),
+            mean: self.sum_us as f64 / self.count as f64 / 1000.0,
+            p50: percentile(0.5),
+            p90: percentile(0.9),
+            p99: percentile(0.99),
+            max: millis(self.max_us),
+        }
+    }
+}
+
+/// 周期性统计和最终报告，分别通过 "tcp_benchmark_stats" 和 "tcp_benchmark_finished" 事件发送
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchmarkReport {
+    pub benchmark_id: String,
+    pub elapsed_ms: u64,
+    pub finished: bool,
+    pub active_connections: u64,
+    pub messages_sent: u64,
+    pub responses: u64,
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    /// 自上次统计以来的速率
+    pub messages_per_sec: f64,
+    pub bytes_sent_per_sec: f64,
+    pub bytes_received_per_sec: f64,
+    pub connect_errors: u64,
+    pub send_errors: u64,
+    pub receive_errors: u64,
+    pub timeouts: u64,
+    pub connect_latency: LatencySummary,
+    pub round_trip_latency: LatencySummary,
+}
+
+#[derive(Default)]
+struct Counters {
+    active: AtomicU64,
+    messages_sent: AtomicU64,
+    responses: AtomicU64,
+    bytes_sent: AtomicU64,
+    bytes_received: AtomicU64,
+    connect_errors: AtomicU64,
+    send_errors: AtomicU64,
+    receive_errors: AtomicU64,
+    timeouts: AtomicU64,
+}
+
+/// 一次压测的共享统计
+struct Benchmark {
+    id: String,
+    started: Instant,
+    counters: Counters,
+    connect_latency: Mutex<LatencyHistogram>,
+    round_trip_latency: Mutex<LatencyHistogram>,
+    /// 上次统计时的 (发送条数, 发送字节, 接收字节, 时间)
+    last_sample: Mutex<(u64, u64, u64, Instant)>,
+}
+
+impl Benchmark {
+    async fn report(&self, finished: bool) -> BenchmarkReport {
+        let load = |counter: &AtomicU64| counter.load(Ordering::Relaxed);
+        let c = &self.counters;
+        let (messages_sent, bytes_sent, bytes_received) = (load(&c.messages_sent), load(&c.bytes_sent), load(&c.bytes_received));
+
+        let mut last = self.last_sample.lock().await;
+        let now = Instant::now();
+        let secs = now.duration_since(last.3).as_secs_f64().max(f64::EPSILON);
+        let rate = |current: u64, previous: u64| current.saturating_sub(previous) as f64 / secs;
+        let report = BenchmarkReport {
+            benchmark_id: self.id.clone(),
+            elapsed_ms: self.started.elapsed().as_millis() as u64,
+            finished,
+            active_connections: load(&c.active),
+            messages_sent,
+            responses: load(&c.responses),
+            bytes_sent,
+            bytes_received,
+            messages_per_sec: rate(messages_sent, last.0),
+            bytes_sent_per_sec: rate(bytes_sent, last.1),
+            bytes_received_per_sec: rate(bytes_received, last.2),
+            connect_errors: load(&c.connect_errors),
+            send_errors: load(&c.send_errors),
+            receive_errors: load(&c.receive_errors),
+            timeouts: load(&c.timeouts),
+            connect_latency: self.connect_latency.lock().await.summary(),
+            round_trip_latency: self.round_trip_latency.lock().await.summary(),
+        };
+        *last = (messages_sent, bytes_sent, bytes_received, now);
+        report
+    }
+}
+
+struct Running {
+    benchmark: Arc<Benchmark>,
+    task: JoinHandle<()>,
+}
+
+/// 正在运行的压测；压测连接不登记到连接列表，也不逐条上报数据
+#[derive(Default)]
+pub struct BenchmarkRegistry {
+    running: Mutex<HashMap<String, Running>>,
+    next_id: AtomicU64,
+}
+
+async fn drain(benchmark: &Benchmark, reader: &mut OwnedReadHalf) {
+    let mut buf = vec![0u8; 16384];
+    while let Ok(n) = reader.read(&mut buf).await {
+        if n == 0 {
+            break;
+        }
+        benchmark.counters.bytes_received.fetch_add(n as u64, Ordering::Relaxed);
+    }
+}
+
+/// 单个连接的压测循环
+async fn run_worker(
+    benchmark: Arc<Benchmark>,
+    options: Arc<BenchmarkOptions>,
+    socket_options: SocketOptions,
+    data: Arc<Vec<u8>>,
+    deadline: tokio::time::Instant,
+) {
+    let counters = &benchmark.counters;
+    let connect_started = Instant::now();
+    let stream = match socket::connect(&options.host, options.port, &socket_options).await {
+        Ok(stream) => stream,
+        Err(e) => {
+            warn!("Benchmark {} connect failed: {}", benchmark.id, e);
+            counters.connect_errors.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+    };
+    benchmark.connect_latency.lock().await.record(connect_started.elapsed());
+    counters.active.fetch_add(1, Ordering::Relaxed);
+
+    let (mut reader, mut writer) = stream.into_split();
+    let worker = Worker {
+        benchmark: &benchmark,
+        options: &options,
+        response_timeout: socket_options.connection_timeout,
+        data: &data,
+        deadline,
+    };
+    if options.request_response {
+        worker.send_loop(&mut writer, Some(&mut reader)).await;
+    } else {
+        // 单向发送时同时读走对端数据，避免对端写满缓冲区
+        tokio::select! {
+            _ = worker.send_loop(&mut writer, None) => {}
+            _ = drain(&benchmark, &mut reader) => {}
+        }
+    }
+
+    let _ = writer.shutdown().await;
+    counters.active.fetch_sub(1, Ordering::Relaxed);
+}
+
+struct Worker<'a> {
+    benchmark: &'a Benchmark,
+    options: &'a BenchmarkOptions,
+    response_timeout: Duration,
+    data: &'a [u8],
+    deadline: tokio::time::Instant,
+}
+
+/// 按合计速率计算每个连接的发送间隔，最短 1 毫秒，因此每个连接最多每秒发送 1000 条
+fn send_period(connections: u32, rate: f64) -> Result<Duration, String> {
+    if !rate.is_finite() || rate <= 0.0 {
+        return Err("Rate must be a positive number".to_string());
+    }
+    let period = Duration::try_from_secs_f64(connections as f64 / rate)
+        .ok()
+        .filter(|period| *period <= Duration::from_secs(86400))
+        .ok_or_else(|| format!("Rate {} is too low", rate))?;
+    Ok(period.max(Duration::from_millis(1)))
+}
+
+impl Worker<'_> {
+    /// 按速率发送直到截止时间；传入 `reader` 时每次发送后等待应答
+    async fn send_loop(&self, writer: &mut OwnedWriteHalf, mut reader: Option<&mut OwnedReadHalf>) {
+        let counters = &self.benchmark.counters;
+        let mut interval = self
+            .options
+            .rate
+            .and_then(|rate| send_period(self.options.connections, rate).ok())
+            .map(tokio::time::interval);
+        if let Some(interval) = interval.as_mut() {
+            interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        }
+        let response_bytes = self.options.response_bytes.unwrap_or(1).max(1);
+        let mut buf = vec![0u8; 16384];
+
+        while tokio::time::Instant::now() < self.deadline {
+            if let Some(interval) = interval.as_mut() {
+                if tokio::time::timeout_at(self.deadline, interval.tick()).await.is_err() {
+                    break;
+                }
+            }
+
+            let sent_at = Instant::now();
+            if let Err(e) = writer.write_all(self.data).await {
+                warn!("Benchmark {} send failed: {}", self.benchmark.id, e);
+                counters.send_errors.fetch_add(1, Ordering::Relaxed);
+                break;
+            }
+            counters.messages_sent.fetch_add(1, Ordering::Relaxed);
+            counters.bytes_sent.fetch_add(self.data.len() as u64, Ordering::Relaxed);
+
+            let Some(reader) = reader.as_mut() else {
+                continue;
+            };
+            let mut received = 0;
+            let response = tokio::time::timeout(self.response_timeout, async {
+                while received < response_bytes {
+                    match reader.read(&mut buf).await {
+                        Ok(0) => return Err("connection closed".to_string()),
+                        Ok(n) => received += n,
+                        Err(e) => return Err(e.to_string()),
+                    }
+                }
+                Ok(())
+            })
+            .await;
+            counters.bytes_received.fetch_add(received as u64, Ordering::Relaxed);
+
+            match response {
+                Ok(Ok(())) => {
+                    self.benchmark.round_trip_latency.lock().await.record(sent_at.elapsed());
+                    counters.responses.fetch_add(1, Ordering::Relaxed);
+                }
+                Ok(Err(e)) => {
+                    warn!("Benchmark {} receive failed: {}", self.benchmark.id, e);
+                    counters.receive_errors.fetch_add(1, Ordering::Relaxed);
+                    break;
+                }
+                Err(_) => {
+                    counters.timeouts.fetch_add(1, Ordering::Relaxed);
+                    break;
+                }
+            }
+        }
+    }
+}
+
+async fn run_benchmark<R: Runtime>(
+    app: AppHandle<R>,
+    benchmark: Arc<Benchmark>,
+    options: BenchmarkOptions,
+    socket_options: SocketOptions,
+    data: Vec<u8>,
+) {
+    let deadline = tokio::time::Instant::now() + Duration::from_millis(options.duration_ms);
+    let options = Arc::new(options);
+    let data = Arc::new(data);
+
+    let mut workers = tokio::task::JoinSet::new();
+    for _ in 0..options.connections {
+        workers.spawn(run_worker(
+            benchmark.clone(),
+            options.clone(),
+            socket_options.clone(),
+            data.clone(),
+            deadline,
+        ));
+    }
+
+    let mut stats = tokio::time::interval(Duration::from_millis(options.stats_interval_ms.max(100)));
+    stats.tick().await;
+    loop {
+        tokio::select! {
+            joined = workers.join_next() => {
+                if joined.is_none() {
+                    break;
+                }
+            }
+            _ = stats.tick() => {
+                emit_event(&app, "tcp_benchmark_stats", &benchmark.report(false).await);
+            }
+        }
+    }
+
+    let state = app.state::<TcpToolState>();
+    state.benchmarks.running.lock().await.remove(&benchmark.id);
+    let report = benchmark.report(true).await;
+    info!(
+        "Benchmark {} finished: {} messages, {} responses, {} errors",
+        benchmark.id,
+        report.messages_sent,
+        report.responses,
+        report.connect_errors + report.send_errors + report.receive_errors + report.timeouts
+    );
+    emit_event(&app, "tcp_benchmark_finished", &report);
+}
+
+#[tauri::command]
+pub(crate) async fn tcp_benchmark_start<R: Runtime>(
+    app: AppHandle<R>,
+    options: BenchmarkOptions,
+) -> Result<String, String> {
+    if options.connections == 0 {
+        return Err("At least one connection is required".to_string());
+    }
+    if let Some(rate) = options.rate {
+        send_period(options.connections, rate)?;
+    }
+    let data = payload::decode(&options.message, options.encoding.as_deref())?;
+    if data.is_empty() {
+        return Err("Benchmark message is empty".to_string());
+    }
+
+    let state = app.state::<TcpToolState>();
+    let socket_options = SocketOptions::resolve(&*state.config.read().await, None);
+    let benchmark_id = format!("benchmark_{}", state.benchmarks.next_id.fetch_add(1, Ordering::Relaxed) + 1);
+    let now = Instant::now();
+    let benchmark = Arc::new(Benchmark {
+        id: benchmark_id.clone(),
+        started: now,
+        counters: Counters::default(),
+        connect_latency: Mutex::default(),
+        round_trip_latency: Mutex::default(),
+        last_sample: Mutex::new((0, 0, 0, now)),
+    });
+
+    info!(
+        "Benchmark {} started: {} connections to {}:{} for {} ms",
+        benchmark_id, options.connections, options.host, options.port, options.duration_ms
+    );
+
+    let mut running = state.benchmarks.running.lock().await;
+    let task = tokio::spawn(run_benchmark(app.clone(), benchmark.clone(), options, socket_options, data));
+    running.insert(benchmark_id.clone(), Running { benchmark, task });
+    Ok(benchmark_id)
+}
+
+/// 提前结束压测并返回截至目前的报告
+#[tauri::command]
+pub(crate) async fn tcp_benchmark_stop<R: Runtime>(
+    app: AppHandle<R>,
+    benchmark_id: String,
+) -> Result<BenchmarkReport, String> {
+    let state = app.state::<TcpToolState>();
+    let running = state
+        .benchmarks
+        .running
+        .lock()
+        .await
+        .remove(&benchmark_id)
+        .ok_or_else(|| "Benchmark not found".to_string())?;
+    // 中止主任务会一并中止 JoinSet 中的所有连接
+    running.task.abort();
+
+    let report = running.benchmark.report(true).await;
+    emit_event(&app, "tcp_benchmark_finished", &report);
+    info!("Benchmark {} stopped", benchmark_id);
+    Ok(report)
+}
@@ -0,0 +1,430 @@
+use std::collections::{HashMap, VecDeque};
+use std::net::{IpAddr, SocketAddr};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager, Runtime};
+use tokio::sync::Mutex;
+use tracing::info;
+
+use crate::{ConnectionInfo, MessageData, TcpToolState};
+
+/// 链路类型 LINKTYPE_RAW：数据包直接以 IPv4/IPv6 头开始
+const LINKTYPE_RAW: u16 = 101;
+const TCP_MSS: usize = 1460;
+/// 断开后仍保留抓包缓存的连接数，超出时丢弃最早断开的连接的缓存
+const MAX_CLOSED_CAPTURES: usize = 16;
+
+struct CapturedMessage {
+    /// 自 Unix 纪元起的微秒数
+    timestamp_us: u64,
+    direction: String,
+    side: Option<String>,
+    remote_addr: Option<String>,
+    data: Vec<u8>,
+}
+
+/// 每个连接最近收发的数据，用于导出 pcap。连接断开后缓存仍可导出，
+/// 导出后或断开的连接超过 [`MAX_CLOSED_CAPTURES`] 个时释放
+#[derive(Default)]
+pub struct CaptureBuffer {
+    messages: Mutex<HashMap<String, VecDeque<CapturedMessage>>>,
+    /// 已断开、仍保留缓存的连接，按断开顺序排列
+    closed: Mutex<VecDeque<String>>,
+}
+
+impl CaptureBuffer {
+    /// 连接断开时调用，只保留最近断开的若干个连接的缓存
+    pub(crate) async fn closed(&self, connection_id: &str) {
+        let evicted: Vec<String> = {
+            let mut closed = self.closed.lock().await;
+            closed.retain(|id| id != connection_id);
+            closed.push_back(connection_id.to_string());
+            let excess = closed.len().saturating_sub(MAX_CLOSED_CAPTURES);
+            closed.drain(..excess).collect()
+        };
+        let mut messages = self.messages.lock().await;
+        for id in evicted {
+            messages.remove(&id);
+        }
+    }
+
+    async fn remove(&self, connection_id: &str) {
+        self.closed.lock().await.retain(|id| id != connection_id);
+        self.messages.lock().await.remove(connection_id);
+    }
+
+    /// 追加一条消息，超过 `limit` 条时丢弃最早的消息；`limit` 为 0 时不保存
+    pub(crate) async fn record(&self, message: &MessageData, data: &[u8], limit: usize) {
+        if limit == 0 {
+            return;
+        }
+        let timestamp_us = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_micros() as u64)
+            .unwrap_or_default();
+
+        // 重连后的连接继续使用原来的缓存
+        self.closed.lock().await.retain(|id| id != &message.connection_id);
+        let mut messages = self.messages.lock().await;
+        let buffer = messages.entry(message.connection_id.clone()).or_default();
+        while buffer.len() >= limit {
+            buffer.pop_front();
+        }
+        buffer.push_back(CapturedMessage {
+            timestamp_us,
+            direction: message.direction.clone(),
+            side: message.side.clone(),
+            remote_addr: message.remote_addr.clone(),
+            data: data.to_vec(),
+        });
+    }
+}
+
+fn checksum(chunks: &[&[u8]]) -> u16 {
+    let mut sum = 0u32;
+    let mut odd: Option<u8> = None;
+    for chunk in chunks {
+        for &byte in chunk.iter() {
+            match odd.take() {
+                Some(high) => sum += u32::from(u16::from_be_bytes([high, byte])),
+                None => odd = Some(byte),
+            }
+        }
+    }
+    if let Some(high) = odd {
+        sum += u32::from(u16::from_be_bytes([high, 0]));
+    }
+    while sum > 0xffff {
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+    !(sum as u16)
+}
+
+fn ip_bytes(ip: IpAddr) -> Vec<u8> {
+    match ip {
+        IpAddr::V4(ip) => ip.octets().to_vec(),
+        IpAddr::V6(ip) => ip.octets().to_vec(),
+    }
+}
+
+/// 两端地址族不同时统一为 IPv6（IPv4 映射地址）
+fn normalize(a: SocketAddr, b: SocketAddr) -> (SocketAddr, SocketAddr) {
+    let to_v6 = |addr: SocketAddr| match addr.ip() {
+        IpAddr::V4(ip) => SocketAddr::new(IpAddr::V6(ip.to_ipv6_mapped()), addr.port()),
+        IpAddr::V6(_) => addr,
+    };
+    if a.is_ipv4() == b.is_ipv4() {
+        (a, b)
+    } else {
+        (to_v6(a), to_v6(b))
+    }
+}
+
+/// 为传输层报文加上 IP 头，并填写传输层校验和（位于 `checksum_offset`）
+fn ip_packet(src: SocketAddr, dst: SocketAddr, protocol: u8, mut segment: Vec<u8>, checksum_offset: usize) -> Vec<u8> {
+    let (src_ip, dst_ip) = (ip_bytes(src.ip()), ip_bytes(dst.ip()));
+    let length = segment.len();
+
+    let pseudo = if src.is_ipv4() {
+        let mut pseudo = [0u8; 4];
+        pseudo[1] = protocol;
+        pseudo[2..4].copy_from_slice(&(length as u16).to_be_bytes());
+        pseudo.to_vec()
+    } else {
+        let mut pseudo = [0u8; 8];
+        pseudo[0..4].copy_from_slice(&(length as u32).to_be_bytes());
+        pseudo[7] = protocol;
+        pseudo.to_vec()
+    };
+    let sum = checksum(&[&src_ip, &dst_ip, &pseudo, &segment]);
+    segment[checksum_offset..checksum_offset + 2].copy_from_slice(&sum.to_be_bytes());
+
+    let mut packet = Vec::with_capacity(40 + length);
+    if src.is_ipv4() {
+        packet.extend_from_slice(&[0x45, 0]);
+        packet.extend_from_slice(&((20 + length) as u16).to_be_bytes());
+        packet.extend_from_slice(&[0, 0, 0x40, 0, 64, protocol, 0, 0]);
+        packet.extend_from_slice(&src_ip);
+        packet.extend_from_slice(&dst_ip);
+        let sum = checksum(&[&packet]);
+        packet[10..12].copy_from_slice(&sum.to_be_bytes());
+    } else {
+        packet.extend_from_slice(&[0x60, 0, 0, 0]);
+        packet.extend_from_slice(&(length as u16).to_be_bytes());
+        packet.extend_from_slice(&[protocol, 64]);
+        packet.extend_from_slice(&src_ip);
+        packet.extend_from_slice(&dst_ip);
+    }
+    packet.extend_from_slice(&segment);
+    packet
+}
+
+fn udp_packet(src: SocketAddr, dst: SocketAddr, data: &[u8]) -> Vec<u8> {
+    let mut segment = Vec::with_capacity(8 + data.len());
+    segment.extend_from_slice(&src.port().to_be_bytes());
+    segment.extend_from_slice(&dst.port().to_be_bytes());
+    segment.extend_from_slice(&((8 + data.len()) as u16).to_be_bytes());
+    segment.extend_from_slice(&[0, 0]);
+    segment.extend_from_slice(data);
+    ip_packet(src, dst, 17, segment, 6)
+}
+
+const FIN: u8 = 0x01;
+const SYN: u8 = 0x02;
+const PSH: u8 = 0x08;
+const ACK: u8 = 0x10;
+
+/// 根据收发的数据合成一条 TCP 流，包括握手和挥手
+struct TcpFlow {
+    local: SocketAddr,
+    remote: SocketAddr,
+    local_seq: u32,
+    remote_seq: u32,
+}
+
+impl TcpFlow {
+    fn new(local: SocketAddr, remote: SocketAddr) -> Self {
+        Self {
+            local,
+            remote,
+            local_seq: 1000,
+            remote_seq: 5000,
+        }
+    }
+
+    fn segment(&mut self, outbound: bool, flags: u8, data: &[u8]) -> Vec<u8> {
+        let (src, dst, seq, ack) = if outbound {
+            (self.local, self.remote, self.local_seq, self.remote_seq)
+        } else {
+            (self.remote, self.local, self.remote_seq, self.local_seq)
+        };
+        let consumed = data.len() as u32 + u32::from(flags & (SYN | FIN) != 0);
+        if outbound {
+            self.local_seq = self.local_seq.wrapping_add(consumed);
+        } else {
+            self.remote_seq = self.remote_seq.wrapping_add(consumed);
+        }
+
+        let mut segment = Vec::with_capacity(20 + data.len());
+        segment.extend_from_slice(&src.port().to_be_bytes());
+        segment.extend_from_slice(&dst.port().to_be_bytes());
+        segment.extend_from_slice(&seq.to_be_bytes());
+        segment.extend_from_slice(&(if flags & ACK != 0 { ack } else { 0 }).to_be_bytes());
+        segment.extend_from_slice(&[5 << 4, flags, 0xff, 0xff, 0, 0, 0, 0]);
+        segment.extend_from_slice(data);
+        ip_packet(src, dst, 6, segment, 16)
+    }
+
+    fn handshake(&mut self, local_initiates: bool) -> Vec<Vec<u8>> {
+        vec![
+            self.segment(local_initiates, SYN, &[]),
+            self.segment(!local_initiates, SYN | ACK, &[]),
+            self.segment(local_initiates, ACK, &[]),
+        ]
+    }
+
+    fn data(&mut self, outbound: bool, data: &[u8]) -> Vec<Vec<u8>> {
+        data.chunks(TCP_MSS)
+            .map(|chunk| self.segment(outbound, PSH | ACK, chunk))
+            .collect()
+    }
+
+    fn close(&mut self) -> Vec<Vec<u8>> {
+        vec![
+            self.segment(true, FIN | ACK, &[]),
+            self.segment(false, FIN | ACK, &[]),
+            self.segment(true, ACK, &[]),
+        ]
+    }
+}
+
+type Packet = (u64, Vec<u8>);
+
+fn parse_addr(addr: Option<&str>, connection_id: &str) -> Result<SocketAddr, String> {
+    addr.and_then(|a| a.parse().ok())
+        .ok_or_else(|| format!("Connection {} has no usable address", connection_id))
+}
+
+/// 按连接类型把缓存的消息还原为数据包
+fn connection_packets(info: &ConnectionInfo, messages: &VecDeque<CapturedMessage>) -> Result<Vec<Packet>, String> {
+    let local: SocketAddr = parse_addr(Some(&info.local_addr), &info.id)?;
+    let mut packets = Vec::new();
+
+    if info.connection_type == "udp" {
+        for message in messages {
+            let peer = parse_addr(message.remote_addr.as_deref().or(info.remote_addr.as_deref()), &info.id)?;
+            let (local, peer) = normalize(local, peer);
+            let packet = if message.direction == "sent" {
+                udp_packet(local, peer, &message.data)
+            } else {
+                udp_packet(peer, local, &message.data)
+            };
+            packets.push((message.timestamp_us, packet));
+        }
+        return Ok(packets);
+    }
+
+    let local_initiates = match info.connection_type.as_str() {
+        "client" | "replay" | "proxy_session" => true,
+        "accepted" => false,
+        "websocket" | "websocket_server" => {
+            return Err("WebSocket traffic is captured as decoded frames and cannot be exported".to_string())
+        }
+        other => return Err(format!("Connection type {} has no traffic to export", other)),
+    };
+    let remote = parse_addr(info.remote_addr.as_deref(), &info.id)?;
+    let (local, remote) = normalize(local, remote);
+    let mut flow = TcpFlow::new(local, remote);
+
+    let Some(first) = messages.front() else {
+        return Ok(packets);
+    };
+    packets.extend(flow.handshake(local_initiates).into_iter().map(|p| (first.timestamp_us, p)));
+
+    for message in messages {
+        // 代理会话的本端为客户端、对端为上游，只导出两侧实际收到的原始数据
+        let outbound = if info.connection_type == "proxy_session" {
+            if message.direction != "received" {
+                continue;
+            }
+            message.side.as_deref() == Some("client")
+        } else {
+            message.direction == "sent"
+        };
+        packets.extend(flow.data(outbound, &message.data).into_iter().map(|p| (message.timestamp_us, p)));
+    }
+
+    if info.status == "disconnected" {
+        let last = messages.back().map(|m| m.timestamp_us).unwrap_or_default();
+        packets.extend(flow.close().into_iter().map(|p| (last, p)));
+    }
+    Ok(packets)
+}
+
+fn padded(data: &[u8]) -> Vec<u8> {
+    let mut data = data.to_vec();
+    data.resize(data.len().div_ceil(4) * 4, 0);
+    data
+}
+
+fn write_pcapng(packets: &[Packet]) -> Vec<u8> {
+    let mut out = Vec::new();
+
+    // Section Header Block
+    out.extend_from_slice(&0x0A0D_0D0Au32.to_le_bytes());
+    out.extend_from_slice(&28u32.to_le_bytes());
+    out.extend_from_slice(&0x1A2B_3C4Du32.to_le_bytes());
+    out.extend_from_slice(&1u16.to_le_bytes());
+    out.extend_from_slice(&0u16.to_le_bytes());
+    out.extend_from_slice(&(-1i64).to_le_bytes());
+    out.extend_from_slice(&28u32.to_le_bytes());
+
+    // Interface Description Block，时间戳精度为默认的微秒
+    out.extend_from_slice(&1u32.to_le_bytes());
+    out.extend_from_slice(&20u32.to_le_bytes());
+    out.extend_from_slice(&LINKTYPE_RAW.to_le_bytes());
+    out.extend_from_slice(&0u16.to_le_bytes());
+    out.extend_from_slice(&0u32.to_le_bytes());
+    out.extend_from_slice(&20u32.to_le_bytes());
+
+    for (timestamp_us, packet) in packets {
+        let data = padded(packet);
+        let length = 32 + data.len() as u32;
+        out.extend_from_slice(&6u32.to_le_bytes());
+        out.extend_from_slice(&length.to_le_bytes());
+        out.extend_from_slice(&0u32.to_le_bytes());
+        out.extend_from_slice(&((timestamp_us >> 32) as u32).to_le_bytes());
+        out.extend_from_slice(&(*timestamp_us as u32).to_le_bytes());
+        out.extend_from_slice(&(packet.len() as u32).to_le_bytes());
+        out.extend_from_slice(&(packet.len() as u32).to_le_bytes());
+        out.extend_from_slice(&data);
+        out.extend_from_slice(&length.to_le_bytes());
+    }
+    out
+}
+
+fn write_pcap(packets: &[Packet]) -> Vec<u8> {
+    let mut out = Vec::new();
+    out.extend_from_slice(&0xA1B2_C3D4u32.to_le_bytes());
+    out.extend_from_slice(&2u16.to_le_bytes());
+    out.extend_from_slice(&4u16.to_le_bytes());
+    out.extend_from_slice(&0i32.to_le_bytes());
+    out.extend_from_slice(&0u32.to_le_bytes());
+    out.extend_from_slice(&65535u32.to_le_bytes());
+    out.extend_from_slice(&u32::from(LINKTYPE_RAW).to_le_bytes());
+
+    for (timestamp_us, packet) in packets {
+        out.extend_from_slice(&((timestamp_us / 1_000_000) as u32).to_le_bytes());
+        out.extend_from_slice(&((timestamp_us % 1_000_000) as u32).to_le_bytes());
+        out.extend_from_slice(&(packet.len() as u32).to_le_bytes());
+        out.extend_from_slice(&(packet.len() as u32).to_le_bytes());
+        out.extend_from_slice(packet);
+    }
+    out
+}
+
+/// 连接本身以及服务器或代理接受的所有连接
+async fn related_connections(state: &TcpToolState, connection_id: &str) -> Vec<ConnectionInfo> {
+    state
+        .connection_info
+        .read()
+        .await
+        .values()
+        .filter(|info| info.id == connection_id || info.server_id.as_deref() == Some(connection_id))
+        .cloned()
+        .collect()
+}
+
+/// 导出连接的流量；服务器或代理会一并导出它接受的所有连接
+#[tauri::command]
+pub(crate) async fn tcp_export_pcap<R: Runtime>(
+    app: AppHandle<R>,
+    connection_id: String,
+    path: String,
+    format: Option<String>,
+) -> Result<usize, String> {
+    let state = app.state::<TcpToolState>();
+    let connections = related_connections(&state, &connection_id).await;
+    if connections.is_empty() {
+        return Err("Connection not found".to_string());
+    }
+
+    let mut packets = Vec::new();
+    {
+        let captured = state.capture.messages.lock().await;
+        for info in &connections {
+            if let Some(messages) = captured.get(&info.id) {
+                packets.extend(connection_packets(info, messages)?);
+            }
+        }
+    }
+    if packets.is_empty() {
+        return Err("No captured traffic for this connection".to_string());
+    }
+    packets.sort_by_key(|(timestamp_us, _)| *timestamp_us);
+
+    let contents = match format.as_deref().unwrap_or("pcapng") {
+        "pcapng" => write_pcapng(&packets),
+        "pcap" => write_pcap(&packets),
+        other => return Err(format!("Unsupported capture format: {}", other)),
+    };
+    tokio::fs::write(&path, contents)
+        .await
+        .map_err(|e| format!("Failed to write {}: {}", path, e))?;
+
+    // 已断开的连接导出后不再需要缓存
+    for info in connections.iter().filter(|info| info.status == "disconnected") {
+        state.capture.remove(&info.id).await;
+    }
+
+    info!("Exported {} packets of {} to {}", packets.len(), connection_id, path);
+    Ok(packets.len())
+}
+
+#[tauri::command]
+pub(crate) async fn tcp_capture_clear<R: Runtime>(app: AppHandle<R>, connection_id: String) -> Result<(), String> {
+    let state = app.state::<TcpToolState>();
+    for info in related_connections(&state, &connection_id).await {
+        state.capture.remove(&info.id).await;
+    }
+    Ok(())
+}
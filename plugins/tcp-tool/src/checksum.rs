@@ -0,0 +1,206 @@
+use crc::{Crc, CRC_16_IBM_3740, CRC_16_KERMIT, CRC_16_MODBUS, CRC_16_XMODEM, CRC_32_ISO_HDLC, CRC_8_SMBUS};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::{AppHandle, Manager, Runtime};
+use tokio::sync::RwLock;
+use tracing::info;
+
+use crate::TcpToolState;
+
+const CRC8: Crc<u8> = Crc::<u8>::new(&CRC_8_SMBUS);
+const CRC16_MODBUS: Crc<u16> = Crc::<u16>::new(&CRC_16_MODBUS);
+const CRC16_CCITT_FALSE: Crc<u16> = Crc::<u16>::new(&CRC_16_IBM_3740);
+const CRC16_KERMIT: Crc<u16> = Crc::<u16>::new(&CRC_16_KERMIT);
+const CRC16_XMODEM: Crc<u16> = Crc::<u16>::new(&CRC_16_XMODEM);
+const CRC32: Crc<u32> = Crc::<u32>::new(&CRC_32_ISO_HDLC);
+
+/// 计算校验值，返回 (值, 字节数)
+fn value(algorithm: &str, data: &[u8]) -> Result<(u64, usize), String> {
+    match algorithm {
+        "sum8" => Ok((u64::from(data.iter().fold(0u8, |acc, b| acc.wrapping_add(*b))), 1)),
+        "xor8" => Ok((u64::from(data.iter().fold(0u8, |acc, b| acc ^ b)), 1)),
+        "crc8" => Ok((u64::from(CRC8.checksum(data)), 1)),
+        "crc16_modbus" => Ok((u64::from(CRC16_MODBUS.checksum(data)), 2)),
+        // "crc16_ccitt" 为旧名称，保留为 CCITT-FALSE 的别名
+        "crc16_ccitt_false" | "crc16_ccitt" => Ok((u64::from(CRC16_CCITT_FALSE.checksum(data)), 2)),
+        "crc16_kermit" => Ok((u64::from(CRC16_KERMIT.checksum(data)), 2)),
+        "crc16_xmodem" => Ok((u64::from(CRC16_XMODEM.checksum(data)), 2)),
+        "crc32" => Ok((u64::from(CRC32.checksum(data)), 4)),
+        other => Err(format!("Unsupported checksum algorithm: {}", other)),
+    }
+}
+
+/// 按协议惯例的默认字节序：Modbus 与 Kermit CRC 为小端，其余为大端
+fn default_little_endian(algorithm: &str) -> bool {
+    matches!(algorithm, "crc16_modbus" | "crc16_kermit")
+}
+
+fn encode(value: u64, width: usize, little_endian: bool) -> Vec<u8> {
+    let bytes = value.to_le_bytes()[..width].to_vec();
+    if little_endian {
+        bytes
+    } else {
+        bytes.into_iter().rev().collect()
+    }
+}
+
+/// 计算校验值，按协议惯例的字节序返回
+pub fn compute(algorithm: &str, data: &[u8]) -> Result<Vec<u8>, String> {
+    compute_with_endianness(algorithm, data, None)
+}
+
+/// 计算校验值，`endianness` 为 "big" 或 "little"，为空时使用算法的惯例字节序
+pub(crate) fn compute_with_endianness(algorithm: &str, data: &[u8], endianness: Option<&str>) -> Result<Vec<u8>, String> {
+    let (value, width) = value(algorithm, data)?;
+    let little_endian = match endianness {
+        Some("little") => true,
+        Some("big") => false,
+        _ => default_little_endian(algorithm),
+    };
+    Ok(encode(value, width, little_endian))
+}
+
+/// 算法的校验值字节数
+pub(crate) fn width(algorithm: &str) -> Result<usize, String> {
+    value(algorithm, &[]).map(|(_, width)| width)
+}
+
+/// 连接的校验设置：发送时在帧尾（`end_exclude` 字节的帧尾之前）插入校验值，接收时校验该位置的校验值
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChecksumSpec {
+    /// "sum8"、"xor8"、"crc8"、"crc16_modbus"、"crc16_ccitt_false"、"crc16_kermit"、"crc16_xmodem" 或 "crc32"
+    pub algorithm: String,
+    /// "big" 或 "little"，为空时使用算法的惯例字节序
+    #[serde(default)]
+    pub endianness: Option<String>,
+    /// 参与计算的起始偏移，用于跳过帧头
+    #[serde(default)]
+    pub start: usize,
+    /// 数据末尾不参与计算的字节数，用于跳过帧尾
+    #[serde(default)]
+    pub end_exclude: usize,
+    #[serde(default = "default_true")]
+    pub append: bool,
+    #[serde(default = "default_true")]
+    pub validate: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl ChecksumSpec {
+    fn check(&self) -> Result<(), String> {
+        value(&self.algorithm, &[])?;
+        match self.endianness.as_deref() {
+            None | Some("big") | Some("little") => Ok(()),
+            Some(other) => Err(format!("Unsupported endianness: {}", other)),
+        }
+    }
+
+    fn width(&self) -> usize {
+        width(&self.algorithm).unwrap_or_default()
+    }
+
+    /// 计算不含校验值的帧的校验值
+    fn checksum(&self, frame: &[u8]) -> Result<Vec<u8>, String> {
+        let end = frame.len().saturating_sub(self.end_exclude);
+        if self.start > end {
+            return Err(format!("Frame of {} bytes is shorter than the checksum range", frame.len()));
+        }
+        compute_with_endianness(&self.algorithm, &frame[self.start..end], self.endianness.as_deref())
+    }
+
+    /// 在 `end_exclude` 字节的帧尾之前插入校验值
+    fn insert(&self, mut data: Vec<u8>) -> Result<Vec<u8>, String> {
+        let checksum = self.checksum(&data)?;
+        let at = data.len().saturating_sub(self.end_exclude);
+        data.splice(at..at, checksum);
+        Ok(data)
+    }
+
+    /// 校验帧尾之前的校验值
+    fn verify(&self, data: &[u8]) -> bool {
+        let width = self.width();
+        let Some(at) = data.len().checked_sub(self.end_exclude + width) else {
+            return false;
+        };
+        let (head, rest) = data.split_at(at);
+        let (received, trailer) = rest.split_at(width);
+        let frame = [head, trailer].concat();
+        self.checksum(&frame).is_ok_and(|expected| expected == received)
+    }
+}
+
+/// 按连接保存的校验设置；服务器上的设置对其接受的连接生效，连接自身的设置优先
+#[derive(Default)]
+pub struct ChecksumRegistry {
+    specs: RwLock<HashMap<String, ChecksumSpec>>,
+}
+
+impl ChecksumRegistry {
+    async fn spec(&self, connection_id: &str, parent_id: Option<&str>) -> Option<ChecksumSpec> {
+        let specs = self.specs.read().await;
+        specs
+            .get(connection_id)
+            .or_else(|| parent_id.and_then(|id| specs.get(id)))
+            .cloned()
+    }
+
+    pub(crate) async fn remove(&self, connection_id: &str) {
+        self.specs.write().await.remove(connection_id);
+    }
+
+    /// 需要时在待发送的数据中插入校验值
+    pub(crate) async fn append(&self, connection_id: &str, parent_id: Option<&str>, data: Vec<u8>) -> Result<Vec<u8>, String> {
+        match self.spec(connection_id, parent_id).await.filter(|spec| spec.append) {
+            Some(spec) => spec.insert(data),
+            None => Ok(data),
+        }
+    }
+
+    /// 校验收到的数据，未设置校验时返回 `None`
+    pub(crate) async fn verify(&self, connection_id: &str, parent_id: Option<&str>, data: &[u8]) -> Option<bool> {
+        let spec = self.spec(connection_id, parent_id).await.filter(|spec| spec.validate)?;
+        Some(spec.verify(data))
+    }
+}
+
+/// 设置或清除（`spec` 为空时）连接的校验
+#[tauri::command]
+pub(crate) async fn tcp_set_checksum<R: Runtime>(
+    app: AppHandle<R>,
+    connection_id: String,
+    spec: Option<ChecksumSpec>,
+) -> Result<(), String> {
+    let state = app.state::<TcpToolState>();
+    if !state.connection_info.read().await.contains_key(&connection_id) {
+        return Err("Connection not found".to_string());
+    }
+
+    match spec {
+        Some(spec) => {
+            spec.check()?;
+            info!("Checksum on {}: {:?}", connection_id, spec);
+            state.checksums.specs.write().await.insert(connection_id, spec);
+        }
+        None => {
+            state.checksums.specs.write().await.remove(&connection_id);
+        }
+    }
+    Ok(())
+}
+
+/// 计算一段数据的校验值，供前端预览
+#[tauri::command]
+pub(crate) async fn tcp_compute_checksum<R: Runtime>(
+    _app: AppHandle<R>,
+    message: String,
+    encoding: Option<String>,
+    spec: ChecksumSpec,
+) -> Result<String, String> {
+    spec.check()?;
+    let data = network_common::payload::decode(&message, encoding.as_deref())?;
+    let checksum = spec.checksum(&data)?;
+    Ok(network_common::payload::to_hex(&checksum))
+}
@@ -0,0 +1,213 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Runtime};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tokio::sync::{Mutex, RwLock};
+use tracing::info;
+
+use crate::TcpToolState;
+
+/// 故障注入配置，作用于服务端或代理写出的数据
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FaultConfig {
+    /// 每次写出前增加的固定延迟（毫秒）
+    pub latency_ms: u64,
+    /// 在固定延迟之上随机增加 0..=jitter_ms 毫秒
+    pub jitter_ms: u64,
+    /// 带宽上限（字节/秒）
+    pub bandwidth: Option<u64>,
+    /// 将每次写出拆分为不超过该大小的片段
+    pub fragment_size: Option<usize>,
+    /// 片段之间的间隔（毫秒）
+    pub fragment_delay_ms: u64,
+    /// 每个字节被随机翻转一位的概率（0.0 - 1.0）
+    pub corrupt_probability: f64,
+    /// 累计写出该字节数后断开连接
+    pub drop_after_bytes: Option<u64>,
+    /// 累计写出该字节数后关闭写方向（半关闭），仍可继续接收
+    pub half_close_after_bytes: Option<u64>,
+    /// 代理会话中故障作用的方向：写给客户端的数据 "client"（默认）、写给上游的数据 "server" 或 "both"。
+    /// 两个方向分别计算 `drop_after_bytes` 和 `half_close_after_bytes`
+    pub side: Option<String>,
+}
+
+impl FaultConfig {
+    fn validate(&self) -> Result<(), String> {
+        if !(0.0..=1.0).contains(&self.corrupt_probability) {
+            return Err("Corrupt probability must be between 0 and 1".to_string());
+        }
+        if self.bandwidth == Some(0) {
+            return Err("Bandwidth must be greater than 0".to_string());
+        }
+        if self.fragment_size == Some(0) {
+            return Err("Fragment size must be greater than 0".to_string());
+        }
+        match self.side.as_deref() {
+            None | Some("client") | Some("server") | Some("both") => Ok(()),
+            Some(other) => Err(format!("Unsupported side: {}", other)),
+        }
+    }
+
+    /// 是否作用于写往 `side` 的数据，`side` 为空表示服务端连接，不区分方向
+    fn applies_to(&self, side: Option<&str>) -> bool {
+        match (self.side.as_deref().unwrap_or("client"), side) {
+            (_, None) | ("both", _) => true,
+            (configured, Some(side)) => configured == side,
+        }
+    }
+}
+
+/// 写出后需要执行的动作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FaultAction {
+    Drop,
+    HalfClose,
+}
+
+/// 一次写出的结果：实际写出的数据（可能被截断或篡改）以及后续动作
+pub(crate) struct FaultOutcome {
+    pub sent: Vec<u8>,
+    pub action: Option<FaultAction>,
+}
+
+/// 按连接保存的故障配置；服务器或代理上的配置对其所有连接生效，连接自身的配置优先
+#[derive(Default)]
+pub struct FaultRegistry {
+    configs: RwLock<HashMap<String, FaultConfig>>,
+    /// 每个连接各方向已写出的字节数，以及是否已半关闭
+    written: Mutex<HashMap<(String, String), (u64, bool)>>,
+}
+
+impl FaultRegistry {
+    async fn config(&self, connection_id: &str, parent_id: Option<&str>) -> Option<FaultConfig> {
+        let configs = self.configs.read().await;
+        configs
+            .get(connection_id)
+            .or_else(|| parent_id.and_then(|id| configs.get(id)))
+            .cloned()
+    }
+
+    pub(crate) async fn remove(&self, connection_id: &str) {
+        self.configs.write().await.remove(connection_id);
+        self.written.lock().await.retain(|(id, _), _| id != connection_id);
+    }
+
+    /// 按故障配置写出数据；未配置故障或故障不作用于该方向时直接写出。
+    /// `side` 为代理会话中数据写往的一侧（"client" 或 "server"），服务端连接为空
+    pub(crate) async fn write<W: AsyncWrite + Unpin>(
+        &self,
+        connection_id: &str,
+        parent_id: Option<&str>,
+        side: Option<&str>,
+        writer: &mut W,
+        data: &[u8],
+    ) -> std::io::Result<FaultOutcome> {
+        let config = self.config(connection_id, parent_id).await.filter(|config| config.applies_to(side));
+        let Some(config) = config else {
+            writer.write_all(data).await?;
+            return Ok(FaultOutcome { sent: data.to_vec(), action: None });
+        };
+
+        let key = (connection_id.to_string(), side.unwrap_or_default().to_string());
+        let (written, half_closed) = self.written.lock().await.get(&key).copied().unwrap_or_default();
+        if half_closed {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::BrokenPipe,
+                "write side has been closed by fault injection",
+            ));
+        }
+
+        // 截断到断开或半关闭的字节阈值
+        let limits = [
+            config.drop_after_bytes.map(|n| (n, FaultAction::Drop)),
+            config.half_close_after_bytes.map(|n| (n, FaultAction::HalfClose)),
+        ];
+        let limit = limits.into_iter().flatten().min_by_key(|(n, _)| *n);
+        let (mut sent, action) = match limit {
+            Some((n, action)) if written + data.len() as u64 >= n => {
+                (data[..n.saturating_sub(written) as usize].to_vec(), Some(action))
+            }
+            _ => (data.to_vec(), None),
+        };
+
+        let delay = {
+            let mut rng = rand::thread_rng();
+            if config.corrupt_probability > 0.0 {
+                for byte in sent.iter_mut() {
+                    if rng.gen_bool(config.corrupt_probability) {
+                        *byte ^= 1 << rng.gen_range(0..8);
+                    }
+                }
+            }
+            config.latency_ms + if config.jitter_ms > 0 { rng.gen_range(0..=config.jitter_ms) } else { 0 }
+        };
+        tokio::time::sleep(Duration::from_millis(delay)).await;
+
+        let fragment_size = config.fragment_size.unwrap_or(sent.len()).max(1);
+        for (index, fragment) in sent.chunks(fragment_size).enumerate() {
+            if index > 0 && config.fragment_delay_ms > 0 {
+                tokio::time::sleep(Duration::from_millis(config.fragment_delay_ms)).await;
+            }
+            if let Some(bandwidth) = config.bandwidth {
+                tokio::time::sleep(Duration::from_secs_f64(fragment.len() as f64 / bandwidth as f64)).await;
+            }
+            writer.write_all(fragment).await?;
+            writer.flush().await?;
+        }
+
+        if action == Some(FaultAction::HalfClose) {
+            writer.shutdown().await?;
+        }
+        self.written.lock().await.insert(
+            key,
+            (written + sent.len() as u64, action == Some(FaultAction::HalfClose)),
+        );
+        Ok(FaultOutcome { sent, action })
+    }
+}
+
+/// 设置或清除（`faults` 为空时）连接的故障注入，立即对之后的写出生效
+#[tauri::command]
+pub(crate) async fn tcp_set_faults<R: Runtime>(
+    app: AppHandle<R>,
+    connection_id: String,
+    faults: Option<FaultConfig>,
+) -> Result<(), String> {
+    let state = app.state::<TcpToolState>();
+    let connection_type = state
+        .connection_info
+        .read()
+        .await
+        .get(&connection_id)
+        .map(|info| info.connection_type.clone())
+        .ok_or_else(|| "Connection not found".to_string())?;
+    if !matches!(connection_type.as_str(), "server" | "accepted" | "proxy" | "proxy_session") {
+        return Err("Fault injection is only available for server and proxy connections".to_string());
+    }
+
+    match faults {
+        Some(faults) => {
+            faults.validate()?;
+            info!("Fault injection on {}: {:?}", connection_id, faults);
+            state.faults.configs.write().await.insert(connection_id, faults);
+        }
+        None => {
+            info!("Fault injection on {} cleared", connection_id);
+            state.faults.configs.write().await.remove(&connection_id);
+        }
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) async fn tcp_get_faults<R: Runtime>(
+    app: AppHandle<R>,
+    connection_id: String,
+) -> Result<Option<FaultConfig>, String> {
+    let state = app.state::<TcpToolState>();
+    let faults = state.faults.configs.read().await.get(&connection_id).cloned();
+    Ok(faults)
+}
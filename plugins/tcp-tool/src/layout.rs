@@ -0,0 +1,391 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::{AppHandle, Manager, Runtime};
+use tokio::sync::RwLock;
+use tracing::info;
+use network_common::payload;
+
+use crate::{save_config, TcpToolState};
+
+/// 二进制帧结构定义，随 tcp-tool 配置保存
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FrameLayout {
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub fields: Vec<LayoutField>,
+    /// 自动用于服务端口在列表中的连接：客户端和 UDP 取对端端口，服务端接受的连接取监听端口。
+    /// 通过 `tcp_set_frame_layout` 指定的结构优先
+    #[serde(default)]
+    pub ports: Vec<u16>,
+}
+
+/// 帧中的一个字段
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LayoutField {
+    pub name: String,
+    /// "u8"、"u16"、"u32"、"u64"、"i8"、"i16"、"i32"、"i64"、"f32"、"f64"、"bytes" 或 "string"
+    pub field_type: String,
+    /// 多字节数值的字节序，"big"（默认）或 "little"
+    #[serde(default)]
+    pub endianness: Option<String>,
+    /// bytes/string 字段的固定字节数
+    #[serde(default)]
+    pub length: Option<usize>,
+    /// bytes/string 字段的字节数取自前面某个整数字段的值，
+    /// 与 `length` 都为空时字段占用帧的剩余部分
+    #[serde(default)]
+    pub length_field: Option<String>,
+    /// 整数字段的枚举值名称
+    #[serde(default)]
+    pub enum_values: Vec<EnumValue>,
+    /// 整数字段中的位域，解码为子字段
+    #[serde(default)]
+    pub bits: Vec<BitField>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnumValue {
+    pub value: i64,
+    pub name: String,
+}
+
+/// 位域，`bit` 为最低位的位置（0 为最低位）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BitField {
+    pub name: String,
+    pub bit: u32,
+    #[serde(default = "default_bit_width")]
+    pub width: u32,
+    #[serde(default)]
+    pub enum_values: Vec<EnumValue>,
+}
+
+fn default_bit_width() -> u32 {
+    1
+}
+
+/// 解码后的字段树节点
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecodedField {
+    pub name: String,
+    pub offset: usize,
+    pub size: usize,
+    pub hex: String,
+    /// 数值为十进制，bytes 为十六进制，string 为文本
+    pub value: String,
+    /// 枚举值对应的名称
+    #[serde(default)]
+    pub label: Option<String>,
+    #[serde(default)]
+    pub children: Vec<DecodedField>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecodedFrame {
+    pub layout_id: String,
+    pub layout_name: String,
+    pub fields: Vec<DecodedField>,
+    /// 结构之外未解码的字节数
+    pub remaining: usize,
+    /// 数据不足或字段无法解码时的错误，已解码的字段仍然保留
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+impl LayoutField {
+    fn little_endian(&self) -> Result<bool, String> {
+        match self.endianness.as_deref() {
+            None | Some("big") => Ok(false),
+            Some("little") => Ok(true),
+            Some(other) => Err(format!("Unsupported endianness: {}", other)),
+        }
+    }
+
+    /// 整数类型的 (字节数, 是否有符号)
+    fn integer(&self) -> Option<(usize, bool)> {
+        match self.field_type.as_str() {
+            "u8" => Some((1, false)),
+            "u16" => Some((2, false)),
+            "u32" => Some((4, false)),
+            "u64" => Some((8, false)),
+            "i8" => Some((1, true)),
+            "i16" => Some((2, true)),
+            "i32" => Some((4, true)),
+            "i64" => Some((8, true)),
+            _ => None,
+        }
+    }
+}
+
+fn label(enum_values: &[EnumValue], value: i128) -> Option<String> {
+    enum_values
+        .iter()
+        .find(|entry| i128::from(entry.value) == value)
+        .map(|entry| entry.name.clone())
+}
+
+/// 按字节序读取无符号整数
+fn read_unsigned(bytes: &[u8], little_endian: bool) -> u64 {
+    let fold = |acc: u64, b: &u8| (acc << 8) | u64::from(*b);
+    if little_endian {
+        bytes.iter().rev().fold(0, fold)
+    } else {
+        bytes.iter().fold(0, fold)
+    }
+}
+
+/// 检查结构定义
+pub(crate) fn validate(layout: &FrameLayout) -> Result<(), String> {
+    if layout.name.trim().is_empty() {
+        return Err("Layout name is required".to_string());
+    }
+    if layout.fields.is_empty() {
+        return Err("Layout has no fields".to_string());
+    }
+    for (index, field) in layout.fields.iter().enumerate() {
+        let previous = &layout.fields[..index];
+        if previous.iter().any(|other| other.name == field.name) {
+            return Err(format!("Duplicate field name: {}", field.name));
+        }
+        field.little_endian()?;
+        match (field.field_type.as_str(), field.integer()) {
+            (_, Some((size, _))) => {
+                if let Some(bit) = field.bits.iter().find(|bit| bit.width == 0 || bit.bit.saturating_add(bit.width) > size as u32 * 8) {
+                    return Err(format!("Field {}: bit field {} does not fit in {} bits", field.name, bit.name, size * 8));
+                }
+            }
+            ("f32" | "f64", None) => {}
+            ("bytes" | "string", None) => {
+                if let Some(name) = &field.length_field {
+                    if !previous.iter().any(|other| &other.name == name && other.integer().is_some()) {
+                        return Err(format!("Field {}: length field {} must be an earlier integer field", field.name, name));
+                    }
+                }
+            }
+            (other, None) => return Err(format!("Field {}: unsupported type {}", field.name, other)),
+        }
+    }
+    Ok(())
+}
+
+/// 按结构解码一帧数据。数据不足时停止解码并在 `error` 中说明
+pub fn decode(layout: &FrameLayout, data: &[u8]) -> DecodedFrame {
+    let mut fields = Vec::with_capacity(layout.fields.len());
+    let mut values: HashMap<&str, i128> = HashMap::new();
+    let mut offset = 0;
+    let mut error = None;
+
+    for field in &layout.fields {
+        let little_endian = field.little_endian().unwrap_or_default();
+        let size = match (field.field_type.as_str(), field.integer()) {
+            (_, Some((size, _))) => Some(size),
+            ("f32", _) => Some(4),
+            ("f64", _) => Some(8),
+            _ => match (&field.length_field, field.length) {
+                (Some(name), _) => values.get(name.as_str()).and_then(|value| usize::try_from(*value).ok()),
+                (None, Some(length)) => Some(length),
+                (None, None) => Some(data.len() - offset),
+            },
+        };
+        let Some(size) = size.filter(|size| *size <= data.len() - offset) else {
+            error = Some(format!(
+                "Frame too short for field {} at offset {} ({} bytes left)",
+                field.name,
+                offset,
+                data.len() - offset
+            ));
+            break;
+        };
+
+        let bytes = &data[offset..offset + size];
+        let mut decoded = DecodedField {
+            name: field.name.clone(),
+            offset,
+            size,
+            hex: payload::to_hex(bytes),
+            value: String::new(),
+            label: None,
+            children: Vec::new(),
+        };
+        match (field.field_type.as_str(), field.integer()) {
+            (_, Some((size, signed))) => {
+                let raw = read_unsigned(bytes, little_endian);
+                let bits = size as u32 * 8;
+                // 有符号数按位宽做符号扩展
+                let value = if signed {
+                    i128::from(((raw << (64 - bits)) as i64) >> (64 - bits))
+                } else {
+                    i128::from(raw)
+                };
+                values.insert(field.name.as_str(), value);
+                decoded.value = value.to_string();
+                decoded.label = label(&field.enum_values, value);
+                decoded.children = field
+                    .bits
+                    .iter()
+                    .map(|bit| {
+                        let mask = if bit.width >= 64 { u64::MAX } else { (1u64 << bit.width) - 1 };
+                        let value = raw.checked_shr(bit.bit).unwrap_or_default() & mask;
+                        DecodedField {
+                            name: bit.name.clone(),
+                            offset,
+                            size,
+                            hex: decoded.hex.clone(),
+                            value: value.to_string(),
+                            label: label(&bit.enum_values, i128::from(value)),
+                            children: Vec::new(),
+                        }
+                    })
+                    .collect();
+            }
+            ("f32", _) => {
+                decoded.value = f32::from_bits(read_unsigned(bytes, little_endian) as u32).to_string();
+            }
+            ("f64", _) => {
+                decoded.value = f64::from_bits(read_unsigned(bytes, little_endian)).to_string();
+            }
+            ("string", _) => {
+                decoded.value = String::from_utf8_lossy(bytes).trim_end_matches('\0').to_string();
+            }
+            _ => decoded.value = decoded.hex.clone(),
+        }
+        fields.push(decoded);
+        offset += size;
+    }
+
+    DecodedFrame {
+        layout_id: layout.id.clone(),
+        layout_name: layout.name.clone(),
+        fields,
+        remaining: data.len() - offset,
+        error,
+    }
+}
+
+/// 从 "host:port" 形式的地址中取端口
+fn port_of(addr: &str) -> Option<u16> {
+    addr.rsplit_once(':').and_then(|(_, port)| port.parse().ok())
+}
+
+/// 按连接指定的帧结构；服务器上的设置对其接受的连接生效，连接自身的设置优先
+#[derive(Default)]
+pub struct LayoutRegistry {
+    assignments: RwLock<HashMap<String, String>>,
+}
+
+impl LayoutRegistry {
+    pub(crate) async fn remove(&self, connection_id: &str) {
+        self.assignments.write().await.remove(connection_id);
+    }
+
+    /// 解码收到的数据：先查连接或其服务器指定的结构，再按服务端口匹配，都没有时返回 `None`。
+    /// 配置文件中手工修改过的无效结构会被跳过
+    pub(crate) async fn decode(
+        &self,
+        layouts: &[FrameLayout],
+        connection_id: &str,
+        parent_id: Option<&str>,
+        service_addr: Option<&str>,
+        data: &[u8],
+    ) -> Option<DecodedFrame> {
+        let assigned = {
+            let assignments = self.assignments.read().await;
+            assignments
+                .get(connection_id)
+                .or_else(|| parent_id.and_then(|id| assignments.get(id)))
+                .cloned()
+        };
+        let mut valid = layouts.iter().filter(|layout| validate(layout).is_ok());
+        let layout = match assigned {
+            Some(layout_id) => valid.find(|layout| layout.id == layout_id),
+            None => {
+                let port = service_addr.and_then(port_of)?;
+                valid.find(|layout| layout.ports.contains(&port))
+            }
+        }?;
+        Some(decode(layout, data))
+    }
+}
+
+#[tauri::command]
+pub(crate) async fn layout_get_all<R: Runtime>(app: AppHandle<R>) -> Result<Vec<FrameLayout>, String> {
+    let state = app.state::<TcpToolState>();
+    let layouts = state.config.read().await.frame_layouts.clone();
+    Ok(layouts)
+}
+
+/// 新增或更新帧结构（按 id），id 为空时自动生成
+#[tauri::command]
+pub(crate) async fn layout_save<R: Runtime>(app: AppHandle<R>, mut layout: FrameLayout) -> Result<FrameLayout, String> {
+    validate(&layout)?;
+    if layout.id.is_empty() {
+        layout.id = format!("layout_{}", uuid::Uuid::new_v4().simple());
+    }
+
+    let state = app.state::<TcpToolState>();
+    {
+        let mut config = state.config.write().await;
+        match config.frame_layouts.iter_mut().find(|l| l.id == layout.id) {
+            Some(existing) => *existing = layout.clone(),
+            None => config.frame_layouts.push(layout.clone()),
+        }
+    }
+    save_config(&app).await?;
+
+    info!("Frame layout {} saved", layout.id);
+    Ok(layout)
+}
+
+#[tauri::command]
+pub(crate) async fn layout_remove<R: Runtime>(app: AppHandle<R>, layout_id: String) -> Result<(), String> {
+    let state = app.state::<TcpToolState>();
+    state.config.write().await.frame_layouts.retain(|l| l.id != layout_id);
+    state.layouts.assignments.write().await.retain(|_, id| *id != layout_id);
+    save_config(&app).await?;
+
+    info!("Frame layout {} removed", layout_id);
+    Ok(())
+}
+
+/// 为连接指定或清除（`layout_id` 为空时）帧结构
+#[tauri::command]
+pub(crate) async fn tcp_set_frame_layout<R: Runtime>(
+    app: AppHandle<R>,
+    connection_id: String,
+    layout_id: Option<String>,
+) -> Result<(), String> {
+    let state = app.state::<TcpToolState>();
+    if !state.connection_info.read().await.contains_key(&connection_id) {
+        return Err("Connection not found".to_string());
+    }
+
+    match layout_id {
+        Some(layout_id) => {
+            if !state.config.read().await.frame_layouts.iter().any(|l| l.id == layout_id) {
+                return Err(format!("Layout not found: {}", layout_id));
+            }
+            info!("Frame layout on {}: {}", connection_id, layout_id);
+            state.layouts.assignments.write().await.insert(connection_id, layout_id);
+        }
+        None => {
+            state.layouts.assignments.write().await.remove(&connection_id);
+        }
+    }
+    Ok(())
+}
+
+/// 按结构解码一段数据，供编辑结构时预览，结构可以尚未保存
+#[tauri::command]
+pub(crate) async fn layout_decode<R: Runtime>(
+    _app: AppHandle<R>,
+    layout: FrameLayout,
+    message: String,
+    encoding: Option<String>,
+) -> Result<DecodedFrame, String> {
+    validate(&layout)?;
+    let data = payload::decode(&message, encoding.as_deref())?;
+    Ok(decode(&layout, &data))
+}
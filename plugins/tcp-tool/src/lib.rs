@@ -1,13 +1,47 @@
+mod bench;
+mod capture;
+mod checksum;
+mod faults;
+mod layout;
+mod modbus;
+mod proxy;
+mod record;
+mod responder;
+mod schedule;
+mod server;
+mod socket;
+mod stats;
+mod template;
+mod tls;
+mod udp;
+mod websocket;
+
 use serde::{Deserialize, Serialize};
 use tauri::{plugin::TauriPlugin, Runtime, Manager, AppHandle, Emitter};
 use tracing::{info, error, warn};
-use tokio::net::{TcpListener, TcpStream};
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::task::JoinHandle;
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::{Mutex, RwLock};
-use std::net::SocketAddr;
-use chrono::{DateTime, Utc};
+use chrono::Utc;
+
+pub use bench::{BenchmarkOptions, BenchmarkReport, LatencySummary};
+pub use checksum::ChecksumSpec;
+pub use faults::FaultConfig;
+pub use layout::{BitField, DecodedField, DecodedFrame, EnumValue, FrameLayout, LayoutField};
+pub use modbus::{ModbusException, ModbusResponse, ModbusTable, RegisterUpdate};
+pub use socket::{ConnectOptions, NetworkInterface, SocketOptions};
+pub use stats::{ConnectionStats, TrafficSample};
+pub use proxy::InterceptedFrame;
+pub use record::{RecordingSummary, ReplayProgress, SessionHeader, SessionRecord};
+pub use responder::ResponderRule;
+pub use schedule::{ScheduleInfo, ScheduleStep};
+pub use template::{MessageTemplate, RenderedField, RenderedTemplate, TemplateField};
+pub use tls::{PeerCertificate, TlsClientOptions, TlsInfo, TlsServerOptions};
+use socket::Backoff;
+use tls::TlsClient;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TcpToolConfig {
@@ -15,8 +49,68 @@ pub struct TcpToolConfig {
     pub default_client_host: String,
     pub default_client_port: u16,
     pub default_server_port: u16,
+    /// 服务端默认监听地址，可为 IPv4/IPv6 地址或 "::"
+    #[serde(default = "default_bind_address")]
+    pub default_bind_address: String,
     pub auto_reconnect: bool,
     pub connection_timeout: u64,
+    /// TCP keepalive 空闲时间（秒），为空时不启用
+    #[serde(default)]
+    pub keepalive: Option<u64>,
+    #[serde(default)]
+    pub nodelay: bool,
+    /// SO_LINGER 时间（秒），为空时使用系统默认行为
+    #[serde(default)]
+    pub linger: Option<u64>,
+    #[serde(default = "default_reconnect_initial_delay")]
+    pub reconnect_initial_delay: u64,
+    #[serde(default = "default_reconnect_max_delay")]
+    pub reconnect_max_delay: u64,
+    /// 最大重连次数，0 表示不限制
+    #[serde(default)]
+    pub reconnect_max_attempts: u32,
+    /// 每个连接缓存的最近消息条数，用于导出 pcap，0 表示不缓存
+    #[serde(default = "default_capture_limit")]
+    pub capture_limit: usize,
+    /// 服务端自动应答规则，按顺序匹配
+    #[serde(default)]
+    pub responder_rules: Vec<ResponderRule>,
+    /// 流量统计的采样间隔（毫秒）
+    #[serde(default = "default_stats_interval_ms")]
+    pub stats_interval_ms: u64,
+    /// 每个连接保留的采样点数
+    #[serde(default = "default_stats_history_size")]
+    pub stats_history_size: usize,
+    /// 报文模板库
+    #[serde(default)]
+    pub templates: Vec<MessageTemplate>,
+    /// 接收数据的帧结构定义
+    #[serde(default)]
+    pub frame_layouts: Vec<FrameLayout>,
+}
+
+fn default_bind_address() -> String {
+    "127.0.0.1".to_string()
+}
+
+fn default_reconnect_initial_delay() -> u64 {
+    500
+}
+
+fn default_reconnect_max_delay() -> u64 {
+    30000
+}
+
+fn default_capture_limit() -> usize {
+    10000
+}
+
+fn default_stats_interval_ms() -> u64 {
+    1000
+}
+
+fn default_stats_history_size() -> usize {
+    300
 }
 
 impl Default for TcpToolConfig {
@@ -26,8 +120,21 @@ impl Default for TcpToolConfig {
             default_client_host: "127.0.0.1".to_string(),
             default_client_port: 8080,
             default_server_port: 8080,
+            default_bind_address: default_bind_address(),
             auto_reconnect: false,
             connection_timeout: 5000,
+            keepalive: None,
+            nodelay: false,
+            linger: None,
+            reconnect_initial_delay: default_reconnect_initial_delay(),
+            reconnect_max_delay: default_reconnect_max_delay(),
+            reconnect_max_attempts: 0,
+            capture_limit: default_capture_limit(),
+            responder_rules: Vec::new(),
+            stats_interval_ms: default_stats_interval_ms(),
+            stats_history_size: default_stats_history_size(),
+            templates: Vec::new(),
+            frame_layouts: Vec::new(),
         }
     }
 }
@@ -35,13 +142,50 @@ impl Default for TcpToolConfig {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConnectionInfo {
     pub id: String,
-    pub connection_type: String, // "client" or "server"
+    pub connection_type: String, // "client", "server", "accepted" or "udp"
     pub local_addr: String,
     pub remote_addr: Option<String>,
-    pub status: String, // "connected", "disconnected", "connecting", "listening"
+    pub status: String, // "connected", "disconnected", "connecting", "reconnecting", "listening", "bound"
     pub created_at: String,
     pub bytes_sent: u64,
     pub bytes_received: u64,
+    #[serde(default)]
+    pub messages_sent: u64,
+    #[serde(default)]
+    pub messages_received: u64,
+    /// 最近一次收发数据的时间
+    #[serde(default)]
+    pub last_activity: Option<String>,
+    /// 服务端接受的连接所属的服务器 ID
+    #[serde(default)]
+    pub server_id: Option<String>,
+    /// TLS 连接协商的会话信息
+    #[serde(default)]
+    pub tls: Option<TlsInfo>,
+    /// WebSocket 握手协商的子协议
+    #[serde(default)]
+    pub subprotocol: Option<String>,
+}
+
+impl ConnectionInfo {
+    fn new(id: &str, connection_type: &str, local_addr: String, remote_addr: Option<String>, status: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            connection_type: connection_type.to_string(),
+            local_addr,
+            remote_addr,
+            status: status.to_string(),
+            created_at: Utc::now().to_rfc3339(),
+            bytes_sent: 0,
+            bytes_received: 0,
+            messages_sent: 0,
+            messages_received: 0,
+            last_activity: None,
+            server_id: None,
+            tls: None,
+            subprotocol: None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -51,27 +195,196 @@ pub struct MessageData {
     pub timestamp: String,
     pub direction: String, // "sent" or "received"
     pub size: usize,
+    /// 数据来源或目标地址，UDP 报文使用
+    #[serde(default)]
+    pub remote_addr: Option<String>,
+    /// WebSocket 帧类型："text"、"binary"、"ping"、"pong" 或 "close"
+    #[serde(default)]
+    pub frame_type: Option<String>,
+    /// 代理转发的数据所在的一侧："client" 或 "server"
+    #[serde(default)]
+    pub side: Option<String>,
+    /// 接收数据的帧尾校验结果，未设置校验时为空
+    #[serde(default)]
+    pub checksum_valid: Option<bool>,
+    /// 按连接或端口对应的帧结构解码的字段树，仅用于接收的数据
+    #[serde(default)]
+    pub decoded: Option<DecodedFrame>,
+}
+
+impl MessageData {
+    fn new(connection_id: &str, data: &[u8], direction: &str, remote_addr: Option<String>) -> Self {
+        Self {
+            connection_id: connection_id.to_string(),
+            content: String::from_utf8_lossy(data).into_owned(),
+            timestamp: Utc::now().to_rfc3339(),
+            direction: direction.to_string(),
+            size: data.len(),
+            remote_addr,
+            frame_type: None,
+            side: None,
+            checksum_valid: None,
+            decoded: None,
+        }
+    }
 }
 
-type ConnectionMap = Arc<RwLock<HashMap<String, Arc<Mutex<TcpStream>>>>>;
-type ServerMap = Arc<RwLock<HashMap<String, Arc<Mutex<TcpListener>>>>>;
+type BoxedReader = Box<dyn AsyncRead + Send + Unpin>;
+type BoxedWriter = Box<dyn AsyncWrite + Send + Unpin>;
+type ConnectionMap = Arc<RwLock<HashMap<String, Arc<Mutex<BoxedWriter>>>>>;
+type TaskMap = Arc<RwLock<HashMap<String, JoinHandle<()>>>>;
+type UdpMap = Arc<RwLock<HashMap<String, udp::UdpEndpoint>>>;
+type WebSocketMap = Arc<RwLock<HashMap<String, websocket::WsHandle>>>;
 
 pub struct TcpToolState {
-    config: TcpToolConfig,
+    config: Arc<RwLock<TcpToolConfig>>,
     connections: ConnectionMap,
-    servers: ServerMap,
+    tasks: TaskMap,
+    udp_sockets: UdpMap,
+    websockets: WebSocketMap,
+    proxy: proxy::ProxyRegistry,
+    recorder: record::RecorderRegistry,
+    capture: capture::CaptureBuffer,
+    responder: responder::ResponderRegistry,
+    schedules: schedule::ScheduleRegistry,
+    benchmarks: bench::BenchmarkRegistry,
+    stats: stats::StatsRegistry,
+    faults: faults::FaultRegistry,
+    checksums: checksum::ChecksumRegistry,
+    layouts: layout::LayoutRegistry,
+    modbus: modbus::ModbusRegistry,
     connection_info: Arc<RwLock<HashMap<String, ConnectionInfo>>>,
 }
 
 impl TcpToolState {
     pub fn new(config: TcpToolConfig) -> Self {
         Self {
-            config,
+            config: Arc::new(RwLock::new(config)),
             connections: Arc::new(RwLock::new(HashMap::new())),
-            servers: Arc::new(RwLock::new(HashMap::new())),
+            tasks: Arc::new(RwLock::new(HashMap::new())),
+            udp_sockets: Arc::new(RwLock::new(HashMap::new())),
+            websockets: Arc::new(RwLock::new(HashMap::new())),
+            proxy: proxy::ProxyRegistry::default(),
+            recorder: record::RecorderRegistry::default(),
+            capture: capture::CaptureBuffer::default(),
+            responder: responder::ResponderRegistry::default(),
+            schedules: schedule::ScheduleRegistry::default(),
+            benchmarks: bench::BenchmarkRegistry::default(),
+            stats: stats::StatsRegistry::default(),
+            faults: faults::FaultRegistry::default(),
+            checksums: checksum::ChecksumRegistry::default(),
+            layouts: layout::LayoutRegistry::default(),
+            modbus: modbus::ModbusRegistry::default(),
             connection_info: Arc::new(RwLock::new(HashMap::new())),
         }
     }
+
+    /// 更新连接状态并返回更新后的连接信息
+    async fn set_status(&self, connection_id: &str, status: &str) -> Option<ConnectionInfo> {
+        let mut infos = self.connection_info.write().await;
+        let info = infos.get_mut(connection_id)?;
+        info.status = status.to_string();
+        Some(info.clone())
+    }
+
+    /// 连接结束时释放按连接保存的运行时数据并标记为断开，返回断开后的连接信息。
+    /// 无论主动断开还是对端关闭都经过这里：录制写完并关闭文件，随之取消的定时发送通过事件通知前端
+    async fn disconnected<R: Runtime>(&self, app: &AppHandle<R>, connection_id: &str) -> Option<ConnectionInfo> {
+        self.recorder.stop(connection_id).await;
+        for schedule in self.schedules.cancel_connection(connection_id).await {
+            emit_event(app, "tcp_schedule_updated", &schedule);
+        }
+        self.faults.remove(connection_id).await;
+        self.checksums.remove(connection_id).await;
+        self.layouts.remove(connection_id).await;
+        self.modbus.remove(connection_id).await;
+        self.capture.closed(connection_id).await;
+        self.stats.remove(connection_id).await;
+        self.set_status(connection_id, "disconnected").await
+    }
+
+    /// 按连接或其服务器的校验设置在数据末尾附加校验值
+    async fn with_checksum(&self, connection_id: &str, data: Vec<u8>) -> Result<Vec<u8>, String> {
+        let server_id = self
+            .connection_info
+            .read()
+            .await
+            .get(connection_id)
+            .and_then(|info| info.server_id.clone());
+        self.checksums.append(connection_id, server_id.as_deref(), data).await
+    }
+
+    /// 停止连接的后台任务并关闭写入端，返回断开后的连接信息
+    async fn close_connection<R: Runtime>(&self, app: &AppHandle<R>, connection_id: &str) -> Option<ConnectionInfo> {
+        if let Some(task) = self.tasks.write().await.remove(connection_id) {
+            task.abort();
+        }
+        if let Some(writer) = self.connections.write().await.remove(connection_id) {
+            let _ = writer.lock().await.shutdown().await;
+        }
+        self.udp_sockets.write().await.remove(connection_id);
+        self.websockets.write().await.remove(connection_id);
+        self.proxy.remove(connection_id).await;
+        self.disconnected(app, connection_id).await
+    }
+}
+
+fn emit_event<R: Runtime, S: Serialize + Clone>(app: &AppHandle<R>, event: &str, payload: S) {
+    if let Err(e) = app.emit(event, payload) {
+        warn!("Failed to emit {}: {}", event, e);
+    }
+}
+
+/// 所有收发的数据都经过这里：更新流量计数、写入录制文件和抓包缓存并通知前端
+async fn publish_message<R: Runtime>(app: &AppHandle<R>, message: &MessageData, data: &[u8]) {
+    let state = app.state::<TcpToolState>();
+    let sent = message.direction == "sent";
+
+    let mut server_id = None;
+    let mut service_addr = None;
+    if let Some(info) = state.connection_info.write().await.get_mut(&message.connection_id) {
+        server_id = info.server_id.clone();
+        // 帧结构按服务端口匹配：服务端接受的连接取本地（监听）端口，UDP 取报文来源，客户端取对端；
+        // 代理会话的本端地址是客户端地址，取上游的端口
+        service_addr = if info.connection_type == "proxy_session" {
+            info.remote_addr.clone()
+        } else if info.server_id.is_some() {
+            Some(info.local_addr.clone())
+        } else if info.connection_type == "udp" {
+            message.remote_addr.clone().or_else(|| info.remote_addr.clone())
+        } else {
+            info.remote_addr.clone().or_else(|| Some(info.local_addr.clone()))
+        };
+        if sent {
+            info.bytes_sent += data.len() as u64;
+            info.messages_sent += 1;
+        } else {
+            info.bytes_received += data.len() as u64;
+            info.messages_received += 1;
+        }
+        info.last_activity = Some(message.timestamp.clone());
+    }
+    state.recorder.record(message, data).await;
+    let capture_limit = state.config.read().await.capture_limit;
+    state.capture.record(message, data, capture_limit).await;
+
+    if sent {
+        emit_event(app, "tcp_message_sent", message);
+        return;
+    }
+    let checksum_valid = state.checksums.verify(&message.connection_id, server_id.as_deref(), data).await;
+    let decoded = {
+        let config = state.config.read().await;
+        state
+            .layouts
+            .decode(&config.frame_layouts, &message.connection_id, server_id.as_deref(), service_addr.as_deref(), data)
+            .await
+    };
+    if checksum_valid.is_none() && decoded.is_none() {
+        emit_event(app, "tcp_message_received", message);
+    } else {
+        emit_event(app, "tcp_message_received", MessageData { checksum_valid, decoded, ..message.clone() });
+    }
 }
 
 #[tauri::command]
@@ -79,18 +392,108 @@ async fn get_config<R: Runtime>(
     app: AppHandle<R>,
 ) -> Result<TcpToolConfig, String> {
     let state = app.state::<TcpToolState>();
-    Ok(state.config.clone())
+    let config = state.config.read().await.clone();
+    Ok(config)
 }
 
 #[tauri::command]
 async fn update_config<R: Runtime>(
     app: AppHandle<R>,
-    config: TcpToolConfig,
+    mut config: TcpToolConfig,
 ) -> Result<(), String> {
     let state = app.state::<TcpToolState>();
-    // 这里应该实现配置更新逻辑
-    info!("tcp-tool config updated: {:?}", config);
-    Ok(())
+    {
+        let mut current = state.config.write().await;
+        // 应答规则、模板和帧结构由各自的命令维护，这里保留当前内容，避免前端的旧副本覆盖
+        config.responder_rules = std::mem::take(&mut current.responder_rules);
+        config.templates = std::mem::take(&mut current.templates);
+        config.frame_layouts = std::mem::take(&mut current.frame_layouts);
+        *current = config;
+    }
+    info!("tcp-tool config updated");
+    save_config(&app).await
+}
+
+fn config_path<R: Runtime>(app: &AppHandle<R>) -> Option<PathBuf> {
+    app.path().app_config_dir().ok().map(|dir| dir.join("tcp-tool.json"))
+}
+
+/// 读取保存的配置，文件不存在或无法解析时使用默认配置
+fn load_config<R: Runtime>(app: &AppHandle<R>) -> TcpToolConfig {
+    let Some(path) = config_path(app) else {
+        return TcpToolConfig::default();
+    };
+    match std::fs::read_to_string(&path) {
+        Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+            warn!("Invalid tcp-tool config {}: {}", path.display(), e);
+            TcpToolConfig::default()
+        }),
+        Err(_) => TcpToolConfig::default(),
+    }
+}
+
+async fn save_config<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    let path = config_path(app).ok_or_else(|| "Config directory is unavailable".to_string())?;
+    let state = app.state::<TcpToolState>();
+    let contents = serde_json::to_string_pretty(&*state.config.read().await)
+        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir)
+            .await
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    tokio::fs::write(&path, contents)
+        .await
+        .map_err(|e| format!("Failed to save config to {}: {}", path.display(), e))
+}
+
+/// 已建立的连接（可能经过 TLS 封装），读写两端分离
+struct Established {
+    reader: BoxedReader,
+    writer: BoxedWriter,
+    local_addr: String,
+    remote_addr: String,
+    tls: Option<TlsInfo>,
+}
+
+impl Established {
+    fn new<S>(stream: S, local_addr: String, remote_addr: String, tls: Option<TlsInfo>) -> Self
+    where
+        S: AsyncRead + AsyncWrite + Send + Unpin + 'static,
+    {
+        let (reader, writer) = tokio::io::split(stream);
+        Self {
+            reader: Box::new(reader),
+            writer: Box::new(writer),
+            local_addr,
+            remote_addr,
+            tls,
+        }
+    }
+}
+
+/// 建立客户端连接，配置了 TLS 时在同一超时时间内完成握手
+async fn open_client(
+    host: &str,
+    port: u16,
+    options: &SocketOptions,
+    tls: Option<&TlsClient>,
+) -> Result<Established, String> {
+    let stream = socket::connect(host, port, options).await?;
+    let local_addr = stream.local_addr().map_err(|e| e.to_string())?.to_string();
+    let remote_addr = socket::peer_addr(&stream, host, port, options)?;
+
+    match tls {
+        Some(tls) => {
+            let stream = tokio::time::timeout(options.connection_timeout, tls.connect(host, stream))
+                .await
+                .map_err(|_| "TLS handshake timed out".to_string())??;
+            let info = tls::client_info(&stream);
+            Ok(Established::new(stream, local_addr, remote_addr, Some(info)))
+        }
+        None => Ok(Established::new(stream, local_addr, remote_addr, None)),
+    }
 }
 
 #[tauri::command]
@@ -98,32 +501,55 @@ async fn tcp_client_connect<R: Runtime>(
     app: AppHandle<R>,
     host: String,
     port: u16,
+    options: Option<ConnectOptions>,
+    tls: Option<TlsClientOptions>,
 ) -> Result<String, String> {
     let state = app.state::<TcpToolState>();
     let connection_id = format!("client_{}_{}", host, port);
+    let use_proxy = options.as_ref().and_then(|options| options.proxy);
+    let mut options = SocketOptions::resolve(&*state.config.read().await, options);
+    options.proxy = network_proxy::for_host(&app, &host, use_proxy).await;
+    let tls = tls.map(|tls| TlsClient::new(&tls)).transpose()?;
+
+    match &options.proxy {
+        Some(proxy) => info!("Attempting to connect to {}:{} via proxy {}", host, port, proxy.address()),
+        None => info!("Attempting to connect to {}:{}", host, port),
+    }
 
-    info!("Attempting to connect to {}:{}", host, port);
-
-    match TcpStream::connect(format!("{}:{}", host, port)).await {
-        Ok(stream) => {
-            let local_addr = stream.local_addr().map_err(|e| e.to_string())?;
-            let remote_addr = stream.peer_addr().map_err(|e| e.to_string())?;
-
-            let connection_info = ConnectionInfo {
-                id: connection_id.clone(),
-                connection_type: "client".to_string(),
-                local_addr: local_addr.to_string(),
-                remote_addr: Some(remote_addr.to_string()),
-                status: "connected".to_string(),
-                created_at: Utc::now().to_rfc3339(),
-                bytes_sent: 0,
-                bytes_received: 0,
-            };
-
-            // 存储连接
-            state.connections.write().await.insert(connection_id.clone(), Arc::new(Mutex::new(stream)));
+    // 同一目标的旧连接任务需要先停止，避免两个任务争用同一个连接 ID
+    if let Some(task) = state.tasks.write().await.remove(&connection_id) {
+        task.abort();
+    }
+
+    match open_client(&host, port, &options, tls.as_ref()).await {
+        Ok(established) => {
+            let mut connection_info = ConnectionInfo::new(
+                &connection_id,
+                "client",
+                established.local_addr,
+                Some(established.remote_addr),
+                "connected",
+            );
+            connection_info.tls = established.tls;
+
+            // 存储连接，读取端交给后台任务
+            state.connections.write().await.insert(connection_id.clone(), Arc::new(Mutex::new(established.writer)));
             state.connection_info.write().await.insert(connection_id.clone(), connection_info.clone());
 
+            // 持有写锁直到登记完成，避免读取任务先于登记结束并移除自己
+            let mut tasks = state.tasks.write().await;
+            let task = tokio::spawn(run_client(
+                app.clone(),
+                connection_id.clone(),
+                host.clone(),
+                port,
+                options,
+                tls,
+                established.reader,
+            ));
+            tasks.insert(connection_id.clone(), task);
+            drop(tasks);
+
             // 发送连接事件到前端
             app.emit("tcp_connection_established", &connection_info).map_err(|e| e.to_string())?;
 
@@ -132,7 +558,107 @@ async fn tcp_client_connect<R: Runtime>(
         }
         Err(e) => {
             error!("Failed to connect to {}:{}: {}", host, port, e);
-            Err(format!("Connection failed: {}", e))
+            Err(e)
+        }
+    }
+}
+
+/// 读取客户端连接的数据，断开后按配置进行指数退避重连
+async fn run_client<R: Runtime>(
+    app: AppHandle<R>,
+    connection_id: String,
+    host: String,
+    port: u16,
+    options: SocketOptions,
+    tls: Option<TlsClient>,
+    mut reader: BoxedReader,
+) {
+    let state = app.state::<TcpToolState>();
+
+    loop {
+        if let Err(e) = read_loop(&app, &connection_id, &mut reader, false).await {
+            warn!("Connection {} read error: {}", connection_id, e);
+        }
+        if !options.auto_reconnect {
+            state.connections.write().await.remove(&connection_id);
+            break;
+        }
+        // 先标记为重连中再移除写入端，定时发送据此跳过而不是失败
+        state.set_status(&connection_id, "reconnecting").await;
+        state.connections.write().await.remove(&connection_id);
+
+        let mut backoff = Backoff::new(options.reconnect_initial_delay, options.reconnect_max_delay);
+        let mut attempt = 0u32;
+        let established = loop {
+            if options.reconnect_max_attempts > 0 && attempt >= options.reconnect_max_attempts {
+                break None;
+            }
+            attempt += 1;
+
+            if let Some(info) = state.set_status(&connection_id, "reconnecting").await {
+                emit_event(&app, "tcp_connection_reconnecting", &info);
+            }
+
+            let delay = backoff.next_delay();
+            info!("Reconnecting {} in {}ms (attempt {})", connection_id, delay.as_millis(), attempt);
+            tokio::time::sleep(delay).await;
+
+            match open_client(&host, port, &options, tls.as_ref()).await {
+                Ok(established) => break Some(established),
+                Err(e) => warn!("Reconnect attempt {} for {} failed: {}", attempt, connection_id, e),
+            }
+        };
+
+        let Some(established) = established else {
+            warn!("Giving up reconnecting {} after {} attempts", connection_id, attempt);
+            break;
+        };
+
+        reader = established.reader;
+        state.connections.write().await.insert(connection_id.clone(), Arc::new(Mutex::new(established.writer)));
+
+        let info = {
+            let mut infos = state.connection_info.write().await;
+            infos.get_mut(&connection_id).map(|info| {
+                info.local_addr = established.local_addr;
+                info.remote_addr = Some(established.remote_addr);
+                info.tls = established.tls;
+                info.status = "connected".to_string();
+                info.clone()
+            })
+        };
+        if let Some(info) = info {
+            emit_event(&app, "tcp_connection_established", &info);
+        }
+        info!("Reconnected {} to {}:{}", connection_id, host, port);
+    }
+
+    state.tasks.write().await.remove(&connection_id);
+    if let Some(info) = state.disconnected(&app, &connection_id).await {
+        emit_event(&app, "tcp_connection_closed", &info);
+    }
+    info!("Connection {} closed", connection_id);
+}
+
+/// 读取数据直到连接关闭；`auto_respond` 为 true 时按自动应答规则回复
+async fn read_loop<R: Runtime>(
+    app: &AppHandle<R>,
+    connection_id: &str,
+    reader: &mut BoxedReader,
+    auto_respond: bool,
+) -> std::io::Result<()> {
+    let mut buf = vec![0u8; 4096];
+
+    loop {
+        let n = reader.read(&mut buf).await?;
+        if n == 0 {
+            return Ok(());
+        }
+
+        let message_data = MessageData::new(connection_id, &buf[..n], "received", None);
+        publish_message(app, &message_data, &buf[..n]).await;
+        if auto_respond {
+            responder::respond(app, connection_id, &buf[..n]).await;
         }
     }
 }
@@ -141,81 +667,119 @@ async fn tcp_client_connect<R: Runtime>(
 async fn tcp_server_start<R: Runtime>(
     app: AppHandle<R>,
     port: u16,
+    bind_address: Option<String>,
+    dual_stack: Option<bool>,
+    tls: Option<TlsServerOptions>,
 ) -> Result<String, String> {
     let state = app.state::<TcpToolState>();
-    let server_id = format!("server_{}", port);
+    let acceptor = tls.map(|tls| tls::acceptor(&tls)).transpose()?;
+    let bind_address = match bind_address {
+        Some(address) => address,
+        None => state.config.read().await.default_bind_address.clone(),
+    };
+    let addr = socket::parse_bind_addr(&bind_address, port)?;
 
-    info!("Starting TCP server on port {}", port);
+    info!("Starting TCP server on {}", addr);
 
-    match TcpListener::bind(format!("0.0.0.0:{}", port)).await {
+    // 端口为 0 时由系统分配，监听成功后以实际地址作为服务器 ID
+    match socket::bind_listener(addr, dual_stack.unwrap_or(false)) {
         Ok(listener) => {
             let local_addr = listener.local_addr().map_err(|e| e.to_string())?;
+            let server_id = format!("server_{}_{}", local_addr.ip(), local_addr.port());
+
+            let connection_info = ConnectionInfo::new(&server_id, "server", local_addr.to_string(), None, "listening");
 
-            let connection_info = ConnectionInfo {
-                id: server_id.clone(),
-                connection_type: "server".to_string(),
-                local_addr: local_addr.to_string(),
-                remote_addr: None,
-                status: "listening".to_string(),
-                created_at: Utc::now().to_rfc3339(),
-                bytes_sent: 0,
-                bytes_received: 0,
-            };
-
-            // 存储服务器
-            state.servers.write().await.insert(server_id.clone(), Arc::new(Mutex::new(listener)));
+            // 存储服务器，监听套接字交给后台任务接受连接
             state.connection_info.write().await.insert(server_id.clone(), connection_info.clone());
+            let task = tokio::spawn(server::accept_loop(app.clone(), server_id.clone(), listener, acceptor));
+            state.tasks.write().await.insert(server_id.clone(), task);
 
             // 发送服务器启动事件到前端
             app.emit("tcp_server_started", &connection_info).map_err(|e| e.to_string())?;
 
-            info!("TCP server started on port {}", port);
+            info!("TCP server started on {}", local_addr);
             Ok(server_id)
         }
         Err(e) => {
-            error!("Failed to start server on port {}: {}", port, e);
+            error!("Failed to start server on {}: {}", addr, e);
+            // 端口被占用时提示占用端口的进程
+            if e.kind() == std::io::ErrorKind::AddrInUse {
+                let owner = tokio::task::spawn_blocking(move || tauri_plugin_netstat::describe_port_owner("tcp", port))
+                    .await
+                    .ok()
+                    .flatten();
+                if let Some(owner) = owner {
+                    return Err(format!("Server start failed: {} (port {} is used by {})", e, port, owner));
+                }
+            }
             Err(format!("Server start failed: {}", e))
         }
     }
 }
 
+/// 向 TCP 连接写入数据，并更新发送字节数、通知前端
+async fn write_connection<R: Runtime>(app: &AppHandle<R>, connection_id: &str, data: &[u8]) -> Result<(), String> {
+    let state = app.state::<TcpToolState>();
+    let stream_arc = state
+        .connections
+        .read()
+        .await
+        .get(connection_id)
+        .cloned()
+        .ok_or_else(|| "Connection not found".to_string())?;
+
+    let server_id = state
+        .connection_info
+        .read()
+        .await
+        .get(connection_id)
+        .and_then(|info| info.server_id.clone());
+
+    let outcome = {
+        let mut writer = stream_arc.lock().await;
+        state.faults.write(connection_id, server_id.as_deref(), None, &mut *writer, data).await
+    }
+    .map_err(|e| {
+        error!("Failed to send message to {}: {}", connection_id, e);
+        format!("Send failed: {}", e)
+    })?;
+
+    let message_data = MessageData::new(connection_id, &outcome.sent, "sent", None);
+    publish_message(app, &message_data, &outcome.sent).await;
+
+    if outcome.action == Some(faults::FaultAction::Drop) {
+        info!("Connection {} dropped by fault injection", connection_id);
+        if let Some(info) = state.close_connection(app, connection_id).await {
+            emit_event(app, "tcp_connection_closed", &info);
+        }
+    }
+    Ok(())
+}
+
 #[tauri::command]
 async fn tcp_send_message<R: Runtime>(
     app: AppHandle<R>,
     connection_id: String,
     message: String,
 ) -> Result<(), String> {
-    let state = app.state::<TcpToolState>();
-
-    if let Some(stream_arc) = state.connections.read().await.get(&connection_id) {
-        let mut stream = stream_arc.lock().await;
-
-        match stream.write_all(message.as_bytes()).await {
-            Ok(_) => {
-                let message_data = MessageData {
-                    connection_id: connection_id.clone(),
-                    content: message.clone(),
-                    timestamp: Utc::now().to_rfc3339(),
-                    direction: "sent".to_string(),
-                    size: message.len(),
-                };
-
-                // 更新发送字节数
-                if let Some(info) = state.connection_info.write().await.get_mut(&connection_id) {
-                    info.bytes_sent += message.len() as u64;
-                }
-
-                // 发送消息事件到前端
-                app.emit("tcp_message_sent", &message_data).map_err(|e| e.to_string())?;
+    send_payload(&app, &connection_id, message.as_bytes().to_vec()).await?;
+    info!("Message sent to {}: {}", connection_id, message);
+    Ok(())
+}
 
-                info!("Message sent to {}: {}", connection_id, message);
-                Ok(())
-            }
-            Err(e) => {
-                error!("Failed to send message to {}: {}", connection_id, e);
-                Err(format!("Send failed: {}", e))
-            }
-        }
+/// 按连接类型发送数据：先按设置追加校验值，再由 TCP 直接写入、UDP 发往默认目标，
+/// WebSocket 按内容选择文本或二进制帧
+async fn send_payload<R: Runtime>(app: &AppHandle<R>, connection_id: &str, data: Vec<u8>) -> Result<(), String> {
+    let state = app.state::<TcpToolState>();
+    let data = state.with_checksum(connection_id, data).await?;
+
+    if state.connections.read().await.contains_key(connection_id) {
+        write_connection(app, connection_id, &data).await
+    } else if state.udp_sockets.read().await.contains_key(connection_id) {
+        // UDP 套接字发送到绑定时指定的默认目标
+        udp::send_to(app, connection_id, &data, None).await
+    } else if state.websockets.read().await.contains_key(connection_id) {
+        websocket::send_frame(&state, connection_id, websocket::data_frame(data)).await
     } else {
         Err("Connection not found".to_string())
     }
@@ -228,21 +792,35 @@ async fn tcp_disconnect<R: Runtime>(
 ) -> Result<(), String> {
     let state = app.state::<TcpToolState>();
 
-    // 移除连接
-    state.connections.write().await.remove(&connection_id);
-
-    // 更新连接状态
-    if let Some(info) = state.connection_info.write().await.get_mut(&connection_id) {
-        info.status = "disconnected".to_string();
-
-        // 发送断开连接事件到前端
-        app.emit("tcp_connection_closed", info).map_err(|e| e.to_string())?;
+    // 关闭服务器时一并断开它接受的连接
+    let children: Vec<String> = state
+        .connection_info
+        .read()
+        .await
+        .values()
+        .filter(|info| info.server_id.as_deref() == Some(connection_id.as_str()) && info.status != "disconnected")
+        .map(|info| info.id.clone())
+        .collect();
+
+    for id in children.iter().chain(std::iter::once(&connection_id)) {
+        // 更新连接状态
+        if let Some(info) = state.close_connection(&app, id).await {
+            // 发送断开连接事件到前端
+            app.emit("tcp_connection_closed", &info).map_err(|e| e.to_string())?;
+        }
     }
 
     info!("Connection {} disconnected", connection_id);
     Ok(())
 }
 
+#[tauri::command]
+async fn tcp_list_interfaces<R: Runtime>(
+    _app: AppHandle<R>,
+) -> Result<Vec<NetworkInterface>, String> {
+    socket::list_interfaces().map_err(|e| format!("Failed to list interfaces: {}", e))
+}
+
 #[tauri::command]
 async fn tcp_get_connections<R: Runtime>(
     app: AppHandle<R>,
@@ -261,13 +839,63 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
             tcp_server_start,
             tcp_send_message,
             tcp_disconnect,
-            tcp_get_connections
+            tcp_get_connections,
+            tcp_list_interfaces,
+            udp::udp_bind,
+            udp::udp_send,
+            udp::udp_join_multicast,
+            udp::udp_leave_multicast,
+            websocket::ws_connect,
+            websocket::ws_send,
+            websocket::ws_close,
+            websocket::ws_server_start,
+            proxy::proxy_start,
+            proxy::proxy_set_intercept,
+            proxy::proxy_resolve_frame,
+            proxy::proxy_pending_frames,
+            record::tcp_record_start,
+            record::tcp_record_stop,
+            record::tcp_replay_start,
+            capture::tcp_export_pcap,
+            capture::tcp_capture_clear,
+            responder::responder_get_rules,
+            responder::responder_save_rule,
+            responder::responder_remove_rule,
+            responder::responder_set_rules,
+            schedule::tcp_schedule_repeat,
+            schedule::tcp_schedule_sequence,
+            schedule::tcp_schedule_cancel,
+            schedule::tcp_schedule_list,
+            bench::tcp_benchmark_start,
+            bench::tcp_benchmark_stop,
+            stats::tcp_get_stats,
+            faults::tcp_set_faults,
+            faults::tcp_get_faults,
+            checksum::tcp_set_checksum,
+            checksum::tcp_compute_checksum,
+            layout::layout_get_all,
+            layout::layout_save,
+            layout::layout_remove,
+            layout::layout_decode,
+            layout::tcp_set_frame_layout,
+            template::template_get_all,
+            template::template_save,
+            template::template_remove,
+            template::template_render,
+            template::tcp_send_template,
+            modbus::modbus_client_connect,
+            modbus::modbus_read,
+            modbus::modbus_write,
+            modbus::modbus_slave_start,
+            modbus::modbus_slave_set,
+            modbus::modbus_slave_get
         ])
         .setup(|app, _api| {
-            // 初始化插件状态
-            let config = TcpToolConfig::default();
+            // 初始化插件状态，优先使用保存的配置
+            let config = load_config(app);
             let state = TcpToolState::new(config);
             app.manage(state);
+            tauri::async_runtime::spawn(stats::run(app.clone()));
 
             info!("tcp-tool plugin initialized");
             Ok(())
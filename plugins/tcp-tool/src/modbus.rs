@@ -0,0 +1,674 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, Runtime};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{Mutex, RwLock};
+use tracing::{info, warn};
+
+use network_common::payload::to_hex;
+use crate::{emit_event, publish_message, socket, ConnectionInfo, MessageData, SocketOptions, TcpToolState};
+
+const READ_COILS: u8 = 0x01;
+const READ_DISCRETE_INPUTS: u8 = 0x02;
+const READ_HOLDING_REGISTERS: u8 = 0x03;
+const READ_INPUT_REGISTERS: u8 = 0x04;
+const WRITE_SINGLE_COIL: u8 = 0x05;
+const WRITE_SINGLE_REGISTER: u8 = 0x06;
+const WRITE_MULTIPLE_COILS: u8 = 0x0F;
+const WRITE_MULTIPLE_REGISTERS: u8 = 0x10;
+
+const ILLEGAL_FUNCTION: u8 = 0x01;
+const ILLEGAL_DATA_ADDRESS: u8 = 0x02;
+const ILLEGAL_DATA_VALUE: u8 = 0x03;
+
+/// MBAP 头长度：事务号(2) + 协议号(2) + 长度(2) + 单元号(1)
+const MBAP_HEADER_LEN: usize = 7;
+
+/// Modbus 的四类数据表
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModbusTable {
+    Coils,
+    DiscreteInputs,
+    HoldingRegisters,
+    InputRegisters,
+}
+
+impl ModbusTable {
+    fn is_bits(self) -> bool {
+        matches!(self, ModbusTable::Coils | ModbusTable::DiscreteInputs)
+    }
+
+    fn read_function(self) -> u8 {
+        match self {
+            ModbusTable::Coils => READ_COILS,
+            ModbusTable::DiscreteInputs => READ_DISCRETE_INPUTS,
+            ModbusTable::HoldingRegisters => READ_HOLDING_REGISTERS,
+            ModbusTable::InputRegisters => READ_INPUT_REGISTERS,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModbusException {
+    pub code: u8,
+    pub name: String,
+}
+
+fn exception_name(code: u8) -> &'static str {
+    match code {
+        0x01 => "Illegal Function",
+        0x02 => "Illegal Data Address",
+        0x03 => "Illegal Data Value",
+        0x04 => "Server Device Failure",
+        0x05 => "Acknowledge",
+        0x06 => "Server Device Busy",
+        0x08 => "Memory Parity Error",
+        0x0A => "Gateway Path Unavailable",
+        0x0B => "Gateway Target Device Failed To Respond",
+        _ => "Unknown Exception",
+    }
+}
+
+/// 客户端请求的解码结果；线圈和离散输入以 0/1 表示
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModbusResponse {
+    pub transaction_id: u16,
+    pub unit_id: u8,
+    pub function: u8,
+    pub address: u16,
+    pub values: Vec<u16>,
+    pub exception: Option<ModbusException>,
+    pub round_trip_ms: f64,
+    pub request: String,
+    pub response: String,
+}
+
+fn pack_bits(bits: &[bool]) -> Vec<u8> {
+    let mut bytes = vec![0u8; bits.len().div_ceil(8)];
+    for (i, bit) in bits.iter().enumerate() {
+        if *bit {
+            bytes[i / 8] |= 1 << (i % 8);
+        }
+    }
+    bytes
+}
+
+fn unpack_bits(bytes: &[u8], count: usize) -> Vec<bool> {
+    (0..count)
+        .map(|i| bytes.get(i / 8).is_some_and(|byte| byte & (1 << (i % 8)) != 0))
+        .collect()
+}
+
+fn be_u16(data: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_be_bytes([*data.get(offset)?, *data.get(offset + 1)?]))
+}
+
+fn mbap_frame(transaction_id: u16, unit_id: u8, pdu: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(MBAP_HEADER_LEN + pdu.len());
+    frame.extend_from_slice(&transaction_id.to_be_bytes());
+    frame.extend_from_slice(&0u16.to_be_bytes());
+    frame.extend_from_slice(&((pdu.len() + 1) as u16).to_be_bytes());
+    frame.push(unit_id);
+    frame.extend_from_slice(pdu);
+    frame
+}
+
+/// 读取一个完整的 MBAP 帧，返回 (事务号, 单元号, PDU, 原始帧)
+async fn read_frame<T: AsyncRead + Unpin>(reader: &mut T) -> std::io::Result<(u16, u8, Vec<u8>, Vec<u8>)> {
+    let mut header = [0u8; MBAP_HEADER_LEN];
+    reader.read_exact(&mut header).await?;
+    let length = u16::from_be_bytes([header[4], header[5]]) as usize;
+    if !(2..=254).contains(&length) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("invalid MBAP length {}", length),
+        ));
+    }
+    let mut pdu = vec![0u8; length - 1];
+    reader.read_exact(&mut pdu).await?;
+
+    let mut raw = header.to_vec();
+    raw.extend_from_slice(&pdu);
+    Ok((u16::from_be_bytes([header[0], header[1]]), header[6], pdu, raw))
+}
+
+/// 从站的寄存器表，四类数据各有 65536 个地址
+pub struct RegisterMap {
+    coils: Vec<bool>,
+    discrete_inputs: Vec<bool>,
+    holding_registers: Vec<u16>,
+    input_registers: Vec<u16>,
+}
+
+impl Default for RegisterMap {
+    fn default() -> Self {
+        Self {
+            coils: vec![false; 0x10000],
+            discrete_inputs: vec![false; 0x10000],
+            holding_registers: vec![0; 0x10000],
+            input_registers: vec![0; 0x10000],
+        }
+    }
+}
+
+impl RegisterMap {
+    fn read(&self, table: ModbusTable, address: usize, quantity: usize) -> Vec<u16> {
+        let range = address..address + quantity;
+        match table {
+            ModbusTable::Coils => self.coils[range].iter().map(|b| u16::from(*b)).collect(),
+            ModbusTable::DiscreteInputs => self.discrete_inputs[range].iter().map(|b| u16::from(*b)).collect(),
+            ModbusTable::HoldingRegisters => self.holding_registers[range].to_vec(),
+            ModbusTable::InputRegisters => self.input_registers[range].to_vec(),
+        }
+    }
+
+    fn write(&mut self, table: ModbusTable, address: usize, values: &[u16]) {
+        let range = address..address + values.len();
+        match table {
+            ModbusTable::Coils => {
+                for (slot, value) in self.coils[range].iter_mut().zip(values) {
+                    *slot = *value != 0;
+                }
+            }
+            ModbusTable::DiscreteInputs => {
+                for (slot, value) in self.discrete_inputs[range].iter_mut().zip(values) {
+                    *slot = *value != 0;
+                }
+            }
+            ModbusTable::HoldingRegisters => self.holding_registers[range].copy_from_slice(values),
+            ModbusTable::InputRegisters => self.input_registers[range].copy_from_slice(values),
+        }
+    }
+}
+
+/// 主站写入从站时通过 "modbus_slave_written" 事件通知前端
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegisterUpdate {
+    pub slave_id: String,
+    pub table: ModbusTable,
+    pub address: u16,
+    pub values: Vec<u16>,
+}
+
+fn exception(function: u8, code: u8) -> Vec<u8> {
+    vec![function | 0x80, code]
+}
+
+fn check_range(address: u16, quantity: u16, max: u16) -> Result<(), u8> {
+    if quantity == 0 || quantity > max {
+        return Err(ILLEGAL_DATA_VALUE);
+    }
+    if address as usize + quantity as usize > 0x10000 {
+        return Err(ILLEGAL_DATA_ADDRESS);
+    }
+    Ok(())
+}
+
+/// 应答 PDU 以及主站写入的内容
+type Handled = (Vec<u8>, Option<(ModbusTable, u16, Vec<u16>)>);
+
+/// 处理一个请求 PDU
+fn process(map: &mut RegisterMap, pdu: &[u8]) -> Handled {
+    let Some(&function) = pdu.first() else {
+        return (exception(0, ILLEGAL_FUNCTION), None);
+    };
+    match handle(map, function, pdu) {
+        Ok(result) => result,
+        Err(code) => (exception(function, code), None),
+    }
+}
+
+fn handle(map: &mut RegisterMap, function: u8, pdu: &[u8]) -> Result<Handled, u8> {
+    if !matches!(function, READ_COILS..=WRITE_SINGLE_REGISTER | WRITE_MULTIPLE_COILS | WRITE_MULTIPLE_REGISTERS) {
+        return Err(ILLEGAL_FUNCTION);
+    }
+    let address = be_u16(pdu, 1).ok_or(ILLEGAL_DATA_VALUE)?;
+    let field = be_u16(pdu, 3).ok_or(ILLEGAL_DATA_VALUE)?;
+
+    match function {
+        READ_COILS | READ_DISCRETE_INPUTS => {
+            let table = if function == READ_COILS { ModbusTable::Coils } else { ModbusTable::DiscreteInputs };
+            check_range(address, field, 2000)?;
+            let bits: Vec<bool> = map.read(table, address as usize, field as usize).iter().map(|v| *v != 0).collect();
+            let bytes = pack_bits(&bits);
+            let mut response = vec![function, bytes.len() as u8];
+            response.extend(bytes);
+            Ok((response, None))
+        }
+        READ_HOLDING_REGISTERS | READ_INPUT_REGISTERS => {
+            let table = if function == READ_HOLDING_REGISTERS {
+                ModbusTable::HoldingRegisters
+            } else {
+                ModbusTable::InputRegisters
+            };
+            check_range(address, field, 125)?;
+            let values = map.read(table, address as usize, field as usize);
+            let mut response = vec![function, (values.len() * 2) as u8];
+            response.extend(values.iter().flat_map(|v| v.to_be_bytes()));
+            Ok((response, None))
+        }
+        WRITE_SINGLE_COIL => {
+            let value = match field {
+                0xFF00 => 1,
+                0x0000 => 0,
+                _ => return Err(ILLEGAL_DATA_VALUE),
+            };
+            map.write(ModbusTable::Coils, address as usize, &[value]);
+            Ok((pdu[..5].to_vec(), Some((ModbusTable::Coils, address, vec![value]))))
+        }
+        WRITE_SINGLE_REGISTER => {
+            map.write(ModbusTable::HoldingRegisters, address as usize, &[field]);
+            Ok((pdu[..5].to_vec(), Some((ModbusTable::HoldingRegisters, address, vec![field]))))
+        }
+        WRITE_MULTIPLE_COILS => {
+            check_range(address, field, 1968)?;
+            let bytes = pdu.get(6..).ok_or(ILLEGAL_DATA_VALUE)?;
+            if pdu.get(5).copied() != Some((field as usize).div_ceil(8) as u8) || bytes.len() < (field as usize).div_ceil(8) {
+                return Err(ILLEGAL_DATA_VALUE);
+            }
+            let values: Vec<u16> = unpack_bits(bytes, field as usize).into_iter().map(u16::from).collect();
+            map.write(ModbusTable::Coils, address as usize, &values);
+            Ok((pdu[..5].to_vec(), Some((ModbusTable::Coils, address, values))))
+        }
+        WRITE_MULTIPLE_REGISTERS => {
+            check_range(address, field, 123)?;
+            let bytes = pdu.get(6..).ok_or(ILLEGAL_DATA_VALUE)?;
+            if pdu.get(5).copied() != Some((field * 2) as u8) || bytes.len() < field as usize * 2 {
+                return Err(ILLEGAL_DATA_VALUE);
+            }
+            let values: Vec<u16> = bytes.chunks(2).take(field as usize).map(|c| u16::from_be_bytes([c[0], c[1]])).collect();
+            map.write(ModbusTable::HoldingRegisters, address as usize, &values);
+            Ok((pdu[..5].to_vec(), Some((ModbusTable::HoldingRegisters, address, values))))
+        }
+        _ => Err(ILLEGAL_FUNCTION),
+    }
+}
+
+struct ModbusClient {
+    reader: OwnedReadHalf,
+    writer: OwnedWriteHalf,
+    next_transaction_id: u16,
+}
+
+struct ModbusSlave {
+    unit_id: Option<u8>,
+    map: RwLock<RegisterMap>,
+}
+
+/// Modbus 客户端连接和从站模拟器
+#[derive(Default)]
+pub struct ModbusRegistry {
+    clients: Mutex<HashMap<String, Arc<Mutex<ModbusClient>>>>,
+    slaves: RwLock<HashMap<String, Arc<ModbusSlave>>>,
+}
+
+impl ModbusRegistry {
+    pub(crate) async fn remove(&self, connection_id: &str) {
+        self.clients.lock().await.remove(connection_id);
+        self.slaves.write().await.remove(connection_id);
+    }
+
+    async fn slave(&self, slave_id: &str) -> Result<Arc<ModbusSlave>, String> {
+        self.slaves
+            .read()
+            .await
+            .get(slave_id)
+            .cloned()
+            .ok_or_else(|| "Modbus slave not found".to_string())
+    }
+}
+
+#[tauri::command]
+pub(crate) async fn modbus_client_connect<R: Runtime>(
+    app: AppHandle<R>,
+    host: String,
+    port: Option<u16>,
+) -> Result<String, String> {
+    let state = app.state::<TcpToolState>();
+    let port = port.unwrap_or(502);
+    let options = SocketOptions::resolve(&*state.config.read().await, None);
+    let stream = socket::connect(&host, port, &options).await?;
+    let connection_id = format!("modbus_{}_{}", host, port);
+
+    let connection_info = ConnectionInfo::new(
+        &connection_id,
+        "modbus_client",
+        stream.local_addr().map(|a| a.to_string()).unwrap_or_default(),
+        stream.peer_addr().map(|a| a.to_string()).ok(),
+        "connected",
+    );
+    let (reader, writer) = stream.into_split();
+    let client = ModbusClient { reader, writer, next_transaction_id: 1 };
+    state.modbus.clients.lock().await.insert(connection_id.clone(), Arc::new(Mutex::new(client)));
+    state.connection_info.write().await.insert(connection_id.clone(), connection_info.clone());
+    emit_event(&app, "tcp_connection_established", &connection_info);
+
+    info!("Modbus client connected to {}:{}", host, port);
+    Ok(connection_id)
+}
+
+/// 事务失败的原因：应答超时时连接仍可继续使用，读写出错时连接已不可用
+enum TransactError {
+    Timeout,
+    Io(String),
+}
+
+/// 发送一个请求并等待对应事务号的应答
+async fn transact<R: Runtime>(
+    app: &AppHandle<R>,
+    connection_id: &str,
+    unit_id: u8,
+    address: u16,
+    pdu: Vec<u8>,
+) -> Result<ModbusResponse, String> {
+    let state = app.state::<TcpToolState>();
+    let client = state
+        .modbus
+        .clients
+        .lock()
+        .await
+        .get(connection_id)
+        .cloned()
+        .ok_or_else(|| "Modbus connection not found".to_string())?;
+    let timeout = Duration::from_millis(state.config.read().await.connection_timeout);
+
+    let mut client = client.lock().await;
+    let transaction_id = client.next_transaction_id;
+    client.next_transaction_id = client.next_transaction_id.wrapping_add(1);
+    let request = mbap_frame(transaction_id, unit_id, &pdu);
+
+    let started = Instant::now();
+    let result = async {
+        client
+            .writer
+            .write_all(&request)
+            .await
+            .map_err(|e| TransactError::Io(format!("Send failed: {}", e)))?;
+        publish_message(app, &MessageData::new(connection_id, &request, "sent", None), &request).await;
+
+        // 丢弃事务号不匹配的迟到应答
+        loop {
+            let (tid, unit, response_pdu, raw) = tokio::time::timeout(timeout, read_frame(&mut client.reader))
+                .await
+                .map_err(|_| TransactError::Timeout)?
+                .map_err(|e| TransactError::Io(format!("Receive failed: {}", e)))?;
+            publish_message(app, &MessageData::new(connection_id, &raw, "received", None), &raw).await;
+            if tid == transaction_id {
+                return Ok((unit, response_pdu, raw));
+            }
+            warn!("Modbus {} ignored response with transaction {}", connection_id, tid);
+        }
+    }
+    .await;
+    let round_trip_ms = started.elapsed().as_secs_f64() * 1000.0;
+    drop(client);
+
+    let (unit, response_pdu, raw) = match result {
+        Ok(result) => result,
+        // 应答超时时保留连接，后续事务仍可使用
+        Err(TransactError::Timeout) => return Err("Modbus response timed out".to_string()),
+        // 读写出错时连接已不可用
+        Err(TransactError::Io(e)) => {
+            state.modbus.clients.lock().await.remove(connection_id);
+            if let Some(info) = state.disconnected(app, connection_id).await {
+                emit_event(app, "tcp_connection_closed", &info);
+            }
+            return Err(e);
+        }
+    };
+
+    let function = response_pdu.first().copied().unwrap_or_default();
+    let mut response = ModbusResponse {
+        transaction_id,
+        unit_id: unit,
+        function: function & 0x7F,
+        address,
+        values: Vec::new(),
+        exception: None,
+        round_trip_ms,
+        request: to_hex(&request),
+        response: to_hex(&raw),
+    };
+    if function & 0x80 != 0 {
+        let code = response_pdu.get(1).copied().unwrap_or_default();
+        response.exception = Some(ModbusException { code, name: exception_name(code).to_string() });
+        return Ok(response);
+    }
+    if function != pdu[0] {
+        return Err(format!("Unexpected function code {:#04x} in response", function));
+    }
+
+    let quantity = be_u16(&pdu, 3).unwrap_or_default() as usize;
+    let data = response_pdu.get(2..).unwrap_or_default();
+    response.values = match function {
+        READ_COILS | READ_DISCRETE_INPUTS => unpack_bits(data, quantity).into_iter().map(u16::from).collect(),
+        READ_HOLDING_REGISTERS | READ_INPUT_REGISTERS => {
+            data.chunks_exact(2).map(|c| u16::from_be_bytes([c[0], c[1]])).collect()
+        }
+        // 写操作的应答回显地址和数量（或写入的值）
+        _ => vec![be_u16(&response_pdu, 3).unwrap_or_default()],
+    };
+    Ok(response)
+}
+
+#[tauri::command]
+pub(crate) async fn modbus_read<R: Runtime>(
+    app: AppHandle<R>,
+    connection_id: String,
+    unit_id: u8,
+    table: ModbusTable,
+    address: u16,
+    quantity: u16,
+) -> Result<ModbusResponse, String> {
+    let mut pdu = vec![table.read_function()];
+    pdu.extend_from_slice(&address.to_be_bytes());
+    pdu.extend_from_slice(&quantity.to_be_bytes());
+    transact(&app, &connection_id, unit_id, address, pdu).await
+}
+
+/// 写线圈或保持寄存器：单个值使用功能码 05/06，多个值使用 15/16
+#[tauri::command]
+pub(crate) async fn modbus_write<R: Runtime>(
+    app: AppHandle<R>,
+    connection_id: String,
+    unit_id: u8,
+    table: ModbusTable,
+    address: u16,
+    values: Vec<u16>,
+) -> Result<ModbusResponse, String> {
+    let mut pdu = match (table, values.as_slice()) {
+        (_, []) => return Err("No values to write".to_string()),
+        (ModbusTable::Coils, [value]) => {
+            let mut pdu = vec![WRITE_SINGLE_COIL];
+            pdu.extend_from_slice(&address.to_be_bytes());
+            pdu.extend_from_slice(&(if *value != 0 { 0xFF00u16 } else { 0 }).to_be_bytes());
+            return transact(&app, &connection_id, unit_id, address, pdu).await;
+        }
+        (ModbusTable::HoldingRegisters, [value]) => {
+            let mut pdu = vec![WRITE_SINGLE_REGISTER];
+            pdu.extend_from_slice(&address.to_be_bytes());
+            pdu.extend_from_slice(&value.to_be_bytes());
+            return transact(&app, &connection_id, unit_id, address, pdu).await;
+        }
+        (ModbusTable::Coils, _) => vec![WRITE_MULTIPLE_COILS],
+        (ModbusTable::HoldingRegisters, _) => vec![WRITE_MULTIPLE_REGISTERS],
+        _ => return Err("Only coils and holding registers are writable".to_string()),
+    };
+
+    pdu.extend_from_slice(&address.to_be_bytes());
+    pdu.extend_from_slice(&(values.len() as u16).to_be_bytes());
+    let data = if table == ModbusTable::Coils {
+        pack_bits(&values.iter().map(|v| *v != 0).collect::<Vec<_>>())
+    } else {
+        values.iter().flat_map(|v| v.to_be_bytes()).collect()
+    };
+    if data.len() > 246 {
+        return Err("Too many values for a single request".to_string());
+    }
+    pdu.push(data.len() as u8);
+    pdu.extend(data);
+    transact(&app, &connection_id, unit_id, address, pdu).await
+}
+
+/// 启动从站模拟器；`unit_id` 为空时应答任意单元号
+#[tauri::command]
+pub(crate) async fn modbus_slave_start<R: Runtime>(
+    app: AppHandle<R>,
+    port: Option<u16>,
+    bind_address: Option<String>,
+    unit_id: Option<u8>,
+) -> Result<String, String> {
+    let state = app.state::<TcpToolState>();
+    let bind_address = match bind_address {
+        Some(address) => address,
+        None => state.config.read().await.default_bind_address.clone(),
+    };
+    let addr = socket::parse_bind_addr(&bind_address, port.unwrap_or(502))?;
+    let listener = socket::bind_listener(addr, false).map_err(|e| format!("Modbus slave start failed: {}", e))?;
+    let local_addr = listener.local_addr().map_err(|e| e.to_string())?;
+    let slave_id = format!("modbus_slave_{}_{}", local_addr.ip(), local_addr.port());
+
+    let slave = Arc::new(ModbusSlave { unit_id, map: RwLock::new(RegisterMap::default()) });
+    state.modbus.slaves.write().await.insert(slave_id.clone(), slave.clone());
+    let connection_info = ConnectionInfo::new(&slave_id, "modbus_slave", local_addr.to_string(), None, "listening");
+    state.connection_info.write().await.insert(slave_id.clone(), connection_info.clone());
+
+    let task = tokio::spawn(accept_loop(app.clone(), slave_id.clone(), listener, slave));
+    state.tasks.write().await.insert(slave_id.clone(), task);
+    emit_event(&app, "tcp_server_started", &connection_info);
+
+    info!("Modbus slave listening on {}", local_addr);
+    Ok(slave_id)
+}
+
+async fn accept_loop<R: Runtime>(app: AppHandle<R>, slave_id: String, listener: TcpListener, slave: Arc<ModbusSlave>) {
+    let state = app.state::<TcpToolState>();
+
+    loop {
+        match listener.accept().await {
+            Ok((stream, peer)) => {
+                let connection_id = format!("{}_{}", slave_id, peer);
+                // 持有写锁直到登记完成，避免连接任务先于登记结束并移除自己
+                let mut tasks = state.tasks.write().await;
+                let task = tokio::spawn(serve_master(
+                    app.clone(),
+                    slave_id.clone(),
+                    connection_id.clone(),
+                    stream,
+                    peer,
+                    slave.clone(),
+                ));
+                tasks.insert(connection_id, task);
+            }
+            Err(e) => {
+                warn!("Modbus slave {} accept error: {}", slave_id, e);
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+        }
+    }
+}
+
+async fn serve_master<R: Runtime>(
+    app: AppHandle<R>,
+    slave_id: String,
+    connection_id: String,
+    stream: TcpStream,
+    peer: SocketAddr,
+    slave: Arc<ModbusSlave>,
+) {
+    let state = app.state::<TcpToolState>();
+    let mut connection_info = ConnectionInfo::new(
+        &connection_id,
+        "accepted",
+        stream.local_addr().map(|a| a.to_string()).unwrap_or_default(),
+        Some(peer.to_string()),
+        "connected",
+    );
+    connection_info.server_id = Some(slave_id.clone());
+    state.connection_info.write().await.insert(connection_id.clone(), connection_info.clone());
+    emit_event(&app, "tcp_connection_established", &connection_info);
+
+    let (mut reader, mut writer) = stream.into_split();
+    loop {
+        let (transaction_id, unit_id, pdu, raw) = match read_frame(&mut reader).await {
+            Ok(frame) => frame,
+            Err(e) => {
+                if e.kind() != std::io::ErrorKind::UnexpectedEof {
+                    warn!("Modbus slave {} read error from {}: {}", slave_id, peer, e);
+                }
+                break;
+            }
+        };
+        publish_message(&app, &MessageData::new(&connection_id, &raw, "received", None), &raw).await;
+
+        // 单元号不匹配时不应答，与串口网关的行为一致
+        if slave.unit_id.is_some_and(|id| id != unit_id) {
+            continue;
+        }
+
+        let (response_pdu, update) = process(&mut *slave.map.write().await, &pdu);
+        let response = mbap_frame(transaction_id, unit_id, &response_pdu);
+        if let Err(e) = writer.write_all(&response).await {
+            warn!("Modbus slave {} write error to {}: {}", slave_id, peer, e);
+            break;
+        }
+        publish_message(&app, &MessageData::new(&connection_id, &response, "sent", None), &response).await;
+
+        if let Some((table, address, values)) = update {
+            let update = RegisterUpdate { slave_id: slave_id.clone(), table, address, values };
+            emit_event(&app, "modbus_slave_written", &update);
+        }
+    }
+
+    state.tasks.write().await.remove(&connection_id);
+    if let Some(info) = state.disconnected(&app, &connection_id).await {
+        emit_event(&app, "tcp_connection_closed", &info);
+    }
+}
+
+fn check_slave_range(address: u16, count: usize) -> Result<(), String> {
+    if address as usize + count > 0x10000 {
+        return Err("Address range exceeds the register map".to_string());
+    }
+    Ok(())
+}
+
+/// 修改从站寄存器表，四类数据都可写
+#[tauri::command]
+pub(crate) async fn modbus_slave_set<R: Runtime>(
+    app: AppHandle<R>,
+    slave_id: String,
+    table: ModbusTable,
+    address: u16,
+    values: Vec<u16>,
+) -> Result<(), String> {
+    check_slave_range(address, values.len())?;
+    if table.is_bits() && values.iter().any(|v| *v > 1) {
+        return Err("Coil and discrete input values must be 0 or 1".to_string());
+    }
+    let state = app.state::<TcpToolState>();
+    let slave = state.modbus.slave(&slave_id).await?;
+    slave.map.write().await.write(table, address as usize, &values);
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) async fn modbus_slave_get<R: Runtime>(
+    app: AppHandle<R>,
+    slave_id: String,
+    table: ModbusTable,
+    address: u16,
+    quantity: u16,
+) -> Result<Vec<u16>, String> {
+    check_slave_range(address, quantity as usize)?;
+    let state = app.state::<TcpToolState>();
+    let slave = state.modbus.slave(&slave_id).await?;
+    let values = slave.map.read().await.read(table, address as usize, quantity as usize);
+    Ok(values)
+}
@@ -0,0 +1,389 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{oneshot, Mutex, Notify, RwLock};
+use tracing::{info, warn};
+use chrono::Utc;
+use network_common::payload::{self, to_hex};
+
+use crate::faults::FaultAction;
+use crate::{emit_event, publish_message, socket, ConnectionInfo, MessageData, SocketOptions, TcpToolState};
+
+/// 代理的运行时设置，拦截开关可在运行中切换
+pub struct ProxySettings {
+    upstream_host: String,
+    upstream_port: u16,
+    intercept: AtomicBool,
+}
+
+/// 被拦截、等待用户处理的数据块
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InterceptedFrame {
+    pub frame_id: u64,
+    pub proxy_id: String,
+    pub connection_id: String,
+    /// 数据来源："client" 或 "server"
+    pub side: String,
+    pub content: String,
+    pub hex: String,
+    pub size: usize,
+    pub timestamp: String,
+}
+
+enum InterceptAction {
+    Forward(Vec<u8>),
+    Drop,
+}
+
+struct PendingFrame {
+    frame: InterceptedFrame,
+    data: Vec<u8>,
+    responder: oneshot::Sender<InterceptAction>,
+}
+
+/// 代理相关的共享状态
+#[derive(Default)]
+pub struct ProxyRegistry {
+    proxies: RwLock<HashMap<String, Arc<ProxySettings>>>,
+    pending: Mutex<HashMap<u64, PendingFrame>>,
+    next_frame_id: AtomicU64,
+}
+
+impl ProxyRegistry {
+    /// 移除代理及其等待中的数据块
+    pub(crate) async fn remove(&self, proxy_id: &str) {
+        if self.proxies.write().await.remove(proxy_id).is_some() {
+            self.pending.lock().await.retain(|_, pending| pending.frame.proxy_id != proxy_id);
+        }
+    }
+}
+
+#[tauri::command]
+pub(crate) async fn proxy_start<R: Runtime>(
+    app: AppHandle<R>,
+    port: u16,
+    upstream_host: String,
+    upstream_port: u16,
+    bind_address: Option<String>,
+    intercept: Option<bool>,
+) -> Result<String, String> {
+    let state = app.state::<TcpToolState>();
+    let bind_address = match bind_address {
+        Some(address) => address,
+        None => state.config.read().await.default_bind_address.clone(),
+    };
+    let addr = socket::parse_bind_addr(&bind_address, port)?;
+
+    info!("Starting proxy on {} -> {}:{}", addr, upstream_host, upstream_port);
+
+    let listener = socket::bind_listener(addr, false).map_err(|e| format!("Proxy start failed: {}", e))?;
+    let local_addr = listener.local_addr().map_err(|e| e.to_string())?;
+    let proxy_id = format!("proxy_{}_{}", local_addr.ip(), local_addr.port());
+
+    let connection_info = ConnectionInfo::new(
+        &proxy_id,
+        "proxy",
+        local_addr.to_string(),
+        Some(format!("{}:{}", upstream_host, upstream_port)),
+        "listening",
+    );
+    let settings = Arc::new(ProxySettings {
+        upstream_host,
+        upstream_port,
+        intercept: AtomicBool::new(intercept.unwrap_or(false)),
+    });
+
+    state.proxy.proxies.write().await.insert(proxy_id.clone(), settings.clone());
+    state.connection_info.write().await.insert(proxy_id.clone(), connection_info.clone());
+    let task = tokio::spawn(accept_loop(app.clone(), proxy_id.clone(), listener, settings));
+    state.tasks.write().await.insert(proxy_id.clone(), task);
+
+    app.emit("tcp_server_started", &connection_info).map_err(|e| e.to_string())?;
+
+    info!("Proxy started on {}", local_addr);
+    Ok(proxy_id)
+}
+
+async fn accept_loop<R: Runtime>(
+    app: AppHandle<R>,
+    proxy_id: String,
+    listener: TcpListener,
+    settings: Arc<ProxySettings>,
+) {
+    let state = app.state::<TcpToolState>();
+
+    loop {
+        match listener.accept().await {
+            Ok((stream, peer)) => {
+                let session_id = format!("{}_{}", proxy_id, peer);
+                // 持有写锁直到登记完成，避免上游连接失败的会话先于登记移除自己
+                let mut tasks = state.tasks.write().await;
+                let task = tokio::spawn(run_session(
+                    app.clone(),
+                    proxy_id.clone(),
+                    session_id.clone(),
+                    stream,
+                    peer,
+                    settings.clone(),
+                ));
+                tasks.insert(session_id, task);
+            }
+            Err(e) => {
+                warn!("Proxy {} accept error: {}", proxy_id, e);
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+        }
+    }
+}
+
+/// 为每个客户端连接上游，并双向转发数据
+async fn run_session<R: Runtime>(
+    app: AppHandle<R>,
+    proxy_id: String,
+    session_id: String,
+    client: TcpStream,
+    peer: SocketAddr,
+    settings: Arc<ProxySettings>,
+) {
+    let state = app.state::<TcpToolState>();
+    let options = SocketOptions::resolve(&*state.config.read().await, None);
+    if let Err(e) = options.apply(&client) {
+        warn!("Failed to set socket options for {}: {}", session_id, e);
+    }
+
+    let upstream = match socket::connect(&settings.upstream_host, settings.upstream_port, &options).await {
+        Ok(upstream) => upstream,
+        Err(e) => {
+            warn!("Proxy {} failed to reach upstream for {}: {}", proxy_id, peer, e);
+            state.tasks.write().await.remove(&session_id);
+            return;
+        }
+    };
+
+    let mut connection_info = ConnectionInfo::new(
+        &session_id,
+        "proxy_session",
+        peer.to_string(),
+        upstream.peer_addr().map(|a| a.to_string()).ok(),
+        "connected",
+    );
+    connection_info.server_id = Some(proxy_id.clone());
+    state.connection_info.write().await.insert(session_id.clone(), connection_info.clone());
+    emit_event(&app, "tcp_connection_established", &connection_info);
+    info!("Proxy {} relaying {} to {}:{}", proxy_id, peer, settings.upstream_host, settings.upstream_port);
+
+    let (client_reader, client_writer) = client.into_split();
+    let (upstream_reader, upstream_writer) = upstream.into_split();
+    let relay = Relay {
+        app: &app,
+        proxy_id: &proxy_id,
+        session_id: &session_id,
+        settings: &settings,
+        dropped: Notify::new(),
+    };
+    // 故障注入断开连接时不再等待另一方向结束
+    tokio::select! {
+        _ = async {
+            tokio::join!(
+                relay.pump(client_reader, upstream_writer, "client", "server"),
+                relay.pump(upstream_reader, client_writer, "server", "client"),
+            )
+        } => {}
+        _ = relay.dropped.notified() => {
+            info!("Proxy session {} dropped by fault injection", session_id);
+        }
+    }
+
+    state.tasks.write().await.remove(&session_id);
+    if let Some(info) = state.disconnected(&app, &session_id).await {
+        emit_event(&app, "tcp_connection_closed", &info);
+    }
+    info!("Proxy session {} closed", session_id);
+}
+
+struct Relay<'a, R: Runtime> {
+    app: &'a AppHandle<R>,
+    proxy_id: &'a str,
+    session_id: &'a str,
+    settings: &'a ProxySettings,
+    dropped: Notify,
+}
+
+impl<R: Runtime> Relay<'_, R> {
+    /// 单方向转发：读到的数据先上报，拦截开启时等待用户处理后再写出
+    async fn pump(&self, mut reader: OwnedReadHalf, mut writer: OwnedWriteHalf, from: &str, to: &str) {
+        let state = self.app.state::<TcpToolState>();
+        let mut buf = vec![0u8; 16384];
+
+        loop {
+            let n = match reader.read(&mut buf).await {
+                Ok(0) => break,
+                Ok(n) => n,
+                Err(e) => {
+                    warn!("Proxy session {} read error from {}: {}", self.session_id, from, e);
+                    break;
+                }
+            };
+
+            let mut received = MessageData::new(self.session_id, &buf[..n], "received", None);
+            received.side = Some(from.to_string());
+            publish_message(self.app, &received, &buf[..n]).await;
+
+            let data = if self.settings.intercept.load(Ordering::Relaxed) {
+                match self.intercept(&state, from, buf[..n].to_vec()).await {
+                    InterceptAction::Forward(data) => data,
+                    InterceptAction::Drop => continue,
+                }
+            } else {
+                buf[..n].to_vec()
+            };
+
+            let outcome = match state.faults.write(self.session_id, Some(self.proxy_id), Some(to), &mut writer, &data).await {
+                Ok(outcome) => outcome,
+                Err(e) => {
+                    warn!("Proxy session {} write error to {}: {}", self.session_id, to, e);
+                    break;
+                }
+            };
+            let mut sent = MessageData::new(self.session_id, &outcome.sent, "sent", None);
+            sent.side = Some(to.to_string());
+            publish_message(self.app, &sent, &outcome.sent).await;
+
+            match outcome.action {
+                Some(FaultAction::Drop) => {
+                    self.dropped.notify_one();
+                    return;
+                }
+                Some(FaultAction::HalfClose) => return,
+                None => {}
+            }
+        }
+
+        // 一侧结束后半关闭另一侧，让对端也能读到 EOF
+        let _ = writer.shutdown().await;
+    }
+
+    async fn intercept(&self, state: &TcpToolState, side: &str, data: Vec<u8>) -> InterceptAction {
+        let frame_id = state.proxy.next_frame_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let frame = InterceptedFrame {
+            frame_id,
+            proxy_id: self.proxy_id.to_string(),
+            connection_id: self.session_id.to_string(),
+            side: side.to_string(),
+            content: String::from_utf8_lossy(&data).into_owned(),
+            hex: to_hex(&data),
+            size: data.len(),
+            timestamp: Utc::now().to_rfc3339(),
+        };
+
+        let (responder, decision) = oneshot::channel();
+        state.proxy.pending.lock().await.insert(
+            frame_id,
+            PendingFrame {
+                frame: frame.clone(),
+                data,
+                responder,
+            },
+        );
+        emit_event(self.app, "tcp_proxy_intercepted", &frame);
+
+        // 会话被关闭时等待方随之取消；处理方消失则视为丢弃
+        decision.await.unwrap_or(InterceptAction::Drop)
+    }
+}
+
+/// 切换代理的拦截开关；关闭拦截时放行所有等待中的数据块
+#[tauri::command]
+pub(crate) async fn proxy_set_intercept<R: Runtime>(
+    app: AppHandle<R>,
+    proxy_id: String,
+    enabled: bool,
+) -> Result<(), String> {
+    let state = app.state::<TcpToolState>();
+    let settings = state
+        .proxy
+        .proxies
+        .read()
+        .await
+        .get(&proxy_id)
+        .cloned()
+        .ok_or_else(|| "Proxy not found".to_string())?;
+    settings.intercept.store(enabled, Ordering::Relaxed);
+
+    if !enabled {
+        let mut pending = state.proxy.pending.lock().await;
+        let released: Vec<u64> = pending
+            .iter()
+            .filter(|(_, pending)| pending.frame.proxy_id == proxy_id)
+            .map(|(id, _)| *id)
+            .collect();
+        for id in released {
+            if let Some(pending) = pending.remove(&id) {
+                let _ = pending.responder.send(InterceptAction::Forward(pending.data));
+            }
+        }
+    }
+
+    info!("Proxy {} interception {}", proxy_id, if enabled { "enabled" } else { "disabled" });
+    Ok(())
+}
+
+/// 处理被拦截的数据块："forward" 原样或按 `content` 修改后转发，"drop" 丢弃
+#[tauri::command]
+pub(crate) async fn proxy_resolve_frame<R: Runtime>(
+    app: AppHandle<R>,
+    frame_id: u64,
+    action: String,
+    content: Option<String>,
+    encoding: Option<String>,
+) -> Result<(), String> {
+    let state = app.state::<TcpToolState>();
+    let edited = match (action.as_str(), content) {
+        ("forward", Some(content)) => Some(payload::decode(&content, encoding.as_deref())?),
+        ("forward", None) | ("drop", _) => None,
+        (other, _) => return Err(format!("Unsupported action: {}", other)),
+    };
+
+    let pending = state
+        .proxy
+        .pending
+        .lock()
+        .await
+        .remove(&frame_id)
+        .ok_or_else(|| "Frame not found".to_string())?;
+    let decision = if action == "drop" {
+        InterceptAction::Drop
+    } else {
+        InterceptAction::Forward(edited.unwrap_or(pending.data))
+    };
+    pending
+        .responder
+        .send(decision)
+        .map_err(|_| "Proxy session has ended".to_string())
+}
+
+/// 列出等待处理的数据块，已结束会话的数据块会被跳过
+#[tauri::command]
+pub(crate) async fn proxy_pending_frames<R: Runtime>(
+    app: AppHandle<R>,
+    proxy_id: Option<String>,
+) -> Result<Vec<InterceptedFrame>, String> {
+    let state = app.state::<TcpToolState>();
+    let mut pending = state.proxy.pending.lock().await;
+    pending.retain(|_, pending| !pending.responder.is_closed());
+
+    let mut frames: Vec<InterceptedFrame> = pending
+        .values()
+        .filter(|pending| proxy_id.as_ref().is_none_or(|id| &pending.frame.proxy_id == id))
+        .map(|pending| pending.frame.clone())
+        .collect();
+    frames.sort_by_key(|frame| frame.frame_id);
+    Ok(frames)
+}
@@ -0,0 +1,481 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, Runtime};
+use tokio::fs::File;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader, BufWriter};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{watch, Mutex};
+use tracing::{info, warn};
+use network_common::payload::{self, to_hex};
+
+use crate::{
+    emit_event, publish_message, socket, ConnectionInfo, MessageData, SocketOptions, TcpToolState,
+};
+
+/// 会话文件的第一行，描述被录制的连接
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionHeader {
+    pub version: u32,
+    pub connection_id: String,
+    pub connection_type: String,
+    pub local_addr: String,
+    pub remote_addr: Option<String>,
+    pub started_at: String,
+}
+
+/// 会话文件中的一条流量记录，数据以十六进制保存
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionRecord {
+    /// 距录制开始的微秒数
+    pub offset_us: u64,
+    pub timestamp: String,
+    pub direction: String,
+    #[serde(default)]
+    pub side: Option<String>,
+    #[serde(default)]
+    pub remote_addr: Option<String>,
+    pub data: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordingSummary {
+    pub connection_id: String,
+    pub path: String,
+    pub records: u64,
+    pub bytes: u64,
+}
+
+struct Recorder {
+    path: String,
+    writer: BufWriter<File>,
+    started: Instant,
+    records: u64,
+    bytes: u64,
+}
+
+/// 正在录制的连接
+#[derive(Default)]
+pub struct RecorderRegistry {
+    recorders: Mutex<HashMap<String, Recorder>>,
+}
+
+impl RecorderRegistry {
+    pub(crate) async fn record(&self, message: &MessageData, data: &[u8]) {
+        let mut recorders = self.recorders.lock().await;
+        let Some(recorder) = recorders.get_mut(&message.connection_id) else {
+            return;
+        };
+
+        let record = SessionRecord {
+            offset_us: recorder.started.elapsed().as_micros() as u64,
+            timestamp: message.timestamp.clone(),
+            direction: message.direction.clone(),
+            side: message.side.clone(),
+            remote_addr: message.remote_addr.clone(),
+            data: to_hex(data).replace(' ', ""),
+        };
+        if let Err(e) = write_line(&mut recorder.writer, &record).await {
+            warn!("Failed to record traffic of {}: {}", message.connection_id, e);
+            return;
+        }
+        recorder.records += 1;
+        recorder.bytes += data.len() as u64;
+    }
+
+    pub(crate) async fn stop(&self, connection_id: &str) -> Option<RecordingSummary> {
+        let mut recorder = self.recorders.lock().await.remove(connection_id)?;
+        if let Err(e) = recorder.writer.flush().await {
+            warn!("Failed to flush recording {}: {}", recorder.path, e);
+        }
+        Some(RecordingSummary {
+            connection_id: connection_id.to_string(),
+            path: recorder.path,
+            records: recorder.records,
+            bytes: recorder.bytes,
+        })
+    }
+}
+
+async fn write_line<W: AsyncWrite + Unpin, T: Serialize>(writer: &mut W, value: &T) -> std::io::Result<()> {
+    let mut line = serde_json::to_vec(value).map_err(std::io::Error::other)?;
+    line.push(b'\n');
+    writer.write_all(&line).await
+}
+
+#[tauri::command]
+pub(crate) async fn tcp_record_start<R: Runtime>(
+    app: AppHandle<R>,
+    connection_id: String,
+    path: String,
+) -> Result<(), String> {
+    let state = app.state::<TcpToolState>();
+    let info = state
+        .connection_info
+        .read()
+        .await
+        .get(&connection_id)
+        .cloned()
+        .ok_or_else(|| "Connection not found".to_string())?;
+
+    let file = File::create(&path)
+        .await
+        .map_err(|e| format!("Failed to create {}: {}", path, e))?;
+    let mut writer = BufWriter::new(file);
+    let header = SessionHeader {
+        version: 1,
+        connection_id: info.id,
+        connection_type: info.connection_type,
+        local_addr: info.local_addr,
+        remote_addr: info.remote_addr,
+        started_at: Utc::now().to_rfc3339(),
+    };
+    write_line(&mut writer, &header)
+        .await
+        .map_err(|e| format!("Failed to write {}: {}", path, e))?;
+
+    let recorder = Recorder {
+        path: path.clone(),
+        writer,
+        started: Instant::now(),
+        records: 0,
+        bytes: 0,
+    };
+    if let Some(previous) = state.recorder.recorders.lock().await.insert(connection_id.clone(), recorder) {
+        warn!("Replacing active recording {} of {}", previous.path, connection_id);
+    }
+
+    info!("Recording {} to {}", connection_id, path);
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) async fn tcp_record_stop<R: Runtime>(
+    app: AppHandle<R>,
+    connection_id: String,
+) -> Result<RecordingSummary, String> {
+    let state = app.state::<TcpToolState>();
+    let summary = state
+        .recorder
+        .stop(&connection_id)
+        .await
+        .ok_or_else(|| "No active recording for this connection".to_string())?;
+
+    info!("Recording of {} saved to {} ({} records)", connection_id, summary.path, summary.records);
+    Ok(summary)
+}
+
+async fn load_session(path: &str) -> Result<(SessionHeader, Vec<SessionRecord>), String> {
+    let file = File::open(path)
+        .await
+        .map_err(|e| format!("Failed to open {}: {}", path, e))?;
+    let mut lines = BufReader::new(file).lines();
+    let invalid = |e: serde_json::Error| format!("Invalid session file {}: {}", path, e);
+
+    let header = lines
+        .next_line()
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Session file {} is empty", path))?;
+    let header: SessionHeader = serde_json::from_str(&header).map_err(invalid)?;
+
+    let mut records = Vec::new();
+    while let Some(line) = lines.next_line().await.map_err(|e| e.to_string())? {
+        if !line.trim().is_empty() {
+            records.push(serde_json::from_str(&line).map_err(invalid)?);
+        }
+    }
+    Ok((header, records))
+}
+
+/// 判断记录的数据是否由客户端一侧发出；代理转发出去的副本返回 `None`
+fn from_client(header: &SessionHeader, record: &SessionRecord) -> Option<bool> {
+    match header.connection_type.as_str() {
+        "accepted" => Some(record.direction == "received"),
+        "proxy_session" => match (record.direction.as_str(), record.side.as_deref()) {
+            ("received", Some(side)) => Some(side == "client"),
+            _ => None,
+        },
+        _ => Some(record.direction == "sent"),
+    }
+}
+
+/// 回放的时间控制
+#[derive(Debug, Clone, Copy)]
+enum Timing {
+    Original,
+    Scaled(f64),
+    Fast,
+}
+
+impl Timing {
+    fn parse(timing: Option<&str>, speed: Option<f64>) -> Result<Self, String> {
+        match timing.unwrap_or("original") {
+            "original" => Ok(Timing::Original),
+            "scaled" => match speed {
+                Some(speed) if speed > 0.0 => Ok(Timing::Scaled(speed)),
+                _ => Err("Scaled timing requires a positive speed".to_string()),
+            },
+            "fast" => Ok(Timing::Fast),
+            other => Err(format!("Unsupported timing: {}", other)),
+        }
+    }
+
+    fn delay(self, gap_us: u64) -> Duration {
+        match self {
+            Timing::Original => Duration::from_micros(gap_us),
+            Timing::Scaled(speed) => Duration::from_secs_f64(gap_us as f64 / 1_000_000.0 / speed),
+            Timing::Fast => Duration::ZERO,
+        }
+    }
+}
+
+/// 回放步骤：等待对端发来 `peer_bytes` 字节后，延迟 `gap_us` 再发送
+struct Step {
+    gap_us: u64,
+    peer_bytes: u64,
+    data: Vec<u8>,
+}
+
+/// 从录制中提取一侧要发送的数据，并记下每一步之前对端应发来的字节数
+fn build_steps(header: &SessionHeader, records: &[SessionRecord], client_side: bool) -> Result<Vec<Step>, String> {
+    let mut steps = Vec::new();
+    let mut peer_bytes = 0u64;
+    let mut last_offset = 0u64;
+
+    for record in records {
+        let Some(from_client) = from_client(header, record) else {
+            continue;
+        };
+        let data = payload::decode_hex(&record.data)?;
+        if from_client == client_side {
+            steps.push(Step {
+                gap_us: record.offset_us.saturating_sub(last_offset),
+                peer_bytes,
+                data,
+            });
+        } else {
+            peer_bytes += data.len() as u64;
+        }
+        last_offset = record.offset_us;
+    }
+    Ok(steps)
+}
+
+/// 回放任务的运行参数
+#[derive(Clone)]
+struct Replay {
+    replay_id: String,
+    timing: Timing,
+    /// 等待对端数据的最长时间，超时后继续回放
+    wait_timeout: Duration,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayProgress {
+    pub replay_id: String,
+    pub connection_id: String,
+    pub step: usize,
+    pub total: usize,
+    pub finished: bool,
+}
+
+impl Replay {
+    async fn run<R: Runtime>(&self, app: &AppHandle<R>, connection_id: &str, stream: TcpStream, steps: &[Step]) {
+        let (mut reader, mut writer) = stream.into_split();
+        let (received_tx, mut received_rx) = watch::channel(0u64);
+
+        // 对端的数据照常上报，同时累计字节数用于步骤之间的同步
+        let read_app = app.clone();
+        let read_id = connection_id.to_string();
+        let reader_task = tokio::spawn(async move {
+            let mut buf = vec![0u8; 4096];
+            let mut total = 0u64;
+            while let Ok(n) = reader.read(&mut buf).await {
+                if n == 0 {
+                    break;
+                }
+                let message_data = MessageData::new(&read_id, &buf[..n], "received", None);
+                publish_message(&read_app, &message_data, &buf[..n]).await;
+                total += n as u64;
+                let _ = received_tx.send(total);
+            }
+        });
+
+        // 录制中没有这一侧的数据时直接结束，前端仍然收到完成事件
+        if steps.is_empty() {
+            let progress = ReplayProgress {
+                replay_id: self.replay_id.clone(),
+                connection_id: connection_id.to_string(),
+                step: 0,
+                total: 0,
+                finished: true,
+            };
+            emit_event(app, "tcp_replay_progress", &progress);
+        }
+
+        for (index, step) in steps.iter().enumerate() {
+            if step.peer_bytes > *received_rx.borrow() {
+                let waited =
+                    tokio::time::timeout(self.wait_timeout, received_rx.wait_for(|n| *n >= step.peer_bytes)).await;
+                if waited.is_err() {
+                    warn!("Replay {} timed out waiting for peer data before step {}", self.replay_id, index + 1);
+                }
+            }
+            tokio::time::sleep(self.timing.delay(step.gap_us)).await;
+
+            if let Err(e) = writer.write_all(&step.data).await {
+                warn!("Replay {} write failed: {}", self.replay_id, e);
+                break;
+            }
+            let message_data = MessageData::new(connection_id, &step.data, "sent", None);
+            publish_message(app, &message_data, &step.data).await;
+
+            let progress = ReplayProgress {
+                replay_id: self.replay_id.clone(),
+                connection_id: connection_id.to_string(),
+                step: index + 1,
+                total: steps.len(),
+                finished: index + 1 == steps.len(),
+            };
+            emit_event(app, "tcp_replay_progress", &progress);
+        }
+
+        let _ = writer.shutdown().await;
+        reader_task.abort();
+    }
+}
+
+async fn close_replay_connection<R: Runtime>(app: &AppHandle<R>, connection_id: &str) {
+    let state = app.state::<TcpToolState>();
+    state.tasks.write().await.remove(connection_id);
+    if let Some(info) = state.disconnected(app, connection_id).await {
+        emit_event(app, "tcp_connection_closed", &info);
+    }
+}
+
+/// 回放会话：`mode` 为 "client" 时连接 `host:port` 重发客户端数据，
+/// 为 "server" 时在 `port` 上监听并向每个连入的客户端回放服务端数据
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn tcp_replay_start<R: Runtime>(
+    app: AppHandle<R>,
+    path: String,
+    mode: String,
+    host: Option<String>,
+    port: u16,
+    bind_address: Option<String>,
+    timing: Option<String>,
+    speed: Option<f64>,
+) -> Result<String, String> {
+    let state = app.state::<TcpToolState>();
+    let timing = Timing::parse(timing.as_deref(), speed)?;
+    let (header, records) = load_session(&path).await?;
+    let options = SocketOptions::resolve(&*state.config.read().await, None);
+
+    match mode.as_str() {
+        "client" => {
+            let steps = build_steps(&header, &records, true)?;
+            let host = host.ok_or_else(|| "Client replay requires a target host".to_string())?;
+            let stream = socket::connect(&host, port, &options).await?;
+            let local_addr = stream.local_addr().map_err(|e| e.to_string())?;
+            // 同一目标可以同时回放多次，用本地端口区分
+            let replay_id = format!("replay_{}_{}_{}", host, port, local_addr.port());
+            let replay = Replay {
+                replay_id: replay_id.clone(),
+                timing,
+                wait_timeout: options.connection_timeout,
+            };
+
+            let connection_info = ConnectionInfo::new(
+                &replay_id,
+                "replay",
+                local_addr.to_string(),
+                stream.peer_addr().map(|a| a.to_string()).ok(),
+                "connected",
+            );
+            state.connection_info.write().await.insert(replay_id.clone(), connection_info.clone());
+            emit_event(&app, "tcp_connection_established", &connection_info);
+
+            let task_app = app.clone();
+            // 持有写锁直到登记完成，避免回放任务先于登记结束并移除自己
+            let mut tasks = state.tasks.write().await;
+            let task = tokio::spawn(async move {
+                replay.run(&task_app, &replay.replay_id, stream, &steps).await;
+                close_replay_connection(&task_app, &replay.replay_id).await;
+            });
+            tasks.insert(replay_id.clone(), task);
+            drop(tasks);
+
+            info!("Replaying {} ({} steps) against {}:{}", path, records.len(), host, port);
+            Ok(replay_id)
+        }
+        "server" => {
+            let steps = build_steps(&header, &records, false)?;
+            let bind_address = match bind_address {
+                Some(address) => address,
+                None => state.config.read().await.default_bind_address.clone(),
+            };
+            let addr = socket::parse_bind_addr(&bind_address, port)?;
+            let listener = socket::bind_listener(addr, false).map_err(|e| format!("Replay server start failed: {}", e))?;
+            let local_addr = listener.local_addr().map_err(|e| e.to_string())?;
+            let replay_id = format!("replay_server_{}_{}", local_addr.ip(), local_addr.port());
+            let replay = Replay {
+                replay_id: replay_id.clone(),
+                timing,
+                wait_timeout: options.connection_timeout,
+            };
+
+            let connection_info = ConnectionInfo::new(&replay_id, "replay_server", local_addr.to_string(), None, "listening");
+            state.connection_info.write().await.insert(replay_id.clone(), connection_info.clone());
+            emit_event(&app, "tcp_server_started", &connection_info);
+
+            let task = tokio::spawn(serve_replay(app.clone(), replay, listener, steps));
+            state.tasks.write().await.insert(replay_id.clone(), task);
+
+            info!("Replay server for {} listening on {}", path, local_addr);
+            Ok(replay_id)
+        }
+        other => Err(format!("Unsupported replay mode: {}", other)),
+    }
+}
+
+async fn serve_replay<R: Runtime>(app: AppHandle<R>, replay: Replay, listener: TcpListener, steps: Vec<Step>) {
+    let state = app.state::<TcpToolState>();
+    let steps = std::sync::Arc::new(steps);
+
+    loop {
+        let (stream, peer) = match listener.accept().await {
+            Ok(accepted) => accepted,
+            Err(e) => {
+                warn!("Replay server {} accept error: {}", replay.replay_id, e);
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                continue;
+            }
+        };
+
+        let connection_id = format!("{}_{}", replay.replay_id, peer);
+        let mut connection_info = ConnectionInfo::new(
+            &connection_id,
+            "accepted",
+            stream.local_addr().map(|a| a.to_string()).unwrap_or_default(),
+            Some(peer.to_string()),
+            "connected",
+        );
+        connection_info.server_id = Some(replay.replay_id.clone());
+        state.connection_info.write().await.insert(connection_id.clone(), connection_info.clone());
+        emit_event(&app, "tcp_connection_established", &connection_info);
+
+        let task_app = app.clone();
+        let task_replay = replay.clone();
+        let task_steps = steps.clone();
+        let task_id = connection_id.clone();
+        let mut tasks = state.tasks.write().await;
+        let task = tokio::spawn(async move {
+            task_replay.run(&task_app, &task_id, stream, &task_steps).await;
+            close_replay_connection(&task_app, &task_id).await;
+        });
+        tasks.insert(connection_id, task);
+    }
+}
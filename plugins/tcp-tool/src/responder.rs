@@ -0,0 +1,250 @@
+use chrono::Utc;
+use regex::bytes::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Runtime};
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+use network_common::payload;
+
+use crate::{checksum, save_config, write_connection, TcpToolState};
+
+/// 服务端自动应答规则，随 tcp-tool 配置保存
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResponderRule {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// 匹配方式："exact"、"prefix"、"hex"（`??` 为通配字节）或 "regex"
+    pub match_type: String,
+    pub pattern: String,
+    /// exact/prefix 模式下 `pattern` 的编码，"text"（默认）或 "hex"
+    #[serde(default)]
+    pub pattern_encoding: Option<String>,
+    /// 应答模板，占位符见 [`render`]
+    pub response: String,
+    /// 模板中字面部分的编码，"text"（默认）或 "hex"
+    #[serde(default)]
+    pub response_encoding: Option<String>,
+    /// 发送应答前的延迟（毫秒）
+    #[serde(default)]
+    pub delay_ms: u64,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// 规则的运行时状态：命中计数和已编译的正则表达式
+#[derive(Default)]
+pub struct ResponderRegistry {
+    counters: Mutex<HashMap<String, u64>>,
+    regexes: Mutex<HashMap<String, Regex>>,
+}
+
+impl ResponderRegistry {
+    async fn regex(&self, pattern: &str) -> Result<Regex, String> {
+        let mut regexes = self.regexes.lock().await;
+        if let Some(regex) = regexes.get(pattern) {
+            return Ok(regex.clone());
+        }
+        let regex = Regex::new(pattern).map_err(|e| format!("Invalid regex {}: {}", pattern, e))?;
+        regexes.insert(pattern.to_string(), regex.clone());
+        Ok(regex)
+    }
+
+    async fn next_count(&self, rule_id: &str) -> u64 {
+        let mut counters = self.counters.lock().await;
+        let counter = counters.entry(rule_id.to_string()).or_default();
+        *counter += 1;
+        *counter
+    }
+}
+
+/// 检查规则是否匹配整帧数据，返回捕获组：第 0 组为匹配的数据，
+/// 正则模式下依次为各分组，十六进制模式下每个通配字节为一组
+async fn match_rule(registry: &ResponderRegistry, rule: &ResponderRule, data: &[u8]) -> Result<Option<Vec<Vec<u8>>>, String> {
+    match rule.match_type.as_str() {
+        "exact" => {
+            let pattern = payload::decode(&rule.pattern, rule.pattern_encoding.as_deref())?;
+            Ok((data == pattern.as_slice()).then(|| vec![data.to_vec()]))
+        }
+        "prefix" => {
+            let pattern = payload::decode(&rule.pattern, rule.pattern_encoding.as_deref())?;
+            Ok(data.starts_with(&pattern).then(|| vec![pattern.clone()]))
+        }
+        "hex" => {
+            let pattern = payload::decode_hex_pattern(&rule.pattern)?;
+            if pattern.len() != data.len() {
+                return Ok(None);
+            }
+            let mut groups = vec![data.to_vec()];
+            for (expected, actual) in pattern.iter().zip(data) {
+                match expected {
+                    Some(byte) if byte != actual => return Ok(None),
+                    Some(_) => {}
+                    None => groups.push(vec![*actual]),
+                }
+            }
+            Ok(Some(groups))
+        }
+        "regex" => {
+            let regex = registry.regex(&rule.pattern).await?;
+            Ok(regex.captures(data).map(|captures| {
+                captures
+                    .iter()
+                    .map(|group| group.map(|m| m.as_bytes().to_vec()).unwrap_or_default())
+                    .collect()
+            }))
+        }
+        other => Err(format!("Unsupported match type: {}", other)),
+    }
+}
+
+/// 渲染应答模板，支持的占位符：
+/// `{{0}}`…`{{9}}` 捕获组，`{{counter}}` 规则命中次数，`{{timestamp}}` RFC 3339 时间，
+/// `{{unix}}`/`{{unix_ms}}` 时间戳，`{{hex:0D0A}}` 字面字节，
+/// `{{sum8}}`、`{{xor8}}`、`{{crc8}}`、`{{crc16_modbus}}`、`{{crc16_ccitt_false}}`、`{{crc16_kermit}}`、`{{crc16_xmodem}}`、`{{crc32}}` 为此前已生成内容的校验值
+fn render(template: &str, encoding: Option<&str>, groups: &[Vec<u8>], counter: u64) -> Result<Vec<u8>, String> {
+    let mut output = Vec::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        output.extend(payload::decode(&rest[..start], encoding)?);
+        let end = rest[start..]
+            .find("}}")
+            .ok_or_else(|| "Unterminated placeholder in response template".to_string())?;
+        let placeholder = rest[start + 2..start + end].trim();
+        rest = &rest[start + end + 2..];
+
+        let now = Utc::now();
+        match placeholder {
+            "counter" => output.extend(counter.to_string().into_bytes()),
+            "timestamp" => output.extend(now.to_rfc3339().into_bytes()),
+            "unix" => output.extend(now.timestamp().to_string().into_bytes()),
+            "unix_ms" => output.extend(now.timestamp_millis().to_string().into_bytes()),
+            name if checksum::width(name).is_ok() => {
+                let value = checksum::compute(name, &output)?;
+                output.extend(value);
+            }
+            _ => {
+                if let Some(hex) = placeholder.strip_prefix("hex:") {
+                    output.extend(payload::decode_hex(hex)?);
+                } else if let Ok(index) = placeholder.parse::<usize>() {
+                    output.extend(groups.get(index).cloned().unwrap_or_default());
+                } else {
+                    return Err(format!("Unknown placeholder: {}", placeholder));
+                }
+            }
+        }
+    }
+    output.extend(payload::decode(rest, encoding)?);
+    Ok(output)
+}
+
+/// 服务端收到数据后按顺序匹配规则，由第一条匹配的规则应答
+pub(crate) async fn respond<R: Runtime>(app: &AppHandle<R>, connection_id: &str, data: &[u8]) {
+    let state = app.state::<TcpToolState>();
+    let rules = state.config.read().await.responder_rules.clone();
+
+    for rule in rules.iter().filter(|rule| rule.enabled) {
+        let groups = match match_rule(&state.responder, rule, data).await {
+            Ok(Some(groups)) => groups,
+            Ok(None) => continue,
+            Err(e) => {
+                warn!("Responder rule {} is invalid: {}", rule.id, e);
+                continue;
+            }
+        };
+
+        let counter = state.responder.next_count(&rule.id).await;
+        let response = match render(&rule.response, rule.response_encoding.as_deref(), &groups, counter) {
+            Ok(response) => response,
+            Err(e) => {
+                warn!("Failed to render response of rule {}: {}", rule.id, e);
+                return;
+            }
+        };
+
+        let app = app.clone();
+        let connection_id = connection_id.to_string();
+        let rule_id = rule.id.clone();
+        let delay = Duration::from_millis(rule.delay_ms);
+        // 延迟应答不阻塞连接的读取
+        tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+            match write_connection(&app, &connection_id, &response).await {
+                Ok(()) => info!("Rule {} answered {} ({} bytes)", rule_id, connection_id, response.len()),
+                Err(e) => warn!("Rule {} failed to answer {}: {}", rule_id, connection_id, e),
+            }
+        });
+        return;
+    }
+}
+
+fn validate(rule: &ResponderRule) -> Result<(), String> {
+    match rule.match_type.as_str() {
+        "exact" | "prefix" => payload::decode(&rule.pattern, rule.pattern_encoding.as_deref()).map(|_| ()),
+        "hex" => payload::decode_hex_pattern(&rule.pattern).map(|_| ()),
+        "regex" => Regex::new(&rule.pattern)
+            .map(|_| ())
+            .map_err(|e| format!("Invalid regex {}: {}", rule.pattern, e)),
+        other => Err(format!("Unsupported match type: {}", other)),
+    }?;
+    render(&rule.response, rule.response_encoding.as_deref(), &[], 0).map(|_| ())
+}
+
+#[tauri::command]
+pub(crate) async fn responder_get_rules<R: Runtime>(app: AppHandle<R>) -> Result<Vec<ResponderRule>, String> {
+    let state = app.state::<TcpToolState>();
+    let rules = state.config.read().await.responder_rules.clone();
+    Ok(rules)
+}
+
+/// 新增或更新规则（按 id），id 为空时自动生成
+#[tauri::command]
+pub(crate) async fn responder_save_rule<R: Runtime>(app: AppHandle<R>, mut rule: ResponderRule) -> Result<ResponderRule, String> {
+    validate(&rule)?;
+    if rule.id.is_empty() {
+        rule.id = format!("rule_{}", uuid::Uuid::new_v4().simple());
+    }
+
+    let state = app.state::<TcpToolState>();
+    {
+        let mut config = state.config.write().await;
+        match config.responder_rules.iter_mut().find(|r| r.id == rule.id) {
+            Some(existing) => *existing = rule.clone(),
+            None => config.responder_rules.push(rule.clone()),
+        }
+    }
+    save_config(&app).await?;
+
+    info!("Responder rule {} saved", rule.id);
+    Ok(rule)
+}
+
+#[tauri::command]
+pub(crate) async fn responder_remove_rule<R: Runtime>(app: AppHandle<R>, rule_id: String) -> Result<(), String> {
+    let state = app.state::<TcpToolState>();
+    state.config.write().await.responder_rules.retain(|r| r.id != rule_id);
+    state.responder.counters.lock().await.remove(&rule_id);
+    save_config(&app).await?;
+
+    info!("Responder rule {} removed", rule_id);
+    Ok(())
+}
+
+/// 整体替换规则列表，也用于调整顺序：匹配时按列表顺序优先
+#[tauri::command]
+pub(crate) async fn responder_set_rules<R: Runtime>(app: AppHandle<R>, rules: Vec<ResponderRule>) -> Result<(), String> {
+    for rule in &rules {
+        validate(rule)?;
+    }
+    let state = app.state::<TcpToolState>();
+    state.config.write().await.responder_rules = rules;
+    save_config(&app).await
+}
@@ -0,0 +1,303 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Runtime};
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use tokio::time::MissedTickBehavior;
+use tracing::{info, warn};
+use network_common::payload;
+
+use crate::{emit_event, send_payload, TcpToolState};
+
+/// 序列中的一步：等待 `delay_ms` 后发送
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduleStep {
+    pub message: String,
+    #[serde(default)]
+    pub encoding: Option<String>,
+    #[serde(default)]
+    pub delay_ms: u64,
+}
+
+/// 定时发送任务的状态，状态变化时通过 "tcp_schedule_updated" 事件通知前端
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduleInfo {
+    pub schedule_id: String,
+    pub connection_id: String,
+    /// "repeat" 或 "sequence"
+    pub kind: String,
+    /// "running"、"finished"、"cancelled" 或 "failed"
+    pub status: String,
+    pub sent: u64,
+    /// 计划发送的总次数，为空表示一直发送直到取消
+    pub total: Option<u64>,
+    pub error: Option<String>,
+}
+
+struct Schedule {
+    info: ScheduleInfo,
+    task: JoinHandle<()>,
+}
+
+#[derive(Default)]
+pub struct ScheduleRegistry {
+    schedules: Mutex<HashMap<String, Schedule>>,
+    next_id: AtomicU64,
+}
+
+impl ScheduleRegistry {
+    /// 取消连接上的全部定时发送，连接关闭时调用
+    pub(crate) async fn cancel_connection(&self, connection_id: &str) -> Vec<ScheduleInfo> {
+        let mut schedules = self.schedules.lock().await;
+        let ids: Vec<String> = schedules
+            .values()
+            .filter(|s| s.info.connection_id == connection_id)
+            .map(|s| s.info.schedule_id.clone())
+            .collect();
+        ids.iter()
+            .filter_map(|id| schedules.remove(id))
+            .map(|schedule| {
+                schedule.task.abort();
+                ScheduleInfo {
+                    status: "cancelled".to_string(),
+                    ..schedule.info
+                }
+            })
+            .collect()
+    }
+}
+
+async fn is_reconnecting(state: &TcpToolState, connection_id: &str) -> bool {
+    state
+        .connection_info
+        .read()
+        .await
+        .get(connection_id)
+        .is_some_and(|info| info.status == "reconnecting")
+}
+
+/// 发送计划中的一条数据并更新进度；连接自动重连期间跳过这一次，其他发送失败时结束计划
+async fn send_step<R: Runtime>(app: &AppHandle<R>, schedule_id: &str, data: &[u8]) -> bool {
+    let state = app.state::<TcpToolState>();
+    let Some(connection_id) = state
+        .schedules
+        .schedules
+        .lock()
+        .await
+        .get(schedule_id)
+        .map(|s| s.info.connection_id.clone())
+    else {
+        return false;
+    };
+
+    if is_reconnecting(&state, &connection_id).await {
+        return true;
+    }
+    let result = send_payload(app, &connection_id, data.to_vec()).await;
+    if result.is_err() && is_reconnecting(&state, &connection_id).await {
+        info!("Schedule {} skipped a send while {} reconnects", schedule_id, connection_id);
+        return true;
+    }
+
+    let mut schedules = state.schedules.schedules.lock().await;
+    let Some(schedule) = schedules.get_mut(schedule_id) else {
+        return false;
+    };
+    match result {
+        Ok(()) => {
+            schedule.info.sent += 1;
+            emit_event(app, "tcp_schedule_updated", &schedule.info);
+            true
+        }
+        Err(e) => {
+            warn!("Schedule {} failed to send to {}: {}", schedule_id, connection_id, e);
+            schedule.info.status = "failed".to_string();
+            schedule.info.error = Some(e);
+            emit_event(app, "tcp_schedule_updated", &schedule.info);
+            schedules.remove(schedule_id);
+            false
+        }
+    }
+}
+
+async fn finish<R: Runtime>(app: &AppHandle<R>, schedule_id: &str) {
+    let state = app.state::<TcpToolState>();
+    let schedule = state.schedules.schedules.lock().await.remove(schedule_id);
+    if let Some(mut schedule) = schedule {
+        schedule.info.status = "finished".to_string();
+        emit_event(app, "tcp_schedule_updated", &schedule.info);
+        info!("Schedule {} finished after {} sends", schedule_id, schedule.info.sent);
+    }
+}
+
+/// 登记计划并启动任务；任务在登记完成后才开始运行
+async fn start<R: Runtime, F>(app: &AppHandle<R>, connection_id: String, kind: &str, total: Option<u64>, run: F) -> String
+where
+    F: FnOnce(AppHandle<R>, String) -> JoinHandle<()>,
+{
+    let state = app.state::<TcpToolState>();
+    let registry = &state.schedules;
+    let schedule_id = format!("schedule_{}", registry.next_id.fetch_add(1, Ordering::Relaxed) + 1);
+    let info = ScheduleInfo {
+        schedule_id: schedule_id.clone(),
+        connection_id,
+        kind: kind.to_string(),
+        status: "running".to_string(),
+        sent: 0,
+        total,
+        error: None,
+    };
+
+    let mut schedules = registry.schedules.lock().await;
+    let task = run(app.clone(), schedule_id.clone());
+    emit_event(app, "tcp_schedule_updated", &info);
+    schedules.insert(schedule_id.clone(), Schedule { info, task });
+    schedule_id
+}
+
+async fn ensure_connection(state: &TcpToolState, connection_id: &str) -> Result<(), String> {
+    match state.connection_info.read().await.get(connection_id) {
+        Some(info) if info.status != "disconnected" => Ok(()),
+        Some(_) => Err("Connection is closed".to_string()),
+        None => Err("Connection not found".to_string()),
+    }
+}
+
+/// 每隔 `interval_ms` 发送一次，`count` 为空或 0 时一直发送直到取消
+#[tauri::command]
+pub(crate) async fn tcp_schedule_repeat<R: Runtime>(
+    app: AppHandle<R>,
+    connection_id: String,
+    message: String,
+    encoding: Option<String>,
+    interval_ms: u64,
+    count: Option<u64>,
+) -> Result<String, String> {
+    if interval_ms == 0 {
+        return Err("Interval must be greater than 0".to_string());
+    }
+    let data = payload::decode(&message, encoding.as_deref())?;
+    ensure_connection(&app.state::<TcpToolState>(), &connection_id).await?;
+    let total = count.filter(|count| *count > 0);
+
+    let schedule_id = start(&app, connection_id.clone(), "repeat", total, move |app, schedule_id| {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_millis(interval_ms));
+            interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            let mut sent = 0u64;
+            while total.is_none_or(|total| sent < total) {
+                interval.tick().await;
+                if !send_step(&app, &schedule_id, &data).await {
+                    return;
+                }
+                sent += 1;
+            }
+            finish(&app, &schedule_id).await;
+        })
+    })
+    .await;
+
+    info!("Schedule {} repeats on {} every {} ms", schedule_id, connection_id, interval_ms);
+    Ok(schedule_id)
+}
+
+/// 按顺序发送一组数据，每步发送前等待该步的延迟；`loops` 为 0 时循环直到取消，默认执行一遍
+#[tauri::command]
+pub(crate) async fn tcp_schedule_sequence<R: Runtime>(
+    app: AppHandle<R>,
+    connection_id: String,
+    steps: Vec<ScheduleStep>,
+    loops: Option<u64>,
+) -> Result<String, String> {
+    if steps.is_empty() {
+        return Err("Sequence has no steps".to_string());
+    }
+    let steps = steps
+        .iter()
+        .map(|step| Ok((Duration::from_millis(step.delay_ms), payload::decode(&step.message, step.encoding.as_deref())?)))
+        .collect::<Result<Vec<_>, String>>()?;
+    ensure_connection(&app.state::<TcpToolState>(), &connection_id).await?;
+    let loops = match loops.unwrap_or(1) {
+        0 => None,
+        loops => Some(loops),
+    };
+    let step_count = steps.len();
+    let total = match loops {
+        Some(loops) => Some(
+            loops
+                .checked_mul(step_count as u64)
+                .ok_or_else(|| "Too many loops".to_string())?,
+        ),
+        None => None,
+    };
+
+    let schedule_id = start(&app, connection_id.clone(), "sequence", total, move |app, schedule_id| {
+        tokio::spawn(async move {
+            let mut round = 0u64;
+            while loops.is_none_or(|loops| round < loops) {
+                for (delay, data) in &steps {
+                    tokio::time::sleep(*delay).await;
+                    if !send_step(&app, &schedule_id, data).await {
+                        return;
+                    }
+                }
+                round += 1;
+            }
+            finish(&app, &schedule_id).await;
+        })
+    })
+    .await;
+
+    info!("Schedule {} sends a sequence of {} steps on {}", schedule_id, step_count, connection_id);
+    Ok(schedule_id)
+}
+
+/// 取消指定的计划；未指定 `schedule_id` 时取消该连接上的全部计划
+#[tauri::command]
+pub(crate) async fn tcp_schedule_cancel<R: Runtime>(
+    app: AppHandle<R>,
+    connection_id: String,
+    schedule_id: Option<String>,
+) -> Result<Vec<ScheduleInfo>, String> {
+    let state = app.state::<TcpToolState>();
+    let cancelled = match schedule_id {
+        Some(schedule_id) => {
+            let mut schedules = state.schedules.schedules.lock().await;
+            let owned = schedules
+                .get(&schedule_id)
+                .is_some_and(|schedule| schedule.info.connection_id == connection_id);
+            // 只移除属于该连接的计划，其他连接的计划保持不变
+            let Some(schedule) = owned.then(|| schedules.remove(&schedule_id)).flatten() else {
+                return Err("Schedule not found".to_string());
+            };
+            schedule.task.abort();
+            vec![ScheduleInfo {
+                status: "cancelled".to_string(),
+                ..schedule.info
+            }]
+        }
+        None => state.schedules.cancel_connection(&connection_id).await,
+    };
+
+    for info in &cancelled {
+        emit_event(&app, "tcp_schedule_updated", info);
+    }
+    info!("Cancelled {} schedules on {}", cancelled.len(), connection_id);
+    Ok(cancelled)
+}
+
+#[tauri::command]
+pub(crate) async fn tcp_schedule_list<R: Runtime>(
+    app: AppHandle<R>,
+    connection_id: Option<String>,
+) -> Result<Vec<ScheduleInfo>, String> {
+    let state = app.state::<TcpToolState>();
+    let schedules = state.schedules.schedules.lock().await;
+    Ok(schedules
+        .values()
+        .filter(|s| connection_id.as_ref().is_none_or(|id| &s.info.connection_id == id))
+        .map(|s| s.info.clone())
+        .collect())
+}
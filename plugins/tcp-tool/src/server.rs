@@ -0,0 +1,107 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Runtime};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::Mutex;
+use tokio_rustls::TlsAcceptor;
+use tracing::{info, warn};
+
+use crate::{emit_event, read_loop, tls, ConnectionInfo, Established, SocketOptions, TcpToolState};
+
+/// 接受客户端连接，每个连接由独立的任务处理
+pub(crate) async fn accept_loop<R: Runtime>(
+    app: AppHandle<R>,
+    server_id: String,
+    listener: TcpListener,
+    acceptor: Option<TlsAcceptor>,
+) {
+    let state = app.state::<TcpToolState>();
+
+    loop {
+        match listener.accept().await {
+            Ok((stream, peer)) => {
+                let connection_id = format!("{}_{}", server_id, peer);
+                // 持有写锁直到登记完成，避免连接任务先于登记结束并移除自己
+                let mut tasks = state.tasks.write().await;
+                let task = tokio::spawn(handle_connection(
+                    app.clone(),
+                    server_id.clone(),
+                    connection_id.clone(),
+                    stream,
+                    peer,
+                    acceptor.clone(),
+                ));
+                tasks.insert(connection_id, task);
+            }
+            Err(e) => {
+                // 文件描述符耗尽等错误是暂时的，稍后重试而不是关闭服务器
+                warn!("Server {} accept error: {}", server_id, e);
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+        }
+    }
+}
+
+async fn handle_connection<R: Runtime>(
+    app: AppHandle<R>,
+    server_id: String,
+    connection_id: String,
+    stream: TcpStream,
+    peer: SocketAddr,
+    acceptor: Option<TlsAcceptor>,
+) {
+    let state = app.state::<TcpToolState>();
+    let options = SocketOptions::resolve(&*state.config.read().await, None);
+    if let Err(e) = options.apply(&stream) {
+        warn!("Failed to set socket options for {}: {}", connection_id, e);
+    }
+    let local_addr = stream.local_addr().map(|a| a.to_string()).unwrap_or_default();
+
+    let established = match acceptor {
+        Some(acceptor) => match tokio::time::timeout(options.connection_timeout, acceptor.accept(stream)).await {
+            Ok(Ok(stream)) => {
+                let info = tls::server_info(&stream);
+                Established::new(stream, local_addr, peer.to_string(), Some(info))
+            }
+            Ok(Err(e)) => {
+                warn!("TLS handshake with {} failed: {}", peer, e);
+                state.tasks.write().await.remove(&connection_id);
+                return;
+            }
+            Err(_) => {
+                warn!("TLS handshake with {} timed out", peer);
+                state.tasks.write().await.remove(&connection_id);
+                return;
+            }
+        },
+        None => Established::new(stream, local_addr, peer.to_string(), None),
+    };
+
+    let mut connection_info = ConnectionInfo::new(
+        &connection_id,
+        "accepted",
+        established.local_addr,
+        Some(established.remote_addr),
+        "connected",
+    );
+    connection_info.server_id = Some(server_id.clone());
+    connection_info.tls = established.tls;
+
+    state.connections.write().await.insert(connection_id.clone(), Arc::new(Mutex::new(established.writer)));
+    state.connection_info.write().await.insert(connection_id.clone(), connection_info.clone());
+    emit_event(&app, "tcp_connection_established", &connection_info);
+    info!("Server {} accepted connection from {}", server_id, peer);
+
+    let mut reader = established.reader;
+    if let Err(e) = read_loop(&app, &connection_id, &mut reader, true).await {
+        warn!("Connection {} read error: {}", connection_id, e);
+    }
+
+    state.connections.write().await.remove(&connection_id);
+    state.tasks.write().await.remove(&connection_id);
+    if let Some(info) = state.disconnected(&app, &connection_id).await {
+        emit_event(&app, "tcp_connection_closed", &info);
+    }
+    info!("Connection {} closed", connection_id);
+}
@@ -0,0 +1,182 @@
+use serde::{Deserialize, Serialize};
+use socket2::{Domain, SockRef, Socket, TcpKeepalive, Type};
+use std::collections::BTreeMap;
+use std::net::{IpAddr, SocketAddr};
+use std::time::Duration;
+use network_proxy::ProxyConfig;
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::TcpToolConfig;
+
+/// 单个连接的套接字选项，未设置的字段沿用 `TcpToolConfig` 中的默认值
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConnectOptions {
+    pub connection_timeout: Option<u64>,
+    pub auto_reconnect: Option<bool>,
+    /// TCP keepalive 空闲时间（秒），0 表示即使配置中开启也对这个连接关闭
+    pub keepalive: Option<u64>,
+    pub nodelay: Option<bool>,
+    pub linger: Option<u64>,
+    /// 是否经过 shell 中配置的代理，未设置时按代理的全局开关和直连列表决定
+    pub proxy: Option<bool>,
+}
+
+/// 合并配置与连接选项后的最终套接字设置
+#[derive(Debug, Clone)]
+pub struct SocketOptions {
+    pub connection_timeout: Duration,
+    pub auto_reconnect: bool,
+    /// TCP keepalive 空闲时间（秒），`None` 表示关闭
+    pub keepalive: Option<u64>,
+    pub nodelay: bool,
+    /// SO_LINGER 时间（秒），`None` 表示使用系统默认行为
+    pub linger: Option<u64>,
+    pub reconnect_initial_delay: Duration,
+    pub reconnect_max_delay: Duration,
+    /// 最大重连次数，0 表示不限制
+    pub reconnect_max_attempts: u32,
+    /// 出站连接使用的代理，由调用方按 [`ConnectOptions::proxy`] 解析后填入
+    pub proxy: Option<ProxyConfig>,
+}
+
+impl SocketOptions {
+    pub fn resolve(config: &TcpToolConfig, options: Option<ConnectOptions>) -> Self {
+        let options = options.unwrap_or_default();
+        Self {
+            connection_timeout: Duration::from_millis(
+                options.connection_timeout.unwrap_or(config.connection_timeout),
+            ),
+            auto_reconnect: options.auto_reconnect.unwrap_or(config.auto_reconnect),
+            keepalive: options.keepalive.or(config.keepalive).filter(|secs| *secs > 0),
+            nodelay: options.nodelay.unwrap_or(config.nodelay),
+            linger: options.linger.or(config.linger),
+            reconnect_initial_delay: Duration::from_millis(config.reconnect_initial_delay),
+            reconnect_max_delay: Duration::from_millis(config.reconnect_max_delay),
+            reconnect_max_attempts: config.reconnect_max_attempts,
+            proxy: None,
+        }
+    }
+
+    /// 将 keepalive、TCP_NODELAY 和 linger 应用到已建立的连接上
+    pub fn apply(&self, stream: &TcpStream) -> std::io::Result<()> {
+        stream.set_nodelay(self.nodelay)?;
+
+        let socket = SockRef::from(stream);
+        if let Some(secs) = self.keepalive {
+            let keepalive = TcpKeepalive::new().with_time(Duration::from_secs(secs));
+            socket.set_tcp_keepalive(&keepalive)?;
+        }
+        if let Some(secs) = self.linger {
+            socket.set_linger(Some(Duration::from_secs(secs)))?;
+        }
+        Ok(())
+    }
+}
+
+/// 在超时时间内建立 TCP 连接并应用套接字选项，配置了代理时经代理建立隧道
+pub async fn connect(host: &str, port: u16, options: &SocketOptions) -> Result<TcpStream, String> {
+    let connect = async {
+        let stream = match &options.proxy {
+            Some(proxy) => {
+                proxy.validate()?;
+                TcpStream::connect((proxy.host.as_str(), proxy.port))
+                    .await
+                    .map_err(|e| format!("Failed to connect to proxy {}: {}", proxy.address(), e))?
+            }
+            None => TcpStream::connect((host, port))
+                .await
+                .map_err(|e| format!("Connection failed: {}", e))?,
+        };
+        options
+            .apply(&stream)
+            .map_err(|e| format!("Failed to set socket options: {}", e))?;
+
+        match &options.proxy {
+            Some(proxy) => proxy.handshake(stream, host, port).await,
+            None => Ok(stream),
+        }
+    };
+
+    tokio::time::timeout(options.connection_timeout, connect)
+        .await
+        .map_err(|_| format!("Connection timed out after {}ms", options.connection_timeout.as_millis()))?
+}
+
+/// 连接的对端地址，经代理时为目标的 "host:port" 而不是代理服务器地址
+pub fn peer_addr(stream: &TcpStream, host: &str, port: u16, options: &SocketOptions) -> Result<String, String> {
+    match options.proxy {
+        // IPv6 地址需要加方括号，与 `SocketAddr` 的格式一致
+        Some(_) => Ok(match host.parse::<IpAddr>() {
+            Ok(ip) => SocketAddr::new(ip, port).to_string(),
+            Err(_) => format!("{}:{}", host, port),
+        }),
+        None => stream.peer_addr().map(|addr| addr.to_string()).map_err(|e| e.to_string()),
+    }
+}
+
+/// 重连间隔的下限，避免初始间隔配置为 0 时连续不断地重连
+const MIN_RECONNECT_DELAY: Duration = Duration::from_millis(100);
+
+/// 指数退避的重连间隔
+pub struct Backoff {
+    current: Duration,
+    max: Duration,
+}
+
+impl Backoff {
+    pub fn new(initial: Duration, max: Duration) -> Self {
+        let current = initial.max(MIN_RECONNECT_DELAY);
+        Self { current, max: max.max(current) }
+    }
+
+    pub fn next_delay(&mut self) -> Duration {
+        let delay = self.current;
+        self.current = (self.current * 2).min(self.max);
+        delay
+    }
+}
+
+/// 本机网络接口及其地址
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkInterface {
+    pub name: String,
+    pub addresses: Vec<String>,
+    pub is_loopback: bool,
+}
+
+pub fn list_interfaces() -> std::io::Result<Vec<NetworkInterface>> {
+    let mut interfaces: BTreeMap<String, NetworkInterface> = BTreeMap::new();
+    for iface in if_addrs::get_if_addrs()? {
+        let entry = interfaces.entry(iface.name.clone()).or_insert_with(|| NetworkInterface {
+            name: iface.name.clone(),
+            addresses: Vec::new(),
+            is_loopback: iface.is_loopback(),
+        });
+        entry.addresses.push(iface.ip().to_string());
+    }
+    Ok(interfaces.into_values().collect())
+}
+
+/// 解析监听地址，支持 `127.0.0.1`、`::1`、`[::]` 等写法
+pub fn parse_bind_addr(address: &str, port: u16) -> Result<SocketAddr, String> {
+    let address = address.trim().trim_start_matches('[').trim_end_matches(']');
+    let ip: IpAddr = address
+        .parse()
+        .map_err(|_| format!("Invalid bind address: {}", address))?;
+    Ok(SocketAddr::new(ip, port))
+}
+
+/// 绑定监听套接字；IPv6 地址在 `dual_stack` 为 true 时同时接受 IPv4 连接
+pub fn bind_listener(addr: SocketAddr, dual_stack: bool) -> std::io::Result<TcpListener> {
+    let socket = Socket::new(Domain::for_address(addr), Type::STREAM, None)?;
+    if addr.is_ipv6() {
+        socket.set_only_v6(!dual_stack)?;
+    }
+    #[cfg(not(windows))]
+    socket.set_reuse_address(true)?;
+    socket.set_nonblocking(true)?;
+    socket.bind(&addr.into())?;
+    socket.listen(1024)?;
+    TcpListener::from_std(socket.into())
+}
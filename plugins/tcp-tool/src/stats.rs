@@ -0,0 +1,178 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, Runtime};
+use tokio::sync::Mutex;
+
+use crate::{emit_event, ConnectionInfo, TcpToolState};
+
+/// 一个统计周期内的平均速率
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrafficSample {
+    pub timestamp: String,
+    pub bytes_sent_per_sec: f64,
+    pub bytes_received_per_sec: f64,
+    pub messages_sent_per_sec: f64,
+    pub messages_received_per_sec: f64,
+}
+
+/// 连接的实时统计，周期性地通过 "tcp_connection_stats" 事件发送（不含历史序列）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectionStats {
+    pub connection_id: String,
+    pub status: String,
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    pub messages_sent: u64,
+    pub messages_received: u64,
+    pub bytes_sent_per_sec: f64,
+    pub bytes_received_per_sec: f64,
+    pub peak_bytes_sent_per_sec: f64,
+    pub peak_bytes_received_per_sec: f64,
+    pub last_activity: Option<String>,
+    pub duration_ms: u64,
+    /// 最近的采样序列，仅 `tcp_get_stats` 返回
+    #[serde(default)]
+    pub history: Vec<TrafficSample>,
+}
+
+struct Tracker {
+    started: Instant,
+    last_sampled: Instant,
+    /// 上次采样时的累计值：(发送字节, 接收字节, 发送条数, 接收条数)
+    totals: (u64, u64, u64, u64),
+    duration: Duration,
+    peak_sent: f64,
+    peak_received: f64,
+    history: VecDeque<TrafficSample>,
+}
+
+impl Tracker {
+    /// 连接时长从连接建立时算起，而不是从第一次采样开始
+    fn new(now: Instant, info: &ConnectionInfo) -> Self {
+        let elapsed = DateTime::parse_from_rfc3339(&info.created_at)
+            .ok()
+            .and_then(|created| (Utc::now() - created.with_timezone(&Utc)).to_std().ok())
+            .unwrap_or_default();
+        Self {
+            started: now.checked_sub(elapsed).unwrap_or(now),
+            last_sampled: now,
+            totals: (0, 0, 0, 0),
+            duration: elapsed,
+            peak_sent: 0.0,
+            peak_received: 0.0,
+            history: VecDeque::new(),
+        }
+    }
+
+    fn stats(&self, info: &ConnectionInfo, with_history: bool) -> ConnectionStats {
+        let latest = self.history.back();
+        ConnectionStats {
+            connection_id: info.id.clone(),
+            status: info.status.clone(),
+            bytes_sent: info.bytes_sent,
+            bytes_received: info.bytes_received,
+            messages_sent: info.messages_sent,
+            messages_received: info.messages_received,
+            bytes_sent_per_sec: latest.map(|s| s.bytes_sent_per_sec).unwrap_or_default(),
+            bytes_received_per_sec: latest.map(|s| s.bytes_received_per_sec).unwrap_or_default(),
+            peak_bytes_sent_per_sec: self.peak_sent,
+            peak_bytes_received_per_sec: self.peak_received,
+            last_activity: info.last_activity.clone(),
+            duration_ms: self.duration.as_millis() as u64,
+            history: if with_history { self.history.iter().cloned().collect() } else { Vec::new() },
+        }
+    }
+}
+
+/// 各连接的速率跟踪
+#[derive(Default)]
+pub struct StatsRegistry {
+    trackers: Mutex<HashMap<String, Tracker>>,
+}
+
+impl StatsRegistry {
+    pub(crate) async fn remove(&self, connection_id: &str) {
+        self.trackers.lock().await.remove(connection_id);
+    }
+
+    /// 为所有活动的连接采样一次，返回本次的统计
+    async fn sample(&self, connections: &[ConnectionInfo], history_size: usize) -> Vec<ConnectionStats> {
+        let now = Instant::now();
+        let mut trackers = self.trackers.lock().await;
+
+        connections
+            .iter()
+            // 监听中的服务器本身没有流量，统计落在它接受的连接上
+            .filter(|info| info.status != "disconnected" && info.status != "listening")
+            .map(|info| {
+                let tracker = trackers.entry(info.id.clone()).or_insert_with(|| Tracker::new(now, info));
+                let secs = now.duration_since(tracker.last_sampled).as_secs_f64();
+                let totals = (info.bytes_sent, info.bytes_received, info.messages_sent, info.messages_received);
+                if secs > 0.0 {
+                    let rate = |current: u64, previous: u64| current.saturating_sub(previous) as f64 / secs;
+                    let sample = TrafficSample {
+                        timestamp: Utc::now().to_rfc3339(),
+                        bytes_sent_per_sec: rate(totals.0, tracker.totals.0),
+                        bytes_received_per_sec: rate(totals.1, tracker.totals.1),
+                        messages_sent_per_sec: rate(totals.2, tracker.totals.2),
+                        messages_received_per_sec: rate(totals.3, tracker.totals.3),
+                    };
+                    tracker.peak_sent = tracker.peak_sent.max(sample.bytes_sent_per_sec);
+                    tracker.peak_received = tracker.peak_received.max(sample.bytes_received_per_sec);
+                    tracker.history.push_back(sample);
+                    while tracker.history.len() > history_size {
+                        tracker.history.pop_front();
+                    }
+                }
+                tracker.last_sampled = now;
+                tracker.totals = totals;
+                tracker.duration = now.duration_since(tracker.started);
+                tracker.stats(info, false)
+            })
+            .collect()
+    }
+}
+
+/// 后台采样任务，插件初始化时启动
+pub(crate) async fn run<R: Runtime>(app: AppHandle<R>) {
+    let state = app.state::<TcpToolState>();
+
+    loop {
+        let (interval, history_size) = {
+            let config = state.config.read().await;
+            (config.stats_interval_ms.max(100), config.stats_history_size)
+        };
+        tokio::time::sleep(Duration::from_millis(interval)).await;
+
+        let connections: Vec<ConnectionInfo> = state.connection_info.read().await.values().cloned().collect();
+        let stats = state.stats.sample(&connections, history_size).await;
+        if !stats.is_empty() {
+            emit_event(&app, "tcp_connection_stats", &stats);
+        }
+    }
+}
+
+/// 获取连接的统计及最近的采样序列
+#[tauri::command]
+pub(crate) async fn tcp_get_stats<R: Runtime>(
+    app: AppHandle<R>,
+    connection_id: String,
+) -> Result<ConnectionStats, String> {
+    let state = app.state::<TcpToolState>();
+    let info = state
+        .connection_info
+        .read()
+        .await
+        .get(&connection_id)
+        .cloned()
+        .ok_or_else(|| "Connection not found".to_string())?;
+
+    let trackers = state.stats.trackers.lock().await;
+    let stats = match trackers.get(&connection_id) {
+        Some(tracker) => tracker.stats(&info, true),
+        None => Tracker::new(Instant::now(), &info).stats(&info, true),
+    };
+    Ok(stats)
+}
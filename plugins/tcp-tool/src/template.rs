@@ -0,0 +1,365 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::{AppHandle, Manager, Runtime};
+use tracing::info;
+use network_common::payload;
+
+use crate::{checksum, save_config, send_payload, TcpToolState};
+
+/// 可复用的报文模板，随 tcp-tool 配置保存
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageTemplate {
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// 按顺序拼接的字段
+    pub fields: Vec<TemplateField>,
+}
+
+/// 模板字段
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateField {
+    pub name: String,
+    /// "u8"、"u16"、"u32"、"i8"、"i16"、"i32"、"string"、"hex"，
+    /// 或自动计算的 "length" 和 "checksum"
+    pub field_type: String,
+    /// 多字节整数的字节序，"big"（默认）或 "little"；checksum 字段为空时使用算法的惯例字节序
+    #[serde(default)]
+    pub endianness: Option<String>,
+    /// 字段值：整数支持十进制和 `0x` 十六进制，string 为文本，hex 为十六进制字节
+    #[serde(default)]
+    pub value: Option<String>,
+    /// 变量名，发送时从表单填写的变量取值，未填写时使用 `value`
+    #[serde(default)]
+    pub variable: Option<String>,
+    /// string/hex 字段的固定字节数（不足时补 0），length 字段的字节数（1、2 或 4，默认 1）
+    #[serde(default)]
+    pub size: Option<usize>,
+    /// length/checksum 字段覆盖范围的第一个字段名，
+    /// 默认 length 从其后一个字段开始，checksum 从第一个字段开始
+    #[serde(default)]
+    pub from: Option<String>,
+    /// 覆盖范围的最后一个字段名（包含），
+    /// 默认 length 到最后一个字段，checksum 到其前一个字段
+    #[serde(default)]
+    pub to: Option<String>,
+    /// 加到 length 计算结果上的修正值，如长度需要包含自身时为字段字节数
+    #[serde(default)]
+    pub adjust: i64,
+    /// checksum 字段的算法，取值同 [`checksum::ChecksumSpec::algorithm`]
+    #[serde(default)]
+    pub algorithm: Option<String>,
+}
+
+/// 渲染后的字段，供前端对照显示
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RenderedField {
+    pub name: String,
+    pub offset: usize,
+    pub size: usize,
+    pub hex: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RenderedTemplate {
+    pub hex: String,
+    pub size: usize,
+    pub fields: Vec<RenderedField>,
+}
+
+impl TemplateField {
+    fn little_endian(&self) -> Result<bool, String> {
+        match self.endianness.as_deref() {
+            None | Some("big") => Ok(false),
+            Some("little") => Ok(true),
+            Some(other) => Err(format!("Unsupported endianness: {}", other)),
+        }
+    }
+
+    /// 整数类型的 (字节数, 是否有符号)
+    fn integer(&self) -> Option<(usize, bool)> {
+        match self.field_type.as_str() {
+            "u8" => Some((1, false)),
+            "u16" => Some((2, false)),
+            "u32" => Some((4, false)),
+            "i8" => Some((1, true)),
+            "i16" => Some((2, true)),
+            "i32" => Some((4, true)),
+            _ => None,
+        }
+    }
+
+    fn length_size(&self) -> Result<usize, String> {
+        match self.size.unwrap_or(1) {
+            size @ (1 | 2 | 4) => Ok(size),
+            other => Err(format!("Field {}: length size must be 1, 2 or 4, got {}", self.name, other)),
+        }
+    }
+
+    fn algorithm(&self) -> Result<&str, String> {
+        self.algorithm
+            .as_deref()
+            .ok_or_else(|| format!("Field {}: checksum algorithm is required", self.name))
+    }
+}
+
+/// 解析整数值，支持负数、十进制和 `0x` 十六进制
+fn parse_integer(input: &str) -> Result<i128, String> {
+    let input = input.trim();
+    let (negative, digits) = match input.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, input.strip_prefix('+').unwrap_or(input)),
+    };
+    let (radix, digits) = match digits.strip_prefix("0x").or_else(|| digits.strip_prefix("0X")) {
+        Some(hex) => (16, hex),
+        None => (10, digits),
+    };
+    // 只允许一个前导符号，from_str_radix 自身还会再接受一个
+    if digits.starts_with(['+', '-']) {
+        return Err(format!("Invalid integer: {}", input));
+    }
+    let value = i128::from_str_radix(digits, radix).map_err(|_| format!("Invalid integer: {}", input))?;
+    Ok(if negative { -value } else { value })
+}
+
+/// 把整数按字节数和字节序编码，超出范围时返回错误
+fn encode_integer(value: i128, size: usize, signed: bool, little_endian: bool) -> Result<Vec<u8>, String> {
+    let bits = size as u32 * 8;
+    let (min, max) = if signed {
+        (-(1i128 << (bits - 1)), (1i128 << (bits - 1)) - 1)
+    } else {
+        (0, (1i128 << bits) - 1)
+    };
+    if value < min || value > max {
+        return Err(format!("Value {} is out of range {}..={}", value, min, max));
+    }
+    let bytes = value.to_le_bytes()[..size].to_vec();
+    Ok(if little_endian { bytes } else { bytes.into_iter().rev().collect() })
+}
+
+fn fixed_size(field: &TemplateField, mut bytes: Vec<u8>) -> Result<Vec<u8>, String> {
+    if let Some(size) = field.size {
+        if bytes.len() > size {
+            return Err(format!("Field {}: {} bytes exceed the fixed size of {}", field.name, bytes.len(), size));
+        }
+        bytes.resize(size, 0);
+    }
+    Ok(bytes)
+}
+
+/// 字段名对应的下标
+fn field_index(template: &MessageTemplate, name: &str) -> Result<usize, String> {
+    template
+        .fields
+        .iter()
+        .position(|field| field.name == name)
+        .ok_or_else(|| format!("Unknown field: {}", name))
+}
+
+/// length/checksum 字段覆盖的字段下标范围（包含两端）
+fn field_range(template: &MessageTemplate, index: usize) -> Result<(usize, usize), String> {
+    let field = &template.fields[index];
+    let (default_from, default_to) = match field.field_type.as_str() {
+        "length" => (index + 1, template.fields.len().saturating_sub(1)),
+        _ => (0, index.saturating_sub(1)),
+    };
+    let from = match &field.from {
+        Some(name) => field_index(template, name)?,
+        None => default_from,
+    };
+    let to = match &field.to {
+        Some(name) => field_index(template, name)?,
+        None => default_to,
+    };
+    if from > to || to >= template.fields.len() {
+        return Err(format!("Field {} covers an empty range", field.name));
+    }
+    if field.field_type == "checksum" && to >= index {
+        return Err(format!("Checksum field {} must come after the fields it covers", field.name));
+    }
+    Ok((from, to))
+}
+
+/// 检查模板结构，不要求变量已有取值
+pub(crate) fn validate(template: &MessageTemplate) -> Result<(), String> {
+    if template.name.trim().is_empty() {
+        return Err("Template name is required".to_string());
+    }
+    if template.fields.is_empty() {
+        return Err("Template has no fields".to_string());
+    }
+    for (index, field) in template.fields.iter().enumerate() {
+        if template.fields[..index].iter().any(|other| other.name == field.name) {
+            return Err(format!("Duplicate field name: {}", field.name));
+        }
+        field.little_endian()?;
+        match field.field_type.as_str() {
+            "string" | "hex" => {}
+            "length" => {
+                field.length_size()?;
+                field_range(template, index)?;
+            }
+            "checksum" => {
+                checksum::width(field.algorithm()?)?;
+                field_range(template, index)?;
+            }
+            _ if field.integer().is_some() => {}
+            other => return Err(format!("Field {}: unsupported type {}", field.name, other)),
+        }
+    }
+    Ok(())
+}
+
+/// 用变量填充模板并逐字段编码：先编码普通字段，再计算 length 字段，最后按顺序计算 checksum 字段
+fn encode_fields(template: &MessageTemplate, variables: &HashMap<String, String>) -> Result<Vec<Vec<u8>>, String> {
+    validate(template)?;
+
+    let mut parts: Vec<Vec<u8>> = Vec::with_capacity(template.fields.len());
+    for field in &template.fields {
+        let value = field
+            .variable
+            .as_ref()
+            .and_then(|variable| variables.get(variable))
+            .or(field.value.as_ref());
+        let require = || {
+            value.ok_or_else(|| match &field.variable {
+                Some(variable) => format!("Missing value for variable {}", variable),
+                None => format!("Field {} has no value", field.name),
+            })
+        };
+        let bytes = match field.field_type.as_str() {
+            "string" => fixed_size(field, require()?.as_bytes().to_vec())?,
+            "hex" => fixed_size(field, payload::decode_hex(require()?)?)?,
+            "length" => vec![0; field.length_size()?],
+            "checksum" => vec![0; checksum::width(field.algorithm()?)?],
+            _ => {
+                let (size, signed) = field.integer().unwrap_or((1, false));
+                encode_integer(parse_integer(require()?)?, size, signed, field.little_endian()?)
+                    .map_err(|e| format!("Field {}: {}", field.name, e))?
+            }
+        };
+        parts.push(bytes);
+    }
+
+    for (index, field) in template.fields.iter().enumerate().filter(|(_, f)| f.field_type == "length") {
+        let (from, to) = field_range(template, index)?;
+        let length = parts[from..=to].iter().map(Vec::len).sum::<usize>() as i128 + i128::from(field.adjust);
+        parts[index] = encode_integer(length, field.length_size()?, false, field.little_endian()?)
+            .map_err(|e| format!("Field {}: {}", field.name, e))?;
+    }
+
+    for (index, field) in template.fields.iter().enumerate().filter(|(_, f)| f.field_type == "checksum") {
+        let (from, to) = field_range(template, index)?;
+        let covered = parts[from..=to].concat();
+        parts[index] = checksum::compute_with_endianness(field.algorithm()?, &covered, field.endianness.as_deref())?;
+    }
+    Ok(parts)
+}
+
+fn describe(template: &MessageTemplate, parts: &[Vec<u8>]) -> RenderedTemplate {
+    let mut offset = 0;
+    let fields = template
+        .fields
+        .iter()
+        .zip(parts)
+        .map(|(field, bytes)| {
+            let rendered = RenderedField {
+                name: field.name.clone(),
+                offset,
+                size: bytes.len(),
+                hex: payload::to_hex(bytes),
+            };
+            offset += bytes.len();
+            rendered
+        })
+        .collect();
+    let data = parts.concat();
+    RenderedTemplate {
+        hex: payload::to_hex(&data),
+        size: data.len(),
+        fields,
+    }
+}
+
+/// 渲染模板，返回完整报文和各字段的位置
+pub fn render(template: &MessageTemplate, variables: &HashMap<String, String>) -> Result<RenderedTemplate, String> {
+    let parts = encode_fields(template, variables)?;
+    Ok(describe(template, &parts))
+}
+
+#[tauri::command]
+pub(crate) async fn template_get_all<R: Runtime>(app: AppHandle<R>) -> Result<Vec<MessageTemplate>, String> {
+    let state = app.state::<TcpToolState>();
+    let templates = state.config.read().await.templates.clone();
+    Ok(templates)
+}
+
+/// 新增或更新模板（按 id），id 为空时自动生成
+#[tauri::command]
+pub(crate) async fn template_save<R: Runtime>(app: AppHandle<R>, mut template: MessageTemplate) -> Result<MessageTemplate, String> {
+    validate(&template)?;
+    if template.id.is_empty() {
+        template.id = format!("template_{}", uuid::Uuid::new_v4().simple());
+    }
+
+    let state = app.state::<TcpToolState>();
+    {
+        let mut config = state.config.write().await;
+        match config.templates.iter_mut().find(|t| t.id == template.id) {
+            Some(existing) => *existing = template.clone(),
+            None => config.templates.push(template.clone()),
+        }
+    }
+    save_config(&app).await?;
+
+    info!("Message template {} saved", template.id);
+    Ok(template)
+}
+
+#[tauri::command]
+pub(crate) async fn template_remove<R: Runtime>(app: AppHandle<R>, template_id: String) -> Result<(), String> {
+    let state = app.state::<TcpToolState>();
+    state.config.write().await.templates.retain(|t| t.id != template_id);
+    save_config(&app).await?;
+
+    info!("Message template {} removed", template_id);
+    Ok(())
+}
+
+/// 预览模板渲染结果，模板可以尚未保存
+#[tauri::command]
+pub(crate) async fn template_render<R: Runtime>(
+    _app: AppHandle<R>,
+    template: MessageTemplate,
+    variables: Option<HashMap<String, String>>,
+) -> Result<RenderedTemplate, String> {
+    render(&template, &variables.unwrap_or_default())
+}
+
+/// 用表单填写的变量渲染已保存的模板并发送，TCP、UDP 和 WebSocket 连接均可使用
+#[tauri::command]
+pub(crate) async fn tcp_send_template<R: Runtime>(
+    app: AppHandle<R>,
+    connection_id: String,
+    template_id: String,
+    variables: Option<HashMap<String, String>>,
+) -> Result<RenderedTemplate, String> {
+    let template = {
+        let state = app.state::<TcpToolState>();
+        let config = state.config.read().await;
+        config
+            .templates
+            .iter()
+            .find(|t| t.id == template_id)
+            .cloned()
+            .ok_or_else(|| format!("Template not found: {}", template_id))?
+    };
+    let parts = encode_fields(&template, &variables.unwrap_or_default())?;
+    let rendered = describe(&template, &parts);
+    send_payload(&app, &connection_id, parts.concat()).await?;
+
+    info!("Template {} sent to {}: {}", template.name, connection_id, rendered.hex);
+    Ok(rendered)
+}
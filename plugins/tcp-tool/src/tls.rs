@@ -0,0 +1,195 @@
+use chrono::{TimeZone, Utc};
+use network_common::tls::{load_certs, load_key, NoVerification};
+use rustls::crypto::{ring, CryptoProvider};
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName};
+use rustls::{ClientConfig, CommonState, RootCertStore, ServerConfig};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::net::TcpStream;
+use tokio_rustls::{client, server, TlsAcceptor, TlsConnector};
+use x509_parser::prelude::{FromDer, X509Certificate};
+
+/// TLS 客户端选项
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TlsClientOptions {
+    /// SNI 服务器名称，为空时使用连接的主机名
+    pub server_name: Option<String>,
+    /// 自定义 CA 证书（PEM），为空时使用内置的根证书
+    pub ca_file: Option<String>,
+    /// 跳过服务端证书校验，仅用于测试
+    pub insecure: bool,
+    pub client_cert_file: Option<String>,
+    pub client_key_file: Option<String>,
+    pub alpn_protocols: Vec<String>,
+}
+
+/// TLS 服务端选项，未提供证书文件时生成自签名证书
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TlsServerOptions {
+    pub cert_file: Option<String>,
+    pub key_file: Option<String>,
+    /// 自签名证书包含的主机名，默认为 localhost 和回环地址
+    pub self_signed_names: Vec<String>,
+    pub alpn_protocols: Vec<String>,
+}
+
+/// 握手完成后协商的会话信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TlsInfo {
+    pub protocol_version: String,
+    pub cipher_suite: String,
+    pub alpn_protocol: Option<String>,
+    pub peer_certificates: Vec<PeerCertificate>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeerCertificate {
+    pub subject: String,
+    pub issuer: String,
+    pub serial: String,
+    pub not_before: String,
+    pub not_after: String,
+}
+
+fn provider() -> Arc<CryptoProvider> {
+    Arc::new(ring::default_provider())
+}
+
+/// 根据选项构造 TLS 连接器，重连时复用
+#[derive(Clone)]
+pub struct TlsClient {
+    connector: TlsConnector,
+    server_name: Option<String>,
+}
+
+impl TlsClient {
+    pub fn new(options: &TlsClientOptions) -> Result<Self, String> {
+        let provider = provider();
+        let builder = ClientConfig::builder_with_provider(provider.clone())
+            .with_safe_default_protocol_versions()
+            .map_err(|e| format!("Invalid TLS configuration: {}", e))?;
+
+        let builder = if options.insecure {
+            builder
+                .dangerous()
+                .with_custom_certificate_verifier(Arc::new(NoVerification(provider)))
+        } else {
+            let mut roots = RootCertStore::empty();
+            match &options.ca_file {
+                Some(path) => {
+                    for cert in load_certs(path)? {
+                        roots
+                            .add(cert)
+                            .map_err(|e| format!("Invalid CA certificate in {}: {}", path, e))?;
+                    }
+                }
+                None => roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned()),
+            }
+            builder.with_root_certificates(roots)
+        };
+
+        let mut config = match (&options.client_cert_file, &options.client_key_file) {
+            (Some(cert), Some(key)) => builder
+                .with_client_auth_cert(load_certs(cert)?, load_key(key)?)
+                .map_err(|e| format!("Invalid client certificate: {}", e))?,
+            (None, None) => builder.with_no_client_auth(),
+            _ => return Err("Client certificate and key must be provided together".to_string()),
+        };
+        config.alpn_protocols = options.alpn_protocols.iter().map(|p| p.as_bytes().to_vec()).collect();
+
+        Ok(Self {
+            connector: TlsConnector::from(Arc::new(config)),
+            server_name: options.server_name.clone(),
+        })
+    }
+
+    pub async fn connect(&self, host: &str, stream: TcpStream) -> Result<client::TlsStream<TcpStream>, String> {
+        let name = self.server_name.as_deref().unwrap_or(host);
+        let server_name = ServerName::try_from(name.to_string())
+            .map_err(|e| format!("Invalid server name {}: {}", name, e))?;
+        self.connector
+            .connect(server_name, stream)
+            .await
+            .map_err(|e| format!("TLS handshake failed: {}", e))
+    }
+}
+
+pub fn acceptor(options: &TlsServerOptions) -> Result<TlsAcceptor, String> {
+    let (certs, key) = match (&options.cert_file, &options.key_file) {
+        (Some(cert), Some(key)) => (load_certs(cert)?, load_key(key)?),
+        (None, None) => self_signed(&options.self_signed_names)?,
+        _ => return Err("Server certificate and key must be provided together".to_string()),
+    };
+
+    let mut config = ServerConfig::builder_with_provider(provider())
+        .with_safe_default_protocol_versions()
+        .map_err(|e| format!("Invalid TLS configuration: {}", e))?
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .map_err(|e| format!("Invalid server certificate: {}", e))?;
+    config.alpn_protocols = options.alpn_protocols.iter().map(|p| p.as_bytes().to_vec()).collect();
+
+    Ok(TlsAcceptor::from(Arc::new(config)))
+}
+
+fn self_signed(names: &[String]) -> Result<(Vec<CertificateDer<'static>>, PrivateKeyDer<'static>), String> {
+    let names = if names.is_empty() {
+        vec!["localhost".to_string(), "127.0.0.1".to_string(), "::1".to_string()]
+    } else {
+        names.to_vec()
+    };
+    let certified = rcgen::generate_simple_self_signed(names)
+        .map_err(|e| format!("Failed to generate self-signed certificate: {}", e))?;
+    let key = PrivateKeyDer::try_from(certified.key_pair.serialize_der())
+        .map_err(|e| format!("Failed to encode private key: {}", e))?;
+    Ok((vec![certified.cert.der().clone()], key))
+}
+
+fn describe_certificate(der: &CertificateDer<'_>) -> Option<PeerCertificate> {
+    let (_, cert) = X509Certificate::from_der(der.as_ref()).ok()?;
+    let format_time = |timestamp: i64| {
+        Utc.timestamp_opt(timestamp, 0)
+            .single()
+            .map(|t| t.to_rfc3339())
+            .unwrap_or_default()
+    };
+    Some(PeerCertificate {
+        subject: cert.subject().to_string(),
+        issuer: cert.issuer().to_string(),
+        serial: cert.raw_serial_as_string(),
+        not_before: format_time(cert.validity().not_before.timestamp()),
+        not_after: format_time(cert.validity().not_after.timestamp()),
+    })
+}
+
+fn session_info(state: &CommonState) -> TlsInfo {
+    TlsInfo {
+        protocol_version: state
+            .protocol_version()
+            .map(|v| format!("{:?}", v))
+            .unwrap_or_default(),
+        cipher_suite: state
+            .negotiated_cipher_suite()
+            .map(|s| format!("{:?}", s.suite()))
+            .unwrap_or_default(),
+        alpn_protocol: state
+            .alpn_protocol()
+            .map(|p| String::from_utf8_lossy(p).into_owned()),
+        peer_certificates: state
+            .peer_certificates()
+            .unwrap_or_default()
+            .iter()
+            .filter_map(describe_certificate)
+            .collect(),
+    }
+}
+
+pub fn client_info(stream: &client::TlsStream<TcpStream>) -> TlsInfo {
+    session_info(stream.get_ref().1)
+}
+
+pub fn server_info(stream: &server::TlsStream<TcpStream>) -> TlsInfo {
+    session_info(stream.get_ref().1)
+}
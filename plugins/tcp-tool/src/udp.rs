@@ -0,0 +1,244 @@
+use socket2::{Domain, Protocol, Socket, Type};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use tokio::net::UdpSocket;
+use tracing::{error, info, warn};
+
+use crate::{publish_message, socket, ConnectionInfo, MessageData, TcpToolState};
+
+/// 已绑定的 UDP 套接字及其默认发送目标
+pub struct UdpEndpoint {
+    socket: Arc<UdpSocket>,
+    target: Option<SocketAddr>,
+}
+
+/// 绑定 UDP 套接字；开启地址复用以便多个进程加入同一组播端口
+fn bind_udp(addr: SocketAddr, broadcast: bool) -> std::io::Result<UdpSocket> {
+    let socket = Socket::new(Domain::for_address(addr), Type::DGRAM, Some(Protocol::UDP))?;
+    socket.set_reuse_address(true)?;
+    socket.set_broadcast(broadcast)?;
+    socket.set_nonblocking(true)?;
+    socket.bind(&addr.into())?;
+    UdpSocket::from_std(socket.into())
+}
+
+async fn resolve_target(host: &str, port: u16) -> Result<SocketAddr, String> {
+    tokio::net::lookup_host((host, port))
+        .await
+        .map_err(|e| format!("Failed to resolve {}:{}: {}", host, port, e))?
+        .next()
+        .ok_or_else(|| format!("No address found for {}:{}", host, port))
+}
+
+#[tauri::command]
+pub(crate) async fn udp_bind<R: Runtime>(
+    app: AppHandle<R>,
+    port: u16,
+    bind_address: Option<String>,
+    target_host: Option<String>,
+    target_port: Option<u16>,
+    broadcast: Option<bool>,
+) -> Result<String, String> {
+    let state = app.state::<TcpToolState>();
+    let bind_address = match bind_address {
+        Some(address) => address,
+        None => state.config.read().await.default_bind_address.clone(),
+    };
+    let addr = socket::parse_bind_addr(&bind_address, port)?;
+    let target = match (target_host, target_port) {
+        (Some(host), Some(port)) => Some(resolve_target(&host, port).await?),
+        _ => None,
+    };
+
+    info!("Binding UDP socket on {}", addr);
+
+    let udp = bind_udp(addr, broadcast.unwrap_or(false)).map_err(|e| {
+        error!("Failed to bind UDP socket on {}: {}", addr, e);
+        format!("UDP bind failed: {}", e)
+    })?;
+    let local_addr = udp.local_addr().map_err(|e| e.to_string())?;
+    let connection_id = format!("udp_{}_{}", local_addr.ip(), local_addr.port());
+    let udp = Arc::new(udp);
+
+    let connection_info = ConnectionInfo::new(
+        &connection_id,
+        "udp",
+        local_addr.to_string(),
+        target.map(|t| t.to_string()),
+        "bound",
+    );
+
+    {
+        // SO_REUSEADDR 允许重复绑定同一地址，此时连接 ID 相同，拒绝而不是覆盖已有的套接字
+        let mut sockets = state.udp_sockets.write().await;
+        if sockets.contains_key(&connection_id) {
+            return Err(format!("UDP socket {} is already bound", local_addr));
+        }
+        sockets.insert(connection_id.clone(), UdpEndpoint { socket: udp.clone(), target });
+    }
+    state.connection_info.write().await.insert(connection_id.clone(), connection_info.clone());
+
+    let task = tokio::spawn(recv_loop(app.clone(), connection_id.clone(), udp));
+    state.tasks.write().await.insert(connection_id.clone(), task);
+
+    app.emit("tcp_connection_established", &connection_info).map_err(|e| e.to_string())?;
+
+    info!("UDP socket bound on {}", local_addr);
+    Ok(connection_id)
+}
+
+/// 连续接收出错时的最长等待间隔
+const MAX_RECV_ERROR_DELAY: Duration = Duration::from_secs(5);
+
+/// 接收任意来源的报文，并在消息中附带来源地址
+async fn recv_loop<R: Runtime>(app: AppHandle<R>, connection_id: String, udp: Arc<UdpSocket>) {
+    let mut buf = vec![0u8; 65535];
+    let mut error_delay = Duration::ZERO;
+
+    loop {
+        match udp.recv_from(&mut buf).await {
+            Ok((n, peer)) => {
+                error_delay = Duration::ZERO;
+                let message_data = MessageData::new(&connection_id, &buf[..n], "received", Some(peer.to_string()));
+                publish_message(&app, &message_data, &buf[..n]).await;
+            }
+            // Windows 上对端端口不可达会以 recv 错误的形式返回，不应结束接收
+            Err(e) if e.kind() == std::io::ErrorKind::ConnectionReset => {}
+            Err(e) => {
+                // 其他错误通常会持续出现，逐步拉长重试间隔，避免空转和刷屏
+                error_delay = (error_delay * 2).clamp(Duration::from_millis(100), MAX_RECV_ERROR_DELAY);
+                warn!("UDP socket {} receive error, retrying in {}ms: {}", connection_id, error_delay.as_millis(), e);
+                tokio::time::sleep(error_delay).await;
+            }
+        }
+    }
+}
+
+/// 发送一个报文，`target` 为空时使用绑定时的默认目标
+pub(crate) async fn send_to<R: Runtime>(
+    app: &AppHandle<R>,
+    connection_id: &str,
+    data: &[u8],
+    target: Option<SocketAddr>,
+) -> Result<(), String> {
+    let state = app.state::<TcpToolState>();
+    let (udp, target) = {
+        let sockets = state.udp_sockets.read().await;
+        let endpoint = sockets
+            .get(connection_id)
+            .ok_or_else(|| "Connection not found".to_string())?;
+        let target = target
+            .or(endpoint.target)
+            .ok_or_else(|| "No target address for UDP socket".to_string())?;
+        (endpoint.socket.clone(), target)
+    };
+
+    let n = udp.send_to(data, target).await.map_err(|e| {
+        error!("Failed to send datagram from {} to {}: {}", connection_id, target, e);
+        format!("Send failed: {}", e)
+    })?;
+
+    let message_data = MessageData::new(connection_id, &data[..n], "sent", Some(target.to_string()));
+    publish_message(app, &message_data, &data[..n]).await;
+
+    info!("Datagram sent from {} to {} ({} bytes)", connection_id, target, n);
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) async fn udp_send<R: Runtime>(
+    app: AppHandle<R>,
+    connection_id: String,
+    message: String,
+    target_host: Option<String>,
+    target_port: Option<u16>,
+) -> Result<(), String> {
+    let state = app.state::<TcpToolState>();
+    let target = match (target_host, target_port) {
+        (Some(host), Some(port)) => Some(resolve_target(&host, port).await?),
+        _ => None,
+    };
+    // 与其他发送路径一样附加帧尾校验
+    let data = state.checksums.append(&connection_id, None, message.into_bytes()).await?;
+    send_to(&app, &connection_id, &data, target).await
+}
+
+async fn endpoint_socket(state: &TcpToolState, connection_id: &str) -> Result<Arc<UdpSocket>, String> {
+    state
+        .udp_sockets
+        .read()
+        .await
+        .get(connection_id)
+        .map(|endpoint| endpoint.socket.clone())
+        .ok_or_else(|| "Connection not found".to_string())
+}
+
+/// 加入或退出组播组：IPv4 使用网卡地址指定网卡，IPv6 使用网卡索引
+fn set_membership(udp: &UdpSocket, group: &str, interface: Option<&str>, join: bool) -> Result<IpAddr, String> {
+    let group: IpAddr = group
+        .parse()
+        .map_err(|_| format!("Invalid multicast group: {}", group))?;
+    if !group.is_multicast() {
+        return Err(format!("{} is not a multicast address", group));
+    }
+    let interface = interface.map(str::trim).filter(|i| !i.is_empty());
+
+    let result = match group {
+        IpAddr::V4(group) => {
+            let interface = match interface {
+                Some(i) => i.parse().map_err(|_| format!("Invalid interface address: {}", i))?,
+                None => Ipv4Addr::UNSPECIFIED,
+            };
+            if join {
+                udp.join_multicast_v4(group, interface)
+            } else {
+                udp.leave_multicast_v4(group, interface)
+            }
+        }
+        IpAddr::V6(group) => {
+            let index = match interface {
+                Some(i) => i.parse().map_err(|_| format!("Invalid interface index: {}", i))?,
+                None => 0,
+            };
+            if join {
+                udp.join_multicast_v6(&group, index)
+            } else {
+                udp.leave_multicast_v6(&group, index)
+            }
+        }
+    };
+    result.map_err(|e| format!("Multicast membership change for {} failed: {}", group, e))?;
+    Ok(group)
+}
+
+#[tauri::command]
+pub(crate) async fn udp_join_multicast<R: Runtime>(
+    app: AppHandle<R>,
+    connection_id: String,
+    group: String,
+    interface: Option<String>,
+) -> Result<(), String> {
+    let state = app.state::<TcpToolState>();
+    let udp = endpoint_socket(&state, &connection_id).await?;
+    let group = set_membership(&udp, &group, interface.as_deref(), true)?;
+
+    info!("UDP socket {} joined multicast group {}", connection_id, group);
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) async fn udp_leave_multicast<R: Runtime>(
+    app: AppHandle<R>,
+    connection_id: String,
+    group: String,
+    interface: Option<String>,
+) -> Result<(), String> {
+    let state = app.state::<TcpToolState>();
+    let udp = endpoint_socket(&state, &connection_id).await?;
+    let group = set_membership(&udp, &group, interface.as_deref(), false)?;
+
+    info!("UDP socket {} left multicast group {}", connection_id, group);
+    Ok(())
+}
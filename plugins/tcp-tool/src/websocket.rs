@@ -0,0 +1,461 @@
+use futures_util::{SinkExt, StreamExt};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{mpsc, oneshot};
+use tokio_rustls::TlsAcceptor;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tokio_tungstenite::tungstenite::http::{HeaderMap, HeaderName, HeaderValue};
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::WebSocketStream;
+use tracing::{error, info, warn};
+use network_common::payload;
+
+use crate::tls::{self, TlsClient};
+use crate::{
+    emit_event, publish_message, socket, ConnectOptions, ConnectionInfo, MessageData, SocketOptions, TcpToolState,
+    TlsClientOptions, TlsServerOptions,
+};
+
+type Outgoing = (Message, oneshot::Sender<Result<(), String>>);
+
+/// WebSocket 会话的发送通道，帧由会话任务统一写出
+pub struct WsHandle {
+    tx: mpsc::UnboundedSender<Outgoing>,
+}
+
+fn frame_type(message: &Message) -> &'static str {
+    match message {
+        Message::Text(_) => "text",
+        Message::Binary(_) => "binary",
+        Message::Ping(_) => "ping",
+        Message::Pong(_) => "pong",
+        Message::Close(_) => "close",
+        Message::Frame(_) => "frame",
+    }
+}
+
+/// 帧的负载；关闭帧以 "关闭码 原因" 的文本形式表示
+fn frame_payload(message: &Message) -> Vec<u8> {
+    match message {
+        Message::Close(Some(frame)) => format!("{} {}", u16::from(frame.code), frame.reason)
+            .trim_end()
+            .as_bytes()
+            .to_vec(),
+        Message::Close(None) => Vec::new(),
+        other => other.clone().into_data(),
+    }
+}
+
+/// 上报一个帧并更新字节计数
+async fn publish_frame<R: Runtime>(app: &AppHandle<R>, connection_id: &str, message: &Message, direction: &str) {
+    let data = frame_payload(message);
+    let mut message_data = MessageData::new(connection_id, &data, direction, None);
+    message_data.frame_type = Some(frame_type(message).to_string());
+    publish_message(app, &message_data, &data).await;
+}
+
+/// 构造待发送的帧，二进制、ping 和 pong 帧的内容按 `encoding` 解码
+fn build_frame(message: &str, frame_type: &str, encoding: Option<&str>) -> Result<Message, String> {
+    match frame_type {
+        "text" => Ok(Message::Text(message.to_string())),
+        "binary" => Ok(Message::Binary(payload::decode(message, encoding)?)),
+        "ping" => Ok(Message::Ping(payload::decode(message, encoding)?)),
+        "pong" => Ok(Message::Pong(payload::decode(message, encoding)?)),
+        other => Err(format!("Unsupported frame type: {}", other)),
+    }
+}
+
+/// 会话任务：转发收到的帧，写出发送通道中的帧；`echo` 为 true 时回显文本和二进制帧
+async fn run_session<R, S>(
+    app: AppHandle<R>,
+    connection_id: String,
+    ws: WebSocketStream<S>,
+    mut rx: mpsc::UnboundedReceiver<Outgoing>,
+    echo: bool,
+) where
+    R: Runtime,
+    S: AsyncRead + AsyncWrite + Send + Unpin,
+{
+    let state = app.state::<TcpToolState>();
+    let (mut sink, mut stream) = ws.split();
+
+    loop {
+        tokio::select! {
+            frame = stream.next() => match frame {
+                Some(Ok(message)) => {
+                    publish_frame(&app, &connection_id, &message, "received").await;
+
+                    if echo && (message.is_text() || message.is_binary()) {
+                        if let Err(e) = sink.send(message.clone()).await {
+                            warn!("WebSocket {} echo failed: {}", connection_id, e);
+                            break;
+                        }
+                        publish_frame(&app, &connection_id, &message, "sent").await;
+                    }
+                }
+                Some(Err(e)) => {
+                    warn!("WebSocket {} read error: {}", connection_id, e);
+                    break;
+                }
+                None => break,
+            },
+            outgoing = rx.recv() => match outgoing {
+                Some((message, reply)) => {
+                    let result = sink.send(message.clone()).await.map_err(|e| format!("Send failed: {}", e));
+                    if result.is_ok() {
+                        publish_frame(&app, &connection_id, &message, "sent").await;
+                    }
+                    let _ = reply.send(result);
+                }
+                None => break,
+            },
+        }
+    }
+
+    state.websockets.write().await.remove(&connection_id);
+    state.tasks.write().await.remove(&connection_id);
+    if let Some(info) = state.disconnected(&app, &connection_id).await {
+        emit_event(&app, "tcp_connection_closed", &info);
+    }
+    info!("WebSocket {} closed", connection_id);
+}
+
+/// 登记会话并启动会话任务
+async fn start_session<R, S>(
+    app: &AppHandle<R>,
+    mut connection_info: ConnectionInfo,
+    ws: WebSocketStream<S>,
+    subprotocol: Option<String>,
+    echo: bool,
+) -> ConnectionInfo
+where
+    R: Runtime,
+    S: AsyncRead + AsyncWrite + Send + Unpin + 'static,
+{
+    let state = app.state::<TcpToolState>();
+    let connection_id = connection_info.id.clone();
+    connection_info.subprotocol = subprotocol;
+
+    let (tx, rx) = mpsc::unbounded_channel();
+    state.websockets.write().await.insert(connection_id.clone(), WsHandle { tx });
+    state.connection_info.write().await.insert(connection_id.clone(), connection_info.clone());
+
+    let task = tokio::spawn(run_session(app.clone(), connection_id.clone(), ws, rx, echo));
+    state.tasks.write().await.insert(connection_id, task);
+    connection_info
+}
+
+fn negotiated_subprotocol(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get("Sec-WebSocket-Protocol")
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.to_string())
+}
+
+#[tauri::command]
+pub(crate) async fn ws_connect<R: Runtime>(
+    app: AppHandle<R>,
+    url: String,
+    headers: Option<HashMap<String, String>>,
+    subprotocols: Option<Vec<String>>,
+    options: Option<ConnectOptions>,
+    tls: Option<TlsClientOptions>,
+) -> Result<String, String> {
+    let state = app.state::<TcpToolState>();
+    let use_proxy = options.as_ref().and_then(|options| options.proxy);
+    let mut options = SocketOptions::resolve(&*state.config.read().await, options);
+
+    let mut request = url
+        .as_str()
+        .into_client_request()
+        .map_err(|e| format!("Invalid WebSocket URL: {}", e))?;
+    for (name, value) in headers.unwrap_or_default() {
+        let name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|e| format!("Invalid header name {}: {}", name, e))?;
+        let value = HeaderValue::from_str(&value).map_err(|e| format!("Invalid header value: {}", e))?;
+        request.headers_mut().insert(name, value);
+    }
+    if let Some(protocols) = subprotocols.filter(|p| !p.is_empty()) {
+        let value = HeaderValue::from_str(&protocols.join(", "))
+            .map_err(|e| format!("Invalid subprotocol: {}", e))?;
+        request.headers_mut().insert("Sec-WebSocket-Protocol", value);
+    }
+
+    let secure = request.uri().scheme_str() == Some("wss");
+    let host = request
+        .uri()
+        .host()
+        .ok_or_else(|| "WebSocket URL has no host".to_string())?
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .to_string();
+    let port = request.uri().port_u16().unwrap_or(if secure { 443 } else { 80 });
+    let connection_id = format!("ws_{}", url);
+    options.proxy = network_proxy::for_host(&app, &host, use_proxy).await;
+
+    info!("Opening WebSocket connection to {}", url);
+
+    if let Some(task) = state.tasks.write().await.remove(&connection_id) {
+        task.abort();
+    }
+
+    let stream = socket::connect(&host, port, &options).await.map_err(|e| {
+        error!("Failed to connect to {}: {}", url, e);
+        e
+    })?;
+    let local_addr = stream.local_addr().map_err(|e| e.to_string())?.to_string();
+    let remote_addr = socket::peer_addr(&stream, &host, port, &options)?;
+    let mut connection_info =
+        ConnectionInfo::new(&connection_id, "websocket", local_addr, Some(remote_addr), "connected");
+
+    let handshake_failed = |e| format!("WebSocket handshake failed: {}", e);
+    let handshake_timed_out = "WebSocket handshake timed out";
+    let connection_info = if secure {
+        let tls = TlsClient::new(&tls.unwrap_or_default())?;
+        let stream = tokio::time::timeout(options.connection_timeout, tls.connect(&host, stream))
+            .await
+            .map_err(|_| "TLS handshake timed out".to_string())??;
+        connection_info.tls = Some(tls::client_info(&stream));
+        let handshake = tokio_tungstenite::client_async(request, stream);
+        let (ws, response) = tokio::time::timeout(options.connection_timeout, handshake)
+            .await
+            .map_err(|_| handshake_timed_out.to_string())?
+            .map_err(handshake_failed)?;
+        start_session(&app, connection_info, ws, negotiated_subprotocol(response.headers()), false).await
+    } else {
+        let handshake = tokio_tungstenite::client_async(request, stream);
+        let (ws, response) = tokio::time::timeout(options.connection_timeout, handshake)
+            .await
+            .map_err(|_| handshake_timed_out.to_string())?
+            .map_err(handshake_failed)?;
+        start_session(&app, connection_info, ws, negotiated_subprotocol(response.headers()), false).await
+    };
+
+    app.emit("tcp_connection_established", &connection_info).map_err(|e| e.to_string())?;
+
+    info!("WebSocket connected to {}", url);
+    Ok(connection_id)
+}
+
+/// 数据是合法 UTF-8 时作为文本帧，否则作为二进制帧
+pub(crate) fn data_frame(data: Vec<u8>) -> Message {
+    match String::from_utf8(data) {
+        Ok(text) => Message::Text(text),
+        Err(e) => Message::Binary(e.into_bytes()),
+    }
+}
+
+/// 将帧交给会话任务发送并等待写出结果
+pub(crate) async fn send_frame(state: &TcpToolState, connection_id: &str, message: Message) -> Result<(), String> {
+    let tx = state
+        .websockets
+        .read()
+        .await
+        .get(connection_id)
+        .map(|handle| handle.tx.clone())
+        .ok_or_else(|| "Connection not found".to_string())?;
+    let (reply_tx, reply_rx) = oneshot::channel();
+    tx.send((message, reply_tx))
+        .map_err(|_| "WebSocket session has ended".to_string())?;
+    reply_rx
+        .await
+        .map_err(|_| "WebSocket session has ended".to_string())?
+}
+
+#[tauri::command]
+pub(crate) async fn ws_send<R: Runtime>(
+    app: AppHandle<R>,
+    connection_id: String,
+    message: String,
+    frame_type: Option<String>,
+    encoding: Option<String>,
+) -> Result<(), String> {
+    let state = app.state::<TcpToolState>();
+    let frame = build_frame(&message, frame_type.as_deref().unwrap_or("text"), encoding.as_deref())?;
+    // 文本和二进制帧与其他发送路径一样附加帧尾校验，附加后不再是合法 UTF-8 的文本帧改为二进制帧发送
+    let frame = match frame {
+        Message::Text(text) => data_frame(state.with_checksum(&connection_id, text.into_bytes()).await?),
+        Message::Binary(data) => Message::Binary(state.with_checksum(&connection_id, data).await?),
+        other => other,
+    };
+    send_frame(&state, &connection_id, frame).await
+}
+
+/// 发送关闭帧，会话在对端确认后结束
+#[tauri::command]
+pub(crate) async fn ws_close<R: Runtime>(
+    app: AppHandle<R>,
+    connection_id: String,
+    code: Option<u16>,
+    reason: Option<String>,
+) -> Result<(), String> {
+    let state = app.state::<TcpToolState>();
+    let frame = CloseFrame {
+        code: CloseCode::from(code.unwrap_or(1000)),
+        reason: Cow::Owned(reason.unwrap_or_default()),
+    };
+    send_frame(&state, &connection_id, Message::Close(Some(frame))).await
+}
+
+#[tauri::command]
+pub(crate) async fn ws_server_start<R: Runtime>(
+    app: AppHandle<R>,
+    port: u16,
+    bind_address: Option<String>,
+    echo: Option<bool>,
+    subprotocols: Option<Vec<String>>,
+    tls: Option<TlsServerOptions>,
+) -> Result<String, String> {
+    let state = app.state::<TcpToolState>();
+    let acceptor = tls.map(|tls| tls::acceptor(&tls)).transpose()?;
+    let bind_address = match bind_address {
+        Some(address) => address,
+        None => state.config.read().await.default_bind_address.clone(),
+    };
+    let addr = socket::parse_bind_addr(&bind_address, port)?;
+
+    info!("Starting WebSocket server on {}", addr);
+
+    let listener = socket::bind_listener(addr, false).map_err(|e| {
+        error!("Failed to start WebSocket server on {}: {}", addr, e);
+        format!("Server start failed: {}", e)
+    })?;
+    let local_addr = listener.local_addr().map_err(|e| e.to_string())?;
+    let server_id = format!("ws_server_{}_{}", local_addr.ip(), local_addr.port());
+
+    let connection_info = ConnectionInfo::new(&server_id, "websocket_server", local_addr.to_string(), None, "listening");
+    state.connection_info.write().await.insert(server_id.clone(), connection_info.clone());
+
+    let settings = ServerSettings {
+        echo: echo.unwrap_or(true),
+        subprotocols: subprotocols.unwrap_or_default(),
+        acceptor,
+    };
+    let task = tokio::spawn(accept_loop(app.clone(), server_id.clone(), listener, settings));
+    state.tasks.write().await.insert(server_id.clone(), task);
+
+    app.emit("tcp_server_started", &connection_info).map_err(|e| e.to_string())?;
+
+    info!("WebSocket server started on {}", local_addr);
+    Ok(server_id)
+}
+
+#[derive(Clone)]
+struct ServerSettings {
+    echo: bool,
+    subprotocols: Vec<String>,
+    acceptor: Option<TlsAcceptor>,
+}
+
+async fn accept_loop<R: Runtime>(app: AppHandle<R>, server_id: String, listener: TcpListener, settings: ServerSettings) {
+    let state = app.state::<TcpToolState>();
+
+    loop {
+        match listener.accept().await {
+            Ok((stream, peer)) => {
+                let connection_id = format!("{}_{}", server_id, peer);
+                // 握手期间的任务也登记在连接 ID 下，握手完成后由会话任务替换；
+                // 持有写锁直到登记完成，避免握手失败的任务先于登记移除自己
+                let mut tasks = state.tasks.write().await;
+                let task = tokio::spawn(handle_connection(
+                    app.clone(),
+                    server_id.clone(),
+                    connection_id.clone(),
+                    stream,
+                    peer,
+                    settings.clone(),
+                ));
+                tasks.insert(connection_id, task);
+            }
+            Err(e) => {
+                warn!("WebSocket server {} accept error: {}", server_id, e);
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+        }
+    }
+}
+
+/// 从客户端请求的子协议中选出第一个受支持的
+fn choose_subprotocol(request: &Request, supported: &[String]) -> Option<HeaderValue> {
+    let requested = request.headers().get("Sec-WebSocket-Protocol")?.to_str().ok()?;
+    requested
+        .split(',')
+        .map(str::trim)
+        .find(|p| supported.iter().any(|s| s == p))
+        .and_then(|p| HeaderValue::from_str(p).ok())
+}
+
+async fn handle_connection<R: Runtime>(
+    app: AppHandle<R>,
+    server_id: String,
+    connection_id: String,
+    stream: TcpStream,
+    peer: SocketAddr,
+    settings: ServerSettings,
+) {
+    let state = app.state::<TcpToolState>();
+    let timeout = SocketOptions::resolve(&*state.config.read().await, None).connection_timeout;
+    let local_addr = stream.local_addr().map(|a| a.to_string()).unwrap_or_default();
+    let mut connection_info =
+        ConnectionInfo::new(&connection_id, "websocket", local_addr, Some(peer.to_string()), "connected");
+    connection_info.server_id = Some(server_id.clone());
+
+    let result = match settings.acceptor {
+        Some(ref acceptor) => match tokio::time::timeout(timeout, acceptor.accept(stream)).await {
+            Ok(Ok(stream)) => {
+                connection_info.tls = Some(tls::server_info(&stream));
+                accept(&app, connection_info, stream, &settings, timeout).await
+            }
+            Ok(Err(e)) => Err(format!("TLS handshake failed: {}", e)),
+            Err(_) => Err("TLS handshake timed out".to_string()),
+        },
+        None => accept(&app, connection_info, stream, &settings, timeout).await,
+    };
+
+    match result {
+        Ok(info) => {
+            emit_event(&app, "tcp_connection_established", &info);
+            info!("WebSocket server {} accepted connection from {}", server_id, peer);
+        }
+        Err(e) => {
+            warn!("WebSocket connection from {} rejected: {}", peer, e);
+            state.tasks.write().await.remove(&connection_id);
+        }
+    }
+}
+
+async fn accept<R, S>(
+    app: &AppHandle<R>,
+    connection_info: ConnectionInfo,
+    stream: S,
+    settings: &ServerSettings,
+    timeout: Duration,
+) -> Result<ConnectionInfo, String>
+where
+    R: Runtime,
+    S: AsyncRead + AsyncWrite + Send + Unpin + 'static,
+{
+    let mut subprotocol = None;
+    // 握手回调的错误类型由 tungstenite 定义
+    #[allow(clippy::result_large_err)]
+    let callback = |request: &Request, mut response: Response| -> Result<Response, ErrorResponse> {
+        if let Some(protocol) = choose_subprotocol(request, &settings.subprotocols) {
+            subprotocol = protocol.to_str().ok().map(|p| p.to_string());
+            response.headers_mut().insert("Sec-WebSocket-Protocol", protocol);
+        }
+        Ok(response)
+    };
+    let ws = tokio::time::timeout(timeout, tokio_tungstenite::accept_hdr_async(stream, callback))
+        .await
+        .map_err(|_| "WebSocket handshake timed out".to_string())?
+        .map_err(|e| format!("WebSocket handshake failed: {}", e))?;
+    Ok(start_session(app, connection_info, ws, subprotocol, settings.echo).await)
+}